@@ -0,0 +1,60 @@
+//! Helpers shared by the builders of the crate.
+
+/// Generates a non-consuming (`&mut self`) wrapper around an owned builder
+/// generated by `derive_builder`.
+///
+/// The owned builders returned by `derive_builder` move `self` on every
+/// setter call, which makes conditional construction awkward. The generated
+/// wrapper exposes the same setters taking `&mut self`, so that a builder can
+/// be configured step by step:
+///
+/// ```text
+/// let mut builder = SpecBuilderMut::default();
+/// if gpu {
+///     builder.hostname("gpu");
+/// }
+/// let spec = builder.build()?;
+/// ```
+#[allow(unused_macros)]
+macro_rules! mutable_builder {
+    (
+        $(#[$meta:meta])*
+        $name:ident($builder:ident) -> $target:ty {
+            $($(#[$field_meta:meta])* $field:ident: $value:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Default)]
+        pub struct $name($builder);
+
+        impl $name {
+            $(
+                $(#[$field_meta])*
+                #[doc = concat!("Sets the `", stringify!($field), "` field.")]
+                pub fn $field<VALUE: Into<$value>>(&mut self, value: VALUE) -> &mut Self {
+                    self.0 = std::mem::take(&mut self.0).$field(value);
+                    self
+                }
+            )*
+
+            #[doc = concat!("Builds a new [`", stringify!($target), "`] from the current state.")]
+            /// # Errors
+            /// If a required field has not been initialized.
+            pub fn build(&self) -> $crate::error::Result<$target> {
+                self.0.clone().build()
+            }
+        }
+
+        impl From<$builder> for $name {
+            fn from(builder: $builder) -> Self {
+                Self(builder)
+            }
+        }
+
+        impl From<$name> for $builder {
+            fn from(builder: $name) -> Self {
+                builder.0
+            }
+        }
+    };
+}
@@ -1,5 +1,6 @@
 //! Functionality corresponding to <https://github.com/opencontainers/image-spec/blob/main/descriptor.md#digests>.
 
+use std::cmp::Ordering;
 use std::fmt::Display;
 use std::str::FromStr;
 
@@ -107,6 +108,19 @@ pub struct Digest {
     split: usize,
 }
 
+/// Digests are ordered by their string representation.
+impl Ord for Digest {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl PartialOrd for Digest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl AsRef<str> for Digest {
     fn as_ref(&self) -> &str {
         &self.value
@@ -139,6 +153,20 @@ impl serde::ser::Serialize for Digest {
     }
 }
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Digest {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Digest".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "pattern": "^[a-z0-9]+(?:[+._-][a-z0-9]+)*:[a-zA-Z0-9=_-]+$",
+        })
+    }
+}
+
 impl Digest {
     const ALGORITHM_SEPARATOR: &'static [char] = &['+', '.', '_', '-'];
     /// The algorithm name (e.g. sha256, sha512)
@@ -230,7 +258,7 @@ impl TryFrom<&str> for Digest {
 }
 
 /// A SHA-256 digest, guaranteed to be 64 lowercase hexadecimal ASCII characters.
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct Sha256Digest {
     digest: Box<str>,
 }
@@ -282,6 +310,20 @@ impl Sha256Digest {
     }
 }
 
+#[cfg(feature = "proptests")]
+use quickcheck::{Arbitrary, Gen};
+
+#[cfg(feature = "proptests")]
+impl Arbitrary for Digest {
+    fn arbitrary(g: &mut Gen) -> Digest {
+        let (algorithm, len) = g.choose(&[("sha256", 64), ("sha512", 128)]).unwrap();
+        let hex: String = (0..*len)
+            .map(|_| *g.choose(b"0123456789abcdef").unwrap() as char)
+            .collect();
+        Digest::from_str(&format!("{algorithm}:{hex}")).unwrap()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -339,7 +381,7 @@ mod tests {
         let d = Digest::from_str(VALID_DIGEST_SHA256).unwrap();
         assert_eq!(d.algorithm(), &DigestAlgorithm::Sha256);
         assert_eq!(d.digest(), expected_value);
-        let base_digest = Digest::from(d.clone());
+        let base_digest = d.clone();
         assert_eq!(base_digest.digest(), expected_value);
     }
 
@@ -351,7 +393,7 @@ mod tests {
         assert_eq!(d.digest(), expected_value);
         // Verify we can cheaply coerce to a string
         assert_eq!(d.as_ref(), VALID_DIGEST_SHA384);
-        let base_digest = Digest::from(d.clone());
+        let base_digest = d.clone();
         assert_eq!(base_digest.digest(), expected_value);
     }
 
@@ -361,7 +403,7 @@ mod tests {
         let d = Digest::from_str(VALID_DIGEST_SHA512).unwrap();
         assert_eq!(d.algorithm(), &DigestAlgorithm::Sha512);
         assert_eq!(d.digest(), expected_value);
-        let base_digest = Digest::from(d.clone());
+        let base_digest = d.clone();
         assert_eq!(base_digest.digest(), expected_value);
     }
 
@@ -371,4 +413,15 @@ mod tests {
         let v = Sha256Digest::from_str(digest).unwrap();
         assert_eq!(v.digest(), digest);
     }
+
+    #[test]
+    fn digest_ordering() {
+        let digest = |s: &str| Digest::from_str(s).unwrap();
+        let a = digest("sha256:0000000000000000000000000000000000000000000000000000000000000000");
+        let b = digest("sha256:1000000000000000000000000000000000000000000000000000000000000000");
+        let c = digest("sha512:00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000");
+        let mut digests = vec![c.clone(), b.clone(), a.clone()];
+        digests.sort();
+        assert_eq!(digests, [a, b, c]);
+    }
 }
@@ -0,0 +1,318 @@
+//! Token authentication of the distribution API.
+//!
+//! Registries requiring authentication answer requests with a
+//! `WWW-Authenticate` challenge. A client requests a token for the given
+//! scope from the realm of the challenge and sends it with further requests.
+//!
+//! ```
+//! use oci_spec::distribution::auth::{BearerChallenge, Scope};
+//!
+//! let challenge: BearerChallenge = r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/busybox:pull""#
+//!     .parse()
+//!     .unwrap();
+//!
+//! assert_eq!(challenge.realm(), "https://auth.docker.io/token");
+//! assert_eq!(challenge.service().as_deref(), Some("registry.docker.io"));
+//! assert_eq!(challenge.scope().as_deref(), Some("repository:library/busybox:pull"));
+//! assert_eq!(
+//!     Scope::repository("library/busybox", ["pull", "push"]).to_string(),
+//!     "repository:library/busybox:pull,push"
+//! );
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use derive_builder::Builder;
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{oci_error, OciSpecError};
+
+/// A `Bearer` challenge of a `WWW-Authenticate` header.
+#[derive(Builder, Clone, Debug, Eq, Getters, PartialEq)]
+#[builder(
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+#[getset(get = "pub")]
+pub struct BearerChallenge {
+    /// The URL of the token server.
+    realm: String,
+
+    /// The name of the service the token is requested for.
+    #[builder(default)]
+    service: Option<String>,
+
+    /// The scope the token needs to grant, possibly multiple space separated
+    /// [Scope]s.
+    #[builder(default)]
+    scope: Option<String>,
+
+    /// The error of the previous request, e.g. `insufficient_scope`.
+    #[builder(default)]
+    error: Option<String>,
+}
+
+impl BearerChallenge {
+    /// Returns the parsed [Scope]s of the challenge. Malformed scopes are
+    /// skipped.
+    pub fn scopes(&self) -> Vec<Scope> {
+        self.scope
+            .iter()
+            .flat_map(|s| s.split_whitespace())
+            .filter_map(|s| s.parse().ok())
+            .collect()
+    }
+
+    /// Returns the query parameters of the token request.
+    pub fn token_query(&self) -> Vec<(String, String)> {
+        let mut query = Vec::new();
+        if let Some(service) = &self.service {
+            query.push(("service".to_owned(), service.clone()));
+        }
+        for scope in self.scope.iter().flat_map(|s| s.split_whitespace()) {
+            query.push(("scope".to_owned(), scope.to_owned()));
+        }
+        query
+    }
+}
+
+impl FromStr for BearerChallenge {
+    type Err = OciSpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (scheme, params) = s.split_once(' ').unwrap_or((s, ""));
+        if !scheme.eq_ignore_ascii_case("bearer") {
+            return Err(oci_error(format!("unsupported auth scheme: {scheme}")));
+        }
+
+        let mut builder = BearerChallengeBuilder::default();
+        for (key, value) in parse_auth_params(params)? {
+            builder = match key.to_ascii_lowercase().as_str() {
+                "realm" => builder.realm(value),
+                "service" => builder.service(value),
+                "scope" => builder.scope(value),
+                "error" => builder.error(value),
+                _ => builder,
+            };
+        }
+        builder
+            .build()
+            .map_err(|_| oci_error("bearer challenge without realm"))
+    }
+}
+
+/// Parses the comma separated `key=value` pairs of a challenge, where values
+/// may be quoted strings containing commas and escaped characters.
+fn parse_auth_params(s: &str) -> Result<Vec<(String, String)>, OciSpecError> {
+    let mut params = Vec::new();
+    let mut chars = s.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        let key: String = std::iter::from_fn(|| chars.next_if(|c| *c != '=')).collect();
+        if key.is_empty() {
+            break;
+        }
+        if chars.next() != Some('=') {
+            return Err(oci_error(format!("missing value of auth param {key}")));
+        }
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => value.extend(chars.next()),
+                    Some(c) => value.push(c),
+                    None => return Err(oci_error("unterminated quoted string")),
+                }
+            }
+        } else {
+            value.extend(std::iter::from_fn(|| chars.next_if(|c| *c != ',')));
+        }
+        params.push((key.trim().to_owned(), value.trim().to_owned()));
+    }
+    Ok(params)
+}
+
+/// The access requested for a resource, in the form
+/// `<type>:<name>:<action>[,<action>...]`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Scope {
+    /// The type of the resource, e.g. `repository`.
+    pub resource_type: String,
+    /// The name of the resource, e.g. `library/busybox`.
+    pub name: String,
+    /// The requested actions, e.g. `pull` and `push`.
+    pub actions: Vec<String>,
+}
+
+impl Scope {
+    /// Creates the scope for `actions` on the repository `name`.
+    pub fn repository<I, A>(name: impl Into<String>, actions: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<String>,
+    {
+        Self {
+            resource_type: "repository".to_owned(),
+            name: name.into(),
+            actions: actions.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.resource_type,
+            self.name,
+            self.actions.join(",")
+        )
+    }
+}
+
+impl FromStr for Scope {
+    type Err = OciSpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The name may contain a port, so split off the type and actions.
+        let (resource_type, rest) = s
+            .split_once(':')
+            .ok_or_else(|| oci_error(format!("invalid scope: {s}")))?;
+        let (name, actions) = rest
+            .rsplit_once(':')
+            .ok_or_else(|| oci_error(format!("invalid scope: {s}")))?;
+        if resource_type.is_empty() || name.is_empty() {
+            return Err(oci_error(format!("invalid scope: {s}")));
+        }
+        Ok(Self {
+            resource_type: resource_type.to_owned(),
+            name: name.to_owned(),
+            actions: actions
+                .split(',')
+                .filter(|a| !a.is_empty())
+                .map(str::to_owned)
+                .collect(),
+        })
+    }
+}
+
+#[derive(Builder, Clone, Debug, Default, Deserialize, Eq, Getters, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[builder(
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+#[getset(get = "pub")]
+/// The response of a token server.
+pub struct TokenResponse {
+    /// The bearer token to authenticate requests with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    token: Option<String>,
+
+    /// An alias of `token` for compatibility with OAuth 2.0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    access_token: Option<String>,
+
+    /// The lifetime of the token in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    expires_in: Option<u64>,
+
+    /// The RFC 3339 time at which the token was issued.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    issued_at: Option<String>,
+
+    /// A token to obtain further tokens without credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    refresh_token: Option<String>,
+}
+
+impl TokenResponse {
+    /// The lifetime of a token if the response does not specify one.
+    pub const DEFAULT_EXPIRES_IN: u64 = 60;
+
+    /// Returns the bearer token, preferring `token` over `access_token`.
+    pub fn bearer_token(&self) -> Option<&str> {
+        self.token.as_deref().or(self.access_token.as_deref())
+    }
+
+    /// Returns the lifetime of the token in seconds, which defaults to 60.
+    pub fn lifetime(&self) -> u64 {
+        self.expires_in.unwrap_or(Self::DEFAULT_EXPIRES_IN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Result;
+
+    #[test]
+    fn parse_challenge() -> Result<()> {
+        let challenge: BearerChallenge = r#"Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:a/b:pull,push repository:c:pull",error="insufficient_scope""#.parse()?;
+        assert_eq!(challenge.realm(), "https://ghcr.io/token");
+        assert_eq!(challenge.error().as_deref(), Some("insufficient_scope"));
+        assert_eq!(
+            challenge.scopes(),
+            vec![
+                Scope::repository("a/b", ["pull", "push"]),
+                Scope::repository("c", ["pull"])
+            ]
+        );
+        assert_eq!(
+            challenge.token_query(),
+            vec![
+                ("service".to_owned(), "ghcr.io".to_owned()),
+                ("scope".to_owned(), "repository:a/b:pull,push".to_owned()),
+                ("scope".to_owned(), "repository:c:pull".to_owned()),
+            ]
+        );
+
+        let challenge: BearerChallenge = "bearer realm=https://example.com/token".parse()?;
+        assert_eq!(challenge.realm(), "https://example.com/token");
+        assert!(challenge.service().is_none());
+
+        assert!(r#"Basic realm="x""#.parse::<BearerChallenge>().is_err());
+        assert!(r#"Bearer service="x""#.parse::<BearerChallenge>().is_err());
+        assert!(r#"Bearer realm="x"#.parse::<BearerChallenge>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn scope() -> Result<()> {
+        let scope: Scope = "repository:localhost:5000/foo:pull".parse()?;
+        assert_eq!(scope.name, "localhost:5000/foo");
+        assert_eq!(scope.actions, ["pull"]);
+        assert_eq!(scope.to_string(), "repository:localhost:5000/foo:pull");
+        assert!("repository".parse::<Scope>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn token_response() -> Result<()> {
+        let response: TokenResponse =
+            serde_json::from_str(r#"{"access_token":"abc","issued_at":"2024-01-01T00:00:00Z"}"#)?;
+        assert_eq!(response.bearer_token(), Some("abc"));
+        assert_eq!(response.lifetime(), 60);
+
+        let response = TokenResponseBuilder::default()
+            .token("def")
+            .expires_in(300u64)
+            .build()?;
+        assert_eq!(response.bearer_token(), Some("def"));
+        assert_eq!(response.lifetime(), 300);
+        Ok(())
+    }
+}
@@ -4,7 +4,11 @@ use crate::error::OciSpecError;
 use derive_builder::Builder;
 use getset::Getters;
 use serde::{Deserialize, Serialize};
-use std::fmt::{self, Display, Formatter};
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+    hash::{Hash, Hasher},
+};
 use strum_macros::{Display as StrumDisplay, EnumString};
 use thiserror::Error;
 
@@ -12,9 +16,13 @@ use thiserror::Error;
 pub const ERR_REGISTRY: &str = "distribution: registry returned error";
 
 /// Unique identifier representing error code.
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString)]
+///
+/// Codes defined by the specification are always parsed into their own
+/// variant. A known code wrapped in [ErrorCode::Other] nevertheless compares
+/// equal to, and behaves like, its variant.
+#[derive(Clone, Debug, StrumDisplay, EnumString)]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[non_exhaustive]
 pub enum ErrorCode {
     /// Blob unknown to registry.
     BlobUnknown,
@@ -43,11 +51,122 @@ pub enum ErrorCode {
     /// The operation is unsupported.
     Unsupported,
     /// Too many requests.
-    #[serde(rename = "TOOMANYREQUESTS")]
+    #[strum(to_string = "TOOMANYREQUESTS", serialize = "TOO_MANY_REQUESTS")]
     TooManyRequests,
+    /// A code not defined by the specification, e.g. a registry specific
+    /// extension. The original code is retained.
+    #[strum(default, to_string = "{0}")]
+    Other(String),
+}
+
+impl From<String> for ErrorCode {
+    fn from(code: String) -> Self {
+        code.parse().unwrap_or(Self::Other(code))
+    }
+}
+
+impl PartialEq for ErrorCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized().to_string() == other.normalized().to_string()
+    }
+}
+
+impl Eq for ErrorCode {}
+
+impl Hash for ErrorCode {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.normalized().to_string().hash(state);
+    }
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorCode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(String::deserialize(deserializer)?.into())
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for ErrorCode {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "ErrorCode".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "pattern": "^[A-Z_]+$",
+        })
+    }
+}
+
+impl ErrorCode {
+    /// Returns the code with a known code held by [ErrorCode::Other] replaced
+    /// by its variant.
+    fn normalized(&self) -> Cow<'_, Self> {
+        match self {
+            Self::Other(code) => match code.parse() {
+                Ok(Self::Other(_)) | Err(_) => Cow::Borrowed(self),
+                Ok(known) => Cow::Owned(known),
+            },
+            known => Cow::Borrowed(known),
+        }
+    }
+
+    /// Returns the HTTP status code a registry responds with for this error.
+    /// Codes unknown to the specification map to 500.
+    pub fn status_code(&self) -> u16 {
+        match &*self.normalized() {
+            Self::BlobUploadInvalid
+            | Self::DigestInvalid
+            | Self::ManifestInvalid
+            | Self::NameInvalid
+            | Self::SizeInvalid => 400,
+            Self::Unauthorized => 401,
+            Self::Denied => 403,
+            Self::BlobUnknown
+            | Self::BlobUploadUnknown
+            | Self::ManifestBlobUnknown
+            | Self::ManifestUnknown
+            | Self::NameUnknown => 404,
+            Self::Unsupported => 405,
+            Self::TooManyRequests => 429,
+            Self::Other(_) => 500,
+        }
+    }
+
+    /// Returns the description of the error as defined by the specification,
+    /// or `None` for codes unknown to it.
+    pub fn description(&self) -> Option<&'static str> {
+        Some(match &*self.normalized() {
+            Self::BlobUnknown => "blob unknown to registry",
+            Self::BlobUploadInvalid => "blob upload invalid",
+            Self::BlobUploadUnknown => "blob upload unknown to registry",
+            Self::DigestInvalid => "provided digest did not match uploaded content",
+            Self::ManifestBlobUnknown => {
+                "manifest references a manifest or blob unknown to registry"
+            }
+            Self::ManifestInvalid => "manifest invalid",
+            Self::ManifestUnknown => "manifest unknown to registry",
+            Self::NameInvalid => "invalid repository name",
+            Self::NameUnknown => "repository name not known to registry",
+            Self::SizeInvalid => "provided length did not match content length",
+            Self::Unauthorized => "authentication required",
+            Self::Denied => "requested access to the resource is denied",
+            Self::Unsupported => "the operation is unsupported",
+            Self::TooManyRequests => "too many requests",
+            Self::Other(_) => return None,
+        })
+    }
 }
 
 #[derive(Builder, Clone, Debug, Deserialize, Eq, Error, Getters, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     pattern = "owned",
     setter(into, strip_option),
@@ -67,13 +186,55 @@ impl Display for ErrorResponse {
 }
 
 impl ErrorResponse {
+    /// The content type of a serialized ErrorResponse.
+    pub const CONTENT_TYPE: &'static str = "application/json";
+
+    /// Creates a response containing a single error.
+    pub fn single(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            errors: vec![ErrorInfo {
+                code,
+                message: Some(message.into()),
+                detail: None,
+            }],
+        }
+    }
+
+    /// Creates a response containing a single error, using the description
+    /// of the code as message.
+    pub fn from_code(code: ErrorCode) -> Self {
+        let message = code.description().unwrap_or_default();
+        Self::single(code, message)
+    }
+
     /// Returns the ErrorInfo slice for the response.
     pub fn detail(&self) -> &[ErrorInfo] {
         &self.errors
     }
+
+    /// Returns the HTTP status code of the response, which is the one of
+    /// its first error, or 500 if it contains no errors.
+    pub fn status_code(&self) -> u16 {
+        self.errors
+            .first()
+            .map(|e| e.code.status_code())
+            .unwrap_or(500)
+    }
+
+    /// Returns the serialized JSON body of the response.
+    pub fn body(&self) -> Result<Vec<u8>, OciSpecError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Returns the HTTP status code together with the serialized body, ready
+    /// to be sent by a registry.
+    pub fn with_status(&self) -> Result<(u16, Vec<u8>), OciSpecError> {
+        Ok((self.status_code(), self.body()?))
+    }
 }
 
 #[derive(Builder, Clone, Debug, Deserialize, Eq, Getters, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     pattern = "owned",
     setter(into, strip_option),
@@ -93,6 +254,7 @@ pub struct ErrorInfo {
     message: Option<String>,
 
     #[serde(default, skip_serializing_if = "Option::is_none", with = "json_string")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<serde_json::Value>"))]
     #[builder(default = "None")]
     /// The detail field is OPTIONAL and MAY contain arbitrary JSON data providing information
     /// the client can use to resolve the issue.
@@ -152,6 +314,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn error_response_single() -> Result<()> {
+        let response = ErrorResponse::single(ErrorCode::ManifestUnknown, "manifest unknown");
+        let (status, body) = response.with_status()?;
+        assert_eq!(status, 404);
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            r#"{"errors":[{"code":"MANIFEST_UNKNOWN","message":"manifest unknown"}]}"#
+        );
+
+        let response = ErrorResponseBuilder::default().errors(vec![]).build()?;
+        assert_eq!(response.status_code(), 500);
+        Ok(())
+    }
+
+    #[test]
+    fn error_code_description() {
+        let response = ErrorResponse::from_code(ErrorCode::Denied);
+        assert_eq!(response.status_code(), 403);
+        assert_eq!(
+            response.detail()[0].message().as_deref(),
+            Some("requested access to the resource is denied")
+        );
+        assert_eq!(ErrorCode::Other("FOO".to_string()).description(), None);
+    }
+
     #[test]
     fn error_response_failure() {
         assert!(ErrorResponseBuilder::default().build().is_err());
@@ -195,6 +383,43 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn error_code_other() -> Result<()> {
+        let info: ErrorInfo = serde_json::from_str(r#"{"code":"QUOTA_EXCEEDED"}"#)?;
+        assert_eq!(info.code(), &ErrorCode::Other("QUOTA_EXCEEDED".to_string()));
+        assert_eq!(
+            serde_json::to_string(&info)?,
+            r#"{"code":"QUOTA_EXCEEDED"}"#
+        );
+
+        let known = ErrorCode::Other("BLOB_UNKNOWN".to_string());
+        assert_eq!(known, ErrorCode::BlobUnknown);
+        assert_eq!(known.status_code(), 404);
+        assert_eq!(known.description(), Some("blob unknown to registry"));
+        assert!(matches!(
+            "BLOB_UNKNOWN".parse::<ErrorCode>(),
+            Ok(ErrorCode::BlobUnknown)
+        ));
+        assert!(matches!(
+            ErrorCode::from("TOO_MANY_REQUESTS".to_string()),
+            ErrorCode::TooManyRequests
+        ));
+        assert_ne!(known, ErrorCode::Other("QUOTA_EXCEEDED".to_string()));
+        assert_eq!(
+            std::collections::HashSet::from([known, ErrorCode::BlobUnknown]).len(),
+            1
+        );
+
+        let code: ErrorCode = serde_json::from_str(r#""TOOMANYREQUESTS""#)?;
+        assert_eq!(code, ErrorCode::TooManyRequests);
+        assert_eq!(code.to_string(), "TOOMANYREQUESTS");
+        assert_eq!(
+            serde_json::to_string(&ErrorCode::BlobUnknown)?,
+            r#""BLOB_UNKNOWN""#
+        );
+        Ok(())
+    }
+
     #[test]
     fn error_info_deserialize_success() -> Result<()> {
         let error_info_str = r#"
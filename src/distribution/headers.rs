@@ -0,0 +1,260 @@
+//! Names of the HTTP headers used by the distribution API and types for
+//! their values.
+//!
+//! Header names are case-insensitive, the constants use the casing of the
+//! distribution spec. Digests in `Docker-Content-Digest` and `OCI-Subject`
+//! are parsed with [`Digest::from_str`](std::str::FromStr::from_str), the
+//! value of `OCI-Filters-Applied` with
+//! [`FiltersApplied`](super::FiltersApplied).
+//!
+//! ```
+//! use oci_spec::distribution::headers::{self, ContentRange};
+//!
+//! let range: ContentRange = "0-1023".parse().unwrap();
+//! assert_eq!(range.len(), 1024);
+//! assert_eq!(range.next(512).to_string(), "1024-1535");
+//! assert_eq!(headers::CONTENT_RANGE, "Content-Range");
+//! ```
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use super::parse_range;
+use crate::error::{oci_error, OciSpecError, Result};
+
+/// The digest of the content of a manifest or blob response.
+pub const DOCKER_CONTENT_DIGEST: &str = "Docker-Content-Digest";
+/// The digest of the subject of a pushed manifest, returned if the registry
+/// processed the subject field.
+pub const OCI_SUBJECT: &str = "OCI-Subject";
+/// The filters applied by the registry to a referrers response.
+pub const OCI_FILTERS_APPLIED: &str = "OCI-Filters-Applied";
+/// The UUID of a blob upload session.
+pub const DOCKER_UPLOAD_UUID: &str = "Docker-Upload-UUID";
+/// The API version supported by the registry, e.g. `registry/2.0`.
+pub const DOCKER_DISTRIBUTION_API_VERSION: &str = "Docker-Distribution-API-Version";
+/// The range of bytes of a chunk sent in a `PATCH` request of an upload.
+pub const CONTENT_RANGE: &str = "Content-Range";
+/// The range of bytes a registry received for a blob upload so far.
+pub const RANGE: &str = "Range";
+/// The URL of an upload session or a pushed blob or manifest.
+pub const LOCATION: &str = "Location";
+/// The link to the next page of a tag or catalog listing.
+pub const LINK: &str = "Link";
+/// The authentication challenge of a `401 Unauthorized` response.
+pub const WWW_AUTHENTICATE: &str = "WWW-Authenticate";
+/// Warnings about the response, like deprecation notices, see
+/// [RegistryWarning].
+pub const WARNING: &str = "Warning";
+
+/// An inclusive range of bytes, as used in the `Content-Range` and `Range`
+/// headers of blob uploads, e.g. `0-1023`. Ranges are never empty.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ContentRange {
+    start: u64,
+    end: u64,
+}
+
+impl ContentRange {
+    /// Creates the range from `start` to `end`, both inclusive.
+    pub fn new(start: u64, end: u64) -> Result<Self> {
+        match start <= end {
+            true => Ok(Self { start, end }),
+            false => Err(oci_error(format!("invalid range: {start}-{end}"))),
+        }
+    }
+
+    /// Creates the range of `len` bytes starting at `offset`. An empty
+    /// length is treated as one byte.
+    pub fn at(offset: u64, len: u64) -> Self {
+        Self {
+            start: offset,
+            end: offset + len.max(1) - 1,
+        }
+    }
+
+    /// Returns the first byte of the range.
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the last byte of the range.
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    /// Returns the number of bytes in the range.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// Returns the range of `len` bytes following this one.
+    pub fn next(&self, len: u64) -> Self {
+        Self::at(self.end + 1, len)
+    }
+}
+
+/// Parses `<start>-<end>`, with an optional `bytes=` prefix.
+impl FromStr for ContentRange {
+    type Err = OciSpecError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (start, end) = parse_range(s)?;
+        Ok(Self { start, end })
+    }
+}
+
+impl Display for ContentRange {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// A warning of an [RFC 7234](https://datatracker.ietf.org/doc/html/rfc7234#section-5.5)
+/// `Warning` header. Registries use the code `299` with the agent `-` to
+/// return deprecation notices and similar messages meant for the user.
+///
+/// ```
+/// use oci_spec::distribution::headers::{parse_warning_header, RegistryWarning};
+///
+/// let warnings = parse_warning_header(r#"299 - "this image is deprecated, use \"foo\"""#);
+/// assert_eq!(
+///     warnings,
+///     vec![RegistryWarning {
+///         code: 299,
+///         agent: "-".to_string(),
+///         text: r#"this image is deprecated, use "foo""#.to_string(),
+///     }]
+/// );
+/// assert_eq!(warnings[0].to_string(), r#"299 - "this image is deprecated, use \"foo\"""#);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RegistryWarning {
+    /// The three digit warning code.
+    pub code: u16,
+    /// The host name of the agent adding the warning, or `-` if unknown.
+    pub agent: String,
+    /// The unquoted warning text.
+    pub text: String,
+}
+
+impl Display for RegistryWarning {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let text = self.text.replace('\\', "\\\\").replace('"', "\\\"");
+        write!(f, "{:03} {} \"{text}\"", self.code, self.agent)
+    }
+}
+
+/// Parses the value of a `Warning` header, which may contain several
+/// comma separated warnings. Malformed warnings are skipped, as is the
+/// optional date of a warning.
+pub fn parse_warning_header(value: &str) -> Vec<RegistryWarning> {
+    let mut warnings = Vec::new();
+    let mut rest = value;
+    loop {
+        rest = rest.trim_start_matches([' ', '\t', ',']);
+        if rest.is_empty() {
+            break;
+        }
+        match parse_warning(rest) {
+            Some((warning, remainder)) => {
+                warnings.push(warning);
+                rest = remainder;
+            }
+            // Skip to the next warning.
+            None => match rest.find(',') {
+                Some(i) => rest = &rest[i + 1..],
+                None => break,
+            },
+        }
+    }
+    warnings
+}
+
+/// Parses a single warning from the start of `s`, returning it and the
+/// remainder after its optional date.
+fn parse_warning(s: &str) -> Option<(RegistryWarning, &str)> {
+    let (code, rest) = s.split_once(' ')?;
+    if code.len() != 3 || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let code = code.parse().ok()?;
+    let (agent, rest) = rest.trim_start().split_once(' ')?;
+    let (text, rest) = parse_quoted(rest.trim_start())?;
+    let rest = match parse_quoted(rest.trim_start()) {
+        Some((_date, rest)) => rest,
+        None => rest,
+    };
+    let warning = RegistryWarning {
+        code,
+        agent: agent.to_owned(),
+        text,
+    };
+    Some((warning, rest))
+}
+
+/// Parses a quoted string from the start of `s`, returning the unescaped
+/// content and the remainder after the closing quote.
+fn parse_quoted(s: &str) -> Option<(String, &str)> {
+    let mut chars = s.strip_prefix('"')?.char_indices();
+    let mut text = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((text, &s[i + 2..])),
+            '\\' => text.push(chars.next()?.1),
+            c => text.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_range() -> Result<()> {
+        let range = ContentRange::from_str("bytes=100-149")?;
+        assert_eq!((range.start(), range.end(), range.len()), (100, 149, 50));
+        assert_eq!(range, ContentRange::new(100, 149)?);
+        assert_eq!(range, ContentRange::at(100, 50));
+        assert_eq!(ContentRange::at(7, 0).to_string(), "7-7");
+        assert_eq!(range.next(10).to_string(), "150-159");
+        assert!(ContentRange::new(2, 1).is_err());
+        assert!(ContentRange::from_str("1-").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn warning_header() {
+        let warnings = parse_warning_header(
+            r#"299 - "first, with comma" "Sat, 25 Aug 2012 23:34:45 GMT", 199 registry.example.com "second",bad, 300 - "third""#,
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                RegistryWarning {
+                    code: 299,
+                    agent: "-".to_owned(),
+                    text: "first, with comma".to_owned(),
+                },
+                RegistryWarning {
+                    code: 199,
+                    agent: "registry.example.com".to_owned(),
+                    text: "second".to_owned(),
+                },
+                RegistryWarning {
+                    code: 300,
+                    agent: "-".to_owned(),
+                    text: "third".to_owned(),
+                },
+            ]
+        );
+        assert!(parse_warning_header("").is_empty());
+        assert!(parse_warning_header(r#"2999 - "too long code""#).is_empty());
+        assert!(parse_warning_header(r#"299 - "unterminated"#).is_empty());
+    }
+}
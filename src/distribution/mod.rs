@@ -11,14 +11,26 @@
 //! To support other artifact types, please see the [Open Container Initiative Artifact Authors
 //! Guide](https://github.com/opencontainers/artifacts) (a.k.a. "OCI Artifacts").
 
+pub mod auth;
 mod error;
+pub mod headers;
+mod pagination;
 mod reference;
+mod referrers;
+pub mod regexp;
 mod repository;
+pub mod routes;
+mod store;
 mod tag;
+mod upload;
 mod version;
 
 pub use error::*;
+pub use pagination::*;
 pub use reference::*;
+pub use referrers::*;
 pub use repository::*;
+pub use store::*;
 pub use tag::*;
+pub use upload::*;
 pub use version::*;
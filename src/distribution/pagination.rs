@@ -0,0 +1,283 @@
+//! Pagination of the tag and catalog listing endpoints.
+
+use super::{RepositoryList, TagList};
+
+/// The `n` and `last` query parameters used to page through tag and catalog
+/// listings.
+///
+/// ```
+/// use oci_spec::distribution::Pagination;
+///
+/// let header = r#"</v2/_catalog?last=library%2Fbusybox&n=100>; rel="next""#;
+/// let page = Pagination::from_link_header(header).unwrap();
+/// assert_eq!(page.n, Some(100));
+/// assert_eq!(page.last.as_deref(), Some("library/busybox"));
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Pagination {
+    /// The maximum number of results to return.
+    pub n: Option<usize>,
+    /// The last result of the previous page; results start after it.
+    pub last: Option<String>,
+}
+
+impl Pagination {
+    /// Creates the pagination for the first page with at most `n` results.
+    pub fn first(n: usize) -> Self {
+        Self {
+            n: Some(n),
+            last: None,
+        }
+    }
+
+    /// Returns the unescaped query parameters.
+    pub fn query(&self) -> Vec<(String, String)> {
+        let mut query = Vec::new();
+        if let Some(n) = self.n {
+            query.push(("n".to_owned(), n.to_string()));
+        }
+        if let Some(last) = &self.last {
+            query.push(("last".to_owned(), last.clone()));
+        }
+        query
+    }
+
+    /// Parses the `n` and `last` parameters from an escaped query string.
+    /// Other parameters are ignored.
+    pub fn from_query(query: &str) -> Self {
+        let mut page = Self::default();
+        for (key, value) in query
+            .trim_start_matches('?')
+            .split('&')
+            .filter_map(|p| p.split_once('='))
+        {
+            match key {
+                "n" => page.n = unescape(value).parse().ok(),
+                "last" => page.last = Some(unescape(value)),
+                _ => {}
+            }
+        }
+        page
+    }
+
+    /// Returns the pagination of the next page announced by the value of a
+    /// `Link` header, or `None` if there is no further page.
+    pub fn from_link_header(value: &str) -> Option<Self> {
+        let next = parse_link_header(value)
+            .into_iter()
+            .find(|link| link.has_rel("next"))?;
+        let query = next.target.split_once('?').map(|(_, q)| q).unwrap_or("");
+        Some(Self::from_query(query))
+    }
+}
+
+/// A link of an [RFC 5988](https://datatracker.ietf.org/doc/html/rfc5988)
+/// `Link` header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Link {
+    /// The target URI reference.
+    pub target: String,
+    /// The relation types of the link.
+    pub rel: Vec<String>,
+}
+
+impl Link {
+    /// Returns true if the link has the relation type `rel`.
+    pub fn has_rel(&self, rel: &str) -> bool {
+        self.rel.iter().any(|r| r.eq_ignore_ascii_case(rel))
+    }
+}
+
+/// Parses the value of a `Link` header. Malformed links are skipped.
+pub fn parse_link_header(value: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut rest = value.trim();
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let target = rest[start + 1..start + end].to_owned();
+        rest = &rest[start + end + 1..];
+
+        let params_end = rest.find('<').unwrap_or(rest.len());
+        let rel = rest[..params_end]
+            .split([';', ','])
+            .filter_map(|param| param.split_once('='))
+            .filter(|(key, _)| key.trim().eq_ignore_ascii_case("rel"))
+            .flat_map(|(_, value)| {
+                value
+                    .trim()
+                    .trim_matches('"')
+                    .split_whitespace()
+                    .map(str::to_owned)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        rest = &rest[params_end..];
+        links.push(Link { target, rel });
+    }
+    links
+}
+
+impl TagList {
+    /// Returns the pagination of the page following this one if it was
+    /// requested with at most `n` results, or `None` if this is the last page.
+    ///
+    /// The continuation announced by the registry is preferred, if it was
+    /// set from the `Link` header via [`TagList::set_next_from_link_header`].
+    pub fn next_page(&self, n: usize) -> Option<Pagination> {
+        self.next().clone().or_else(|| next_page(self.tags(), n))
+    }
+
+    /// Sets the continuation of the listing from the value of the `Link`
+    /// header of the response.
+    pub fn set_next_from_link_header(&mut self, value: &str) -> &mut Self {
+        self.set_next(Pagination::from_link_header(value))
+    }
+}
+
+impl RepositoryList {
+    /// Returns the pagination of the page following this one if it was
+    /// requested with at most `n` results, or `None` if this is the last page.
+    ///
+    /// The continuation announced by the registry is preferred, if it was
+    /// set from the `Link` header via
+    /// [`RepositoryList::set_next_from_link_header`].
+    pub fn next_page(&self, n: usize) -> Option<Pagination> {
+        self.next()
+            .clone()
+            .or_else(|| next_page(self.repositories(), n))
+    }
+
+    /// Sets the continuation of the catalog from the value of the `Link`
+    /// header of the response.
+    pub fn set_next_from_link_header(&mut self, value: &str) -> &mut Self {
+        self.set_next(Pagination::from_link_header(value))
+    }
+}
+
+fn next_page(results: &[String], n: usize) -> Option<Pagination> {
+    match results.last() {
+        Some(last) if results.len() >= n => Some(Pagination {
+            n: Some(n),
+            last: Some(last.clone()),
+        }),
+        _ => None,
+    }
+}
+
+fn unescape(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(b) => {
+                        out.push(b);
+                        i += 3;
+                        continue;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b'+' => out.push(b' '),
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distribution::{RepositoryListBuilder, TagListBuilder};
+    use crate::error::Result;
+
+    #[test]
+    fn link_header() {
+        let links = parse_link_header(
+            r#"</v2/busybox/tags/list?n=2&last=b>; rel="next", <https://example.com/prev>; rel="prev start""#,
+        );
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].target, "/v2/busybox/tags/list?n=2&last=b");
+        assert!(links[0].has_rel("next"));
+        assert!(links[1].has_rel("prev"));
+        assert!(links[1].has_rel("start"));
+
+        assert_eq!(
+            Pagination::from_link_header(r#"</v2/_catalog?n=2&last=a%2Fb>; rel="next""#),
+            Some(Pagination {
+                n: Some(2),
+                last: Some("a/b".to_owned())
+            })
+        );
+        assert_eq!(
+            Pagination::from_link_header(r#"</v2/_catalog>; rel="prev""#),
+            None
+        );
+        assert!(parse_link_header("garbage").is_empty());
+    }
+
+    #[test]
+    fn query() {
+        let page = Pagination {
+            n: Some(10),
+            last: Some("1.0".to_owned()),
+        };
+        assert_eq!(
+            page.query(),
+            vec![
+                ("n".to_owned(), "10".to_owned()),
+                ("last".to_owned(), "1.0".to_owned())
+            ]
+        );
+        assert_eq!(Pagination::from_query("?n=10&last=1.0&foo=bar"), page);
+        assert_eq!(Pagination::first(5).query().len(), 1);
+    }
+
+    #[test]
+    fn next_page() -> Result<()> {
+        let tags = TagListBuilder::default()
+            .name("busybox")
+            .tags(vec!["a".to_owned(), "b".to_owned()])
+            .build()?;
+        assert_eq!(
+            tags.next_page(2),
+            Some(Pagination {
+                n: Some(2),
+                last: Some("b".to_owned())
+            })
+        );
+        assert_eq!(tags.next_page(3), None);
+
+        let mut tags = tags;
+        tags.set_next_from_link_header(r#"</v2/busybox/tags/list?n=5&last=c>; rel="next""#);
+        assert_eq!(
+            tags.next_page(3),
+            Some(Pagination {
+                n: Some(5),
+                last: Some("c".to_owned())
+            })
+        );
+
+        let repositories = RepositoryListBuilder::default()
+            .repositories(vec![])
+            .build()?;
+        assert_eq!(repositories.next_page(1), None);
+
+        let mut repositories = repositories;
+        repositories.set_next_from_link_header(r#"</v2/_catalog?last=a%2Fb>; rel="next""#);
+        assert_eq!(
+            repositories.next_page(1),
+            Some(Pagination {
+                n: None,
+                last: Some("a/b".to_owned())
+            })
+        );
+        Ok(())
+    }
+}
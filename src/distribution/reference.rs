@@ -1,11 +1,17 @@
+use std::convert::TryFrom;
 use std::fmt;
 use std::str::FromStr;
-use std::{convert::TryFrom, sync::OnceLock};
 
-use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use super::routes::escape;
+use crate::image::{Digest, DigestAlgorithm};
+#[cfg(feature = "proptests")]
+use crate::some_none_generator_util;
+#[cfg(feature = "proptests")]
+use quickcheck::{Arbitrary, Gen};
+
 /// NAME_TOTAL_LENGTH_MAX is the maximum total number of characters in a repository name.
 const NAME_TOTAL_LENGTH_MAX: usize = 255;
 
@@ -13,22 +19,179 @@ const DOCKER_HUB_DOMAIN_LEGACY: &str = "index.docker.io";
 const DOCKER_HUB_DOMAIN: &str = "docker.io";
 const DOCKER_HUB_OFFICIAL_REPO_NAME: &str = "library";
 const DEFAULT_TAG: &str = "latest";
-/// REFERENCE_REGEXP is the full supported format of a reference. The regexp
-/// is anchored and has capturing groups for name, tag, and digest components.
-const REFERENCE_REGEXP: &str = r"^((?:(?:[a-zA-Z0-9]|[a-zA-Z0-9][a-zA-Z0-9-]*[a-zA-Z0-9])(?:(?:\.(?:[a-zA-Z0-9]|[a-zA-Z0-9][a-zA-Z0-9-]*[a-zA-Z0-9]))+)?(?::[0-9]+)?/)?[a-z0-9]+(?:(?:(?:[._]|__|[-]*)[a-z0-9]+)+)?(?:(?:/[a-z0-9]+(?:(?:(?:[._]|__|[-]*)[a-z0-9]+)+)?)+)?)(?::([\w][\w.-]{0,127}))?(?:@([A-Za-z][A-Za-z0-9]*(?:[-_+.][A-Za-z][A-Za-z0-9]*)*[:][[:xdigit:]]{32,}))?$";
-
-fn reference_regexp() -> &'static Regex {
-    static RE: OnceLock<Regex> = OnceLock::new();
-    RE.get_or_init(|| {
-        RegexBuilder::new(REFERENCE_REGEXP)
-            .size_limit(10 * (1 << 21))
-            .build()
-            .unwrap()
-    })
+/// TAG_TOTAL_LENGTH_MAX is the maximum total number of characters in a tag.
+const TAG_TOTAL_LENGTH_MAX: usize = 128;
+/// DIGEST_ENCODED_LENGTH_MIN is the minimum length of the encoded part of a
+/// digest accepted by the reference grammar.
+const DIGEST_ENCODED_LENGTH_MIN: usize = 32;
+
+// The functions below implement the reference grammar of the distribution
+// project by hand, see
+// https://github.com/distribution/reference/blob/main/reference.go
+// The patterns in the `regexp` module must be kept in sync with them.
+//
+//  reference                       := name [ ":" tag ] [ "@" digest ]
+//  name                            := [domain '/'] remote-name
+//  domain                          := domain-name [':' port-number]
+//  domain-name                     := domain-component ['.' domain-component]*
+//  domain-component                := /([a-zA-Z0-9]|[a-zA-Z0-9][a-zA-Z0-9-]*[a-zA-Z0-9])/
+//  port-number                     := /[0-9]+/
+//  path-component                  := alpha-numeric [separator alpha-numeric]*
+//  remote-name                     := path-component ['/' path-component]*
+//  alpha-numeric                   := /[a-z0-9]+/
+//  separator                       := /[_.]|__|[-]*/
+//  tag                             := /[\w][\w.-]{0,127}/
+//  digest                          := digest-algorithm ":" digest-hex
+//  digest-algorithm                := digest-algorithm-component [ digest-algorithm-separator digest-algorithm-component ]*
+//  digest-algorithm-separator      := /[-_+.]/
+//  digest-algorithm-component      := /[A-Za-z][A-Za-z0-9]*/
+//  digest-hex                      := /[0-9a-fA-F]{32,}/
+
+/// Returns true if `s` is a valid `domain`, a host name with an optional port.
+pub(super) fn is_domain(s: &str) -> bool {
+    let (host, port) = match s.split_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (s, None),
+    };
+    let valid_label = |label: &str| {
+        let bytes = label.as_bytes();
+        match (bytes.first(), bytes.last()) {
+            (Some(first), Some(last)) => {
+                first.is_ascii_alphanumeric()
+                    && last.is_ascii_alphanumeric()
+                    && bytes
+                        .iter()
+                        .all(|b| b.is_ascii_alphanumeric() || *b == b'-')
+            }
+            _ => false,
+        }
+    };
+    host.split('.').all(valid_label)
+        && port.is_none_or(|port| !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Returns true if `s` is a valid `path-component`.
+fn is_path_component(s: &str) -> bool {
+    let is_alnum = |b: u8| b.is_ascii_lowercase() || b.is_ascii_digit();
+    let bytes = s.as_bytes();
+    if !bytes.first().is_some_and(|b| is_alnum(*b)) || !bytes.last().is_some_and(|b| is_alnum(*b)) {
+        return false;
+    }
+    bytes
+        .split(|b| is_alnum(*b))
+        .filter(|separator| !separator.is_empty())
+        .all(|separator| {
+            matches!(separator, b"." | b"_" | b"__") || separator.iter().all(|b| *b == b'-')
+        })
+}
+
+/// Returns true if `s` is a valid `remote-name`.
+pub(super) fn is_remote_name(s: &str) -> bool {
+    s.split('/').all(is_path_component)
+}
+
+/// Returns true if `s` is a valid `name`. A leading component which is both
+/// a valid domain and path component is accepted either way, the domain is
+/// split off later by [split_domain].
+pub(super) fn is_name(s: &str) -> bool {
+    is_remote_name(s)
+        || s.split_once('/')
+            .is_some_and(|(domain, remainder)| is_domain(domain) && is_remote_name(remainder))
+}
+
+/// Returns true if `s` is a valid `tag`.
+pub(super) fn is_tag(s: &str) -> bool {
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    s.len() <= TAG_TOTAL_LENGTH_MAX
+        && s.as_bytes().first().is_some_and(|b| is_word(*b))
+        && s.bytes().all(|b| is_word(b) || b == b'.' || b == b'-')
+}
+
+/// Returns true if `s` matches the `digest` production of the grammar. The
+/// algorithm and length are checked separately by [parse_digest].
+pub(super) fn is_digest(s: &str) -> bool {
+    let Some((algorithm, encoded)) = s.split_once(':') else {
+        return false;
+    };
+    let valid_component = |component: &str| {
+        component
+            .as_bytes()
+            .first()
+            .is_some_and(|b| b.is_ascii_alphabetic())
+            && component.bytes().all(|b| b.is_ascii_alphanumeric())
+    };
+    algorithm.split(['-', '_', '+', '.']).all(valid_component)
+        && encoded.len() >= DIGEST_ENCODED_LENGTH_MIN
+        && encoded.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Splits a reference into its name, tag and digest, returning `None` if it
+/// does not match the reference grammar.
+fn split_reference(s: &str) -> Option<(&str, Option<&str>, Option<&str>)> {
+    let (rest, digest) = match s.split_once('@') {
+        Some((rest, digest)) => (rest, Some(digest)),
+        None => (s, None),
+    };
+    // A colon after the last slash separates the tag, any other colon is part
+    // of the port of the domain.
+    let path_start = rest.rfind('/').map_or(0, |i| i + 1);
+    let (name, tag) = match rest[path_start..].find(':') {
+        Some(i) => (&rest[..path_start + i], Some(&rest[path_start + i + 1..])),
+        None => (rest, None),
+    };
+
+    (is_name(name) && tag.is_none_or(is_tag) && digest.is_none_or(is_digest))
+        .then_some((name, tag, digest))
+}
+
+/// Validates a repository name, like `library/busybox`, without registry,
+/// tag or digest.
+///
+/// ```
+/// use oci_spec::distribution::{validate_repository_name, ParseError};
+///
+/// assert!(validate_repository_name("containers/podman").is_ok());
+/// assert_eq!(
+///     validate_repository_name("Podman"),
+///     Err(ParseError::NameContainsUppercase)
+/// );
+/// ```
+pub fn validate_repository_name(name: &str) -> Result<(), ParseError> {
+    if name.is_empty() {
+        return Err(ParseError::NameEmpty);
+    }
+    if name.bytes().any(|b| b.is_ascii_uppercase()) {
+        return Err(ParseError::NameContainsUppercase);
+    }
+    if !is_remote_name(name) {
+        return Err(ParseError::ReferenceInvalidFormat);
+    }
+    if name.len() > NAME_TOTAL_LENGTH_MAX {
+        return Err(ParseError::NameTooLong);
+    }
+    Ok(())
+}
+
+/// Validates a tag, which must start with a word character and may contain
+/// up to 128 word characters, periods and dashes.
+///
+/// ```
+/// use oci_spec::distribution::{validate_tag, ParseError};
+///
+/// assert!(validate_tag("v1.0_rc-1").is_ok());
+/// assert_eq!(validate_tag(".hidden"), Err(ParseError::TagInvalidFormat));
+/// ```
+pub fn validate_tag(tag: &str) -> Result<(), ParseError> {
+    if is_tag(tag) {
+        Ok(())
+    } else {
+        Err(ParseError::TagInvalidFormat)
+    }
 }
 
 /// Reasons that parsing a string as a Reference can fail.
 #[derive(Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ParseError {
     /// Will be returned if digest is ill-formed
     #[error("invalid checksum digest format")]
@@ -54,6 +217,53 @@ pub enum ParseError {
     /// Will be returned if a tag is ill-formed
     #[error("invalid tag format")]
     TagInvalidFormat,
+    /// Will be returned if a registry is ill-formed
+    #[error("invalid registry format")]
+    RegistryInvalidFormat,
+    /// Will be returned if a package URL is ill-formed or not of the `oci` type
+    #[error("invalid oci package URL")]
+    PurlInvalidFormat,
+}
+
+/// Normalization applied by [`Reference::parse_with`] to references which
+/// omit parts of the name.
+///
+/// The default mirrors the behavior of Docker, which is also used when
+/// parsing via [`FromStr`] or [`TryFrom`].
+///
+/// ```
+/// use oci_spec::distribution::{Reference, ReferenceParseOptions};
+///
+/// let options = ReferenceParseOptions {
+///     default_registry: "registry.example.com".to_string(),
+///     add_latest_tag: false,
+///     ..Default::default()
+/// };
+/// let reference = Reference::parse_with("busybox", &options).unwrap();
+/// assert_eq!("registry.example.com/busybox", reference.whole());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferenceParseOptions {
+    /// The registry used if the reference has none. An empty string leaves
+    /// the registry empty.
+    pub default_registry: String,
+    /// Whether repositories on Docker Hub without a namespace are expanded
+    /// to `library/<name>`.
+    pub add_library_namespace: bool,
+    /// Whether references without tag and digest get the `latest` tag.
+    pub add_latest_tag: bool,
+}
+
+/// Uses `docker.io` as default registry and adds both the `library/`
+/// namespace and the `latest` tag.
+impl Default for ReferenceParseOptions {
+    fn default() -> Self {
+        Self {
+            default_registry: DOCKER_HUB_DOMAIN.into(),
+            add_library_namespace: true,
+            add_latest_tag: true,
+        }
+    }
 }
 
 /// Reference provides a general type to represent any way of referencing images within an OCI registry.
@@ -74,6 +284,7 @@ pub enum ParseError {
 /// assert_eq!(None, reference.digest());
 /// ```
 #[derive(Clone, Hash, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Reference {
     registry: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -82,10 +293,34 @@ pub struct Reference {
     #[serde(skip_serializing_if = "Option::is_none")]
     tag: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    digest: Option<String>,
+    digest: Option<Digest>,
 }
 
 impl Reference {
+    /// Parses a reference, normalizing omitted parts according to `options`.
+    pub fn parse_with(s: &str, options: &ReferenceParseOptions) -> Result<Self, ParseError> {
+        if s.is_empty() {
+            return Err(ParseError::NameEmpty);
+        }
+        let (name, tag, digest) = split_reference(s).ok_or(ParseError::ReferenceInvalidFormat)?;
+        let mut tag = tag.map(str::to_owned);
+        let digest = digest.map(parse_digest).transpose()?;
+        if tag.is_none() && digest.is_none() && options.add_latest_tag {
+            tag = Some(DEFAULT_TAG.into());
+        }
+        let (registry, repository) = split_domain(name, options);
+        if repository.len() > NAME_TOTAL_LENGTH_MAX {
+            return Err(ParseError::NameTooLong);
+        }
+        Ok(Reference {
+            registry,
+            mirror_registry: None,
+            repository,
+            tag,
+            digest,
+        })
+    }
+
     /// Create a Reference with a registry, repository and tag.
     pub fn with_tag(registry: String, repository: String, tag: String) -> Self {
         Self {
@@ -98,7 +333,7 @@ impl Reference {
     }
 
     /// Create a Reference with a registry, repository and digest.
-    pub fn with_digest(registry: String, repository: String, digest: String) -> Self {
+    pub fn with_digest(registry: String, repository: String, digest: Digest) -> Self {
         Self {
             registry,
             mirror_registry: None,
@@ -109,7 +344,7 @@ impl Reference {
     }
 
     /// Clone the Reference for the same image with a new digest.
-    pub fn clone_with_digest(&self, digest: String) -> Self {
+    pub fn clone_with_digest(&self, digest: Digest) -> Self {
         Self {
             registry: self.registry.clone(),
             mirror_registry: self.mirror_registry.clone(),
@@ -165,9 +400,14 @@ impl Reference {
         self.tag.as_deref()
     }
 
-    /// Returns the object's digest, if present.
+    /// Returns the object's digest as string, if present.
     pub fn digest(&self) -> Option<&str> {
-        self.digest.as_deref()
+        self.digest.as_ref().map(AsRef::as_ref)
+    }
+
+    /// Returns the object's digest, if present.
+    pub fn as_digest(&self) -> Option<&Digest> {
+        self.digest.as_ref()
     }
 
     /// Returns the original registry when pulled via a mirror.
@@ -209,6 +449,147 @@ impl Reference {
         }
         s
     }
+
+    /// Returns the shortest docker-compatible form of the reference, which
+    /// is the inverse of the normalization applied when parsing: the
+    /// `docker.io` registry, the `library/` namespace of official images and
+    /// the `latest` tag are omitted.
+    ///
+    /// ```
+    /// use oci_spec::distribution::Reference;
+    ///
+    /// let reference: Reference = "docker.io/library/busybox:latest".parse().unwrap();
+    /// assert_eq!("busybox", reference.familiar());
+    ///
+    /// let reference: Reference = "quay.io/library/busybox:latest".parse().unwrap();
+    /// assert_eq!("quay.io/library/busybox", reference.familiar());
+    /// ```
+    pub fn familiar(&self) -> String {
+        let mut s = match self.registry() {
+            DOCKER_HUB_DOMAIN => {
+                let repository = self.repository();
+                match repository.strip_prefix(&format!("{DOCKER_HUB_OFFICIAL_REPO_NAME}/")) {
+                    Some(name) if !name.contains('/') => name.to_string(),
+                    _ => repository.to_string(),
+                }
+            }
+            _ => self.full_name(),
+        };
+        if let Some(t) = self.tag().filter(|t| *t != DEFAULT_TAG) {
+            s.push(':');
+            s.push_str(t);
+        }
+        if let Some(d) = self.digest() {
+            s.push('@');
+            s.push_str(d);
+        }
+        s
+    }
+
+    /// Returns the [package URL](https://github.com/package-url/purl-spec) of
+    /// the `oci` type for the reference, as used by SBOM formats.
+    ///
+    /// The name is the last component of the repository and the version is
+    /// the digest. The `repository_url` qualifier carries the registry and
+    /// repository and the `tag` qualifier the tag. As the `oci` type requires
+    /// a version, `None` is returned if the reference has no digest.
+    ///
+    /// ```
+    /// use oci_spec::distribution::Reference;
+    ///
+    /// let reference: Reference = "ghcr.io/foo/bar:v1@sha256:ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff".parse().unwrap();
+    /// let purl = reference.to_purl().unwrap();
+    /// assert_eq!(
+    ///     purl,
+    ///     "pkg:oci/bar@sha256%3Affffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff?repository_url=ghcr.io/foo/bar&tag=v1"
+    /// );
+    /// assert_eq!(Reference::from_purl(&purl).unwrap(), reference);
+    ///
+    /// let tagged: Reference = "ghcr.io/foo/bar:v1".parse().unwrap();
+    /// assert_eq!(tagged.to_purl(), None);
+    /// ```
+    pub fn to_purl(&self) -> Option<String> {
+        let name = self
+            .repository()
+            .rsplit('/')
+            .next()
+            .unwrap_or(self.repository());
+        let digest = self.digest()?;
+        let mut purl = format!("pkg:oci/{}@{}", escape(name), escape(digest));
+        purl.push_str("?repository_url=");
+        purl.push_str(&self.full_name());
+        if let Some(tag) = self.tag() {
+            purl.push_str("&tag=");
+            purl.push_str(&escape(tag));
+        }
+        Some(purl)
+    }
+
+    /// Parses a package URL of the `oci` type into a reference.
+    ///
+    /// The registry and repository are taken from the `repository_url`
+    /// qualifier. Without it, the name is looked up on Docker Hub. Other
+    /// qualifiers, like `arch`, are ignored. A missing version is accepted
+    /// and results in a reference without digest.
+    pub fn from_purl(purl: &str) -> Result<Self, ParseError> {
+        let rest = purl
+            .strip_prefix("pkg:")
+            .map(|rest| rest.trim_start_matches('/'))
+            .and_then(|rest| rest.strip_prefix("oci/"))
+            .ok_or(ParseError::PurlInvalidFormat)?;
+        let rest = rest.split('#').next().unwrap_or_default();
+        let (path, qualifiers) = rest.split_once('?').unwrap_or((rest, ""));
+        let (name, version) = match path.split_once('@') {
+            Some((name, version)) => (name, Some(percent_decode(version)?)),
+            None => (path, None),
+        };
+        let name = percent_decode(name)?.to_lowercase();
+        if name.is_empty() || name.contains('/') {
+            return Err(ParseError::PurlInvalidFormat);
+        }
+
+        let mut builder = ReferenceBuilder::default().repository(name.clone());
+        for (key, value) in qualifiers.split('&').filter_map(|q| q.split_once('=')) {
+            let value = percent_decode(value)?;
+            match key.to_ascii_lowercase().as_str() {
+                "repository_url" => {
+                    let url = value
+                        .split_once("://")
+                        .map_or(value.as_str(), |(_, url)| url)
+                        .trim_end_matches('/');
+                    let (registry, repository) = url.split_once('/').unwrap_or((url, &name));
+                    builder = builder.registry(registry).repository(repository);
+                }
+                "tag" => builder = builder.tag(value),
+                _ => {}
+            }
+        }
+        if let Some(version) = version {
+            builder = builder.digest(parse_digest(&version)?);
+        }
+        builder.build()
+    }
+}
+
+/// Decodes the percent-encoded octets of a package URL component.
+fn percent_decode(s: &str) -> Result<String, ParseError> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let octet = tail
+                .get(..2)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or(ParseError::PurlInvalidFormat)?;
+            bytes.push(octet);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| ParseError::PurlInvalidFormat)
 }
 
 impl fmt::Display for Reference {
@@ -229,56 +610,7 @@ impl TryFrom<String> for Reference {
     type Error = ParseError;
 
     fn try_from(s: String) -> Result<Self, Self::Error> {
-        if s.is_empty() {
-            return Err(ParseError::NameEmpty);
-        }
-        let captures = match reference_regexp().captures(&s) {
-            Some(caps) => caps,
-            None => {
-                return Err(ParseError::ReferenceInvalidFormat);
-            }
-        };
-        let name = &captures[1];
-        let mut tag = captures.get(2).map(|m| m.as_str().to_owned());
-        let digest = captures.get(3).map(|m| m.as_str().to_owned());
-        if tag.is_none() && digest.is_none() {
-            tag = Some(DEFAULT_TAG.into());
-        }
-        let (registry, repository) = split_domain(name);
-        let reference = Reference {
-            registry,
-            mirror_registry: None,
-            repository,
-            tag,
-            digest,
-        };
-        if reference.repository().len() > NAME_TOTAL_LENGTH_MAX {
-            return Err(ParseError::NameTooLong);
-        }
-        // Digests much always be hex-encoded, ensuring that their hex portion will always be
-        // size*2
-        if let Some(digest) = reference.digest() {
-            match digest.split_once(':') {
-                None => return Err(ParseError::DigestInvalidFormat),
-                Some(("sha256", digest)) => {
-                    if digest.len() != 64 {
-                        return Err(ParseError::DigestInvalidLength);
-                    }
-                }
-                Some(("sha384", digest)) => {
-                    if digest.len() != 96 {
-                        return Err(ParseError::DigestInvalidLength);
-                    }
-                }
-                Some(("sha512", digest)) => {
-                    if digest.len() != 128 {
-                        return Err(ParseError::DigestInvalidLength);
-                    }
-                }
-                Some((_, _)) => return Err(ParseError::DigestUnsupported),
-            }
-        }
-        Ok(reference)
+        Reference::parse_with(&s, &ReferenceParseOptions::default())
     }
 }
 
@@ -295,41 +627,186 @@ impl From<Reference> for String {
     }
 }
 
+/// Parses a digest, which must be valid and use one of the registered
+/// algorithms.
+fn parse_digest(digest: &str) -> Result<Digest, ParseError> {
+    let parsed = Digest::try_from(digest).map_err(|_| match digest.split_once(':') {
+        Some((algorithm, encoded)) => match DigestAlgorithm::from(algorithm).digest_hexlen() {
+            Some(len) if len as usize != encoded.len() => ParseError::DigestInvalidLength,
+            _ => ParseError::DigestInvalidFormat,
+        },
+        None => ParseError::DigestInvalidFormat,
+    })?;
+    supported_digest(parsed)
+}
+
+fn supported_digest(digest: Digest) -> Result<Digest, ParseError> {
+    match digest.algorithm().digest_hexlen() {
+        Some(_) => Ok(digest),
+        None => Err(ParseError::DigestUnsupported),
+    }
+}
+
+/// Builder for a [Reference], which validates every component against the
+/// reference grammar when building.
+///
+/// The registry defaults to `docker.io` and the tag to `latest` if neither a
+/// tag nor a digest is set. Repositories on Docker Hub without a namespace
+/// are normalized to `library/<name>`, just like when parsing a reference.
+///
+/// # Examples
+///
+/// ```
+/// use oci_spec::distribution::ReferenceBuilder;
+///
+/// let reference = ReferenceBuilder::default()
+///     .registry("quay.io")
+///     .repository("containers/podman")
+///     .tag("v5")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!("quay.io/containers/podman:v5", reference.whole());
+/// assert!(ReferenceBuilder::default().repository("Podman").build().is_err());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ReferenceBuilder {
+    registry: Option<String>,
+    repository: Option<String>,
+    tag: Option<String>,
+    digest: Option<Digest>,
+}
+
+impl ReferenceBuilder {
+    /// Sets the registry, including an optional port.
+    pub fn registry(mut self, registry: impl Into<String>) -> Self {
+        self.registry = Some(registry.into());
+        self
+    }
+
+    /// Sets the repository.
+    pub fn repository(mut self, repository: impl Into<String>) -> Self {
+        self.repository = Some(repository.into());
+        self
+    }
+
+    /// Sets the tag.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// Sets the digest.
+    pub fn digest(mut self, digest: impl Into<Digest>) -> Self {
+        self.digest = Some(digest.into());
+        self
+    }
+
+    /// Validates all components and builds the [Reference].
+    pub fn build(self) -> Result<Reference, ParseError> {
+        let registry = self.registry.unwrap_or_else(|| DOCKER_HUB_DOMAIN.into());
+        if !is_domain(&registry) {
+            return Err(ParseError::RegistryInvalidFormat);
+        }
+
+        let repository = self.repository.unwrap_or_default();
+        validate_repository_name(&repository)?;
+        if let Some(tag) = &self.tag {
+            validate_tag(tag)?;
+        }
+        let digest = self.digest.map(supported_digest).transpose()?;
+
+        let (registry, repository) = split_domain(
+            &format!("{registry}/{repository}"),
+            &ReferenceParseOptions::default(),
+        );
+        if repository.len() > NAME_TOTAL_LENGTH_MAX {
+            return Err(ParseError::NameTooLong);
+        }
+        let tag = match (self.tag, &digest) {
+            (None, None) => Some(DEFAULT_TAG.into()),
+            (tag, _) => tag,
+        };
+
+        Ok(Reference {
+            registry,
+            mirror_registry: None,
+            repository,
+            tag,
+            digest,
+        })
+    }
+}
+
 /// Splits a repository name to domain and remotename string.
-/// If no valid domain is found, the default domain is used. Repository name
-/// needs to be already validated before.
+/// If no valid domain is found, the default domain of the options is used.
+/// Repository name needs to be already validated before.
 ///
 /// This function is a Rust rewrite of the official Go code used by Docker:
 /// https://github.com/distribution/distribution/blob/41a0452eea12416aaf01bceb02a924871e964c67/reference/normalize.go#L87-L104
-fn split_domain(name: &str) -> (String, String) {
+fn split_domain(name: &str, options: &ReferenceParseOptions) -> (String, String) {
     let mut domain: String;
     let mut remainder: String;
 
     match name.split_once('/') {
-        None => {
-            domain = DOCKER_HUB_DOMAIN.into();
-            remainder = name.into();
+        Some((left, right)) if left.contains('.') || left.contains(':') || left == "localhost" => {
+            domain = left.into();
+            remainder = right.into();
         }
-        Some((left, right)) => {
-            if !(left.contains('.') || left.contains(':')) && left != "localhost" {
-                domain = DOCKER_HUB_DOMAIN.into();
-                remainder = name.into();
-            } else {
-                domain = left.into();
-                remainder = right.into();
-            }
+        _ => {
+            domain = options.default_registry.clone();
+            remainder = name.into();
         }
     }
     if domain == DOCKER_HUB_DOMAIN_LEGACY {
         domain = DOCKER_HUB_DOMAIN.into();
     }
-    if domain == DOCKER_HUB_DOMAIN && !remainder.contains('/') {
+    if options.add_library_namespace && domain == DOCKER_HUB_DOMAIN && !remainder.contains('/') {
         remainder = format!("{}/{}", DOCKER_HUB_OFFICIAL_REPO_NAME, remainder);
     }
 
     (domain, remainder)
 }
 
+#[cfg(feature = "proptests")]
+impl Arbitrary for Reference {
+    fn arbitrary(g: &mut Gen) -> Reference {
+        let component = |g: &mut Gen| -> String {
+            let len = 1 + usize::arbitrary(g) % 8;
+            (0..len)
+                .map(|_| *g.choose(b"abcdefghijklmnopqrstuvwxyz0123456789").unwrap() as char)
+                .collect()
+        };
+        let registry = g
+            .choose(&[DOCKER_HUB_DOMAIN, "ghcr.io", "quay.io", "localhost:5000"])
+            .unwrap()
+            .to_string();
+        let components = 1 + usize::arbitrary(g) % 3;
+        let mut repository = (0..components)
+            .map(|_| component(g))
+            .collect::<Vec<_>>()
+            .join("/");
+        if registry == DOCKER_HUB_DOMAIN && !repository.contains('/') {
+            repository = format!("{DOCKER_HUB_OFFICIAL_REPO_NAME}/{repository}");
+        }
+        let tag = match bool::arbitrary(g) {
+            true => Some(component(g)),
+            false => None,
+        };
+        let digest = some_none_generator_util(g);
+        Reference {
+            registry,
+            mirror_registry: None,
+            repository,
+            tag: match (&tag, &digest) {
+                (None, None) => Some(DEFAULT_TAG.to_owned()),
+                _ => tag,
+            },
+            digest,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -358,6 +835,8 @@ mod test {
             case("foo_bar.com:8080", "docker.io", "library/foo_bar.com", Some("8080"), None, "docker.io/library/foo_bar.com:8080" ),
             case("foo/foo_bar.com:8080", "docker.io", "foo/foo_bar.com", Some("8080"), None, "docker.io/foo/foo_bar.com:8080"),
             case("opensuse/leap:15.3", "docker.io", "opensuse/leap", Some("15.3"), None, "docker.io/opensuse/leap:15.3"),
+            case("foo__bar/a--b.c_d:_tag", "docker.io", "foo__bar/a--b.c_d", Some("_tag"), None, "docker.io/foo__bar/a--b.c_d:_tag"),
+            case("Registry-1.example.com/foo", "Registry-1.example.com", "foo", Some("latest"), None, "Registry-1.example.com/foo:latest"),
         )]
         fn parse_good_reference(
             input: &str,
@@ -374,6 +853,7 @@ mod test {
             assert_eq!(repository, reference.repository());
             assert_eq!(tag, reference.tag());
             assert_eq!(digest, reference.digest());
+            assert_eq!(digest, reference.as_digest().map(AsRef::as_ref));
             assert_eq!(whole, reference.whole());
         }
 
@@ -391,12 +871,92 @@ mod test {
             // FIXME: should really pass a ParseError::NameContainsUppercase, but "invalid format" is good enough for now.
             case("test:5000/Uppercase/lowercase:tag", ParseError::ReferenceInvalidFormat),
             case("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", ParseError::NameTooLong),
-            case("aa/asdf$$^/aa", ParseError::ReferenceInvalidFormat)
+            case("aa/asdf$$^/aa", ParseError::ReferenceInvalidFormat),
+            case("repo:", ParseError::ReferenceInvalidFormat),
+            case("repo:-tag", ParseError::ReferenceInvalidFormat),
+            case("repo:tag:tag", ParseError::ReferenceInvalidFormat),
+            case("foo___bar", ParseError::ReferenceInvalidFormat),
+            case("foo-/bar", ParseError::ReferenceInvalidFormat),
+            case("foo//bar", ParseError::ReferenceInvalidFormat),
+            case("-foo.com/bar", ParseError::ReferenceInvalidFormat),
+            case("foo.com:50a0/bar", ParseError::ReferenceInvalidFormat),
+            case("foo.com:/bar", ParseError::ReferenceInvalidFormat),
+            case("repo@sha256:ffffffffffffffffffffffffffffffff@sha256:ffffffffffffffffffffffffffffffff", ParseError::ReferenceInvalidFormat),
+            case("repo@sha256:fffffffffffffffffffffffffffffff", ParseError::ReferenceInvalidFormat),
+            case("repo@2sha:ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff", ParseError::ReferenceInvalidFormat)
         )]
         fn parse_bad_reference(input: &str, err: ParseError) {
             assert_eq!(Reference::try_from(input).unwrap_err(), err)
         }
 
+        #[rstest(
+            input,
+            result,
+            case("busybox", Ok(())),
+            case("library/busybox", Ok(())),
+            case("a/b/c-d__e.f", Ok(())),
+            case("", Err(ParseError::NameEmpty)),
+            case("library/Busybox", Err(ParseError::NameContainsUppercase)),
+            case("docker.io:5000/busybox", Err(ParseError::ReferenceInvalidFormat)),
+            case("busybox:latest", Err(ParseError::ReferenceInvalidFormat)),
+            case("/busybox", Err(ParseError::ReferenceInvalidFormat)),
+            case(&"a".repeat(256), Err(ParseError::NameTooLong))
+        )]
+        fn repository_name(input: &str, result: Result<(), ParseError>) {
+            assert_eq!(validate_repository_name(input), result);
+        }
+
+        #[rstest(
+            input,
+            result,
+            case("latest", Ok(())),
+            case("_", Ok(())),
+            case(&"a".repeat(128), Ok(())),
+            case(&"a".repeat(129), Err(ParseError::TagInvalidFormat)),
+            case("", Err(ParseError::TagInvalidFormat)),
+            case("-rc", Err(ParseError::TagInvalidFormat)),
+            case("v1+build", Err(ParseError::TagInvalidFormat))
+        )]
+        fn tag(input: &str, result: Result<(), ParseError>) {
+            assert_eq!(validate_tag(input), result);
+        }
+
+        #[rstest(
+            input,
+            default_registry,
+            library,
+            latest,
+            whole,
+            case("busybox", "docker.io", false, true, "docker.io/busybox:latest"),
+            case("busybox", "docker.io", true, false, "docker.io/library/busybox"),
+            case("busybox", "quay.io", true, true, "quay.io/busybox:latest"),
+            case("busybox:1", "", true, true, "busybox:1"),
+            case("foo/bar", "quay.io", true, true, "quay.io/foo/bar:latest"),
+            case(
+                "index.docker.io/busybox",
+                "quay.io",
+                false,
+                false,
+                "docker.io/busybox"
+            ),
+            case("localhost/busybox", "quay.io", true, false, "localhost/busybox")
+        )]
+        fn parse_with_options(
+            input: &str,
+            default_registry: &str,
+            library: bool,
+            latest: bool,
+            whole: &str,
+        ) {
+            let options = ReferenceParseOptions {
+                default_registry: default_registry.to_string(),
+                add_library_namespace: library,
+                add_latest_tag: latest,
+            };
+            let reference = Reference::parse_with(input, &options).unwrap();
+            assert_eq!(whole, reference.whole());
+        }
+
         #[rstest(
             input,
             registry,
@@ -437,4 +997,156 @@ mod test {
             assert_eq!(whole, reference.whole());
         }
     }
+
+    mod familiar {
+        use super::*;
+        use rstest::rstest;
+
+        #[rstest(input, familiar,
+            case("busybox", "busybox"),
+            case("docker.io/library/busybox:latest", "busybox"),
+            case("index.docker.io/library/busybox:1.36", "busybox:1.36"),
+            case("docker.io/library/foo/bar", "library/foo/bar"),
+            case("opensuse/leap:15.3", "opensuse/leap:15.3"),
+            case("quay.io/busybox:latest", "quay.io/busybox"),
+            case("localhost/busybox", "localhost/busybox"),
+            case("test:5000/repo:latest@sha256:ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff", "test:5000/repo@sha256:ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"),
+        )]
+        fn familiar_name(input: &str, familiar: &str) {
+            let reference = Reference::try_from(input).expect("could not parse reference");
+            assert_eq!(familiar, reference.familiar());
+            let reparsed = Reference::try_from(familiar).expect("could not parse familiar name");
+            assert_eq!(reference.repository(), reparsed.repository());
+            assert_eq!(reference.registry(), reparsed.registry());
+        }
+    }
+
+    mod purl {
+        use super::*;
+        use rstest::rstest;
+
+        #[rstest(input, purl,
+            case("busybox@sha256:ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff", "pkg:oci/busybox@sha256%3Affffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff?repository_url=docker.io/library/busybox"),
+            case("quay.io/foo/bar:1.0@sha256:ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff", "pkg:oci/bar@sha256%3Affffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff?repository_url=quay.io/foo/bar&tag=1.0"),
+            case("localhost:5000/repo@sha256:ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff", "pkg:oci/repo@sha256%3Affffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff?repository_url=localhost:5000/repo"),
+        )]
+        fn to_purl(input: &str, purl: &str) {
+            let reference = Reference::try_from(input).expect("could not parse reference");
+            assert_eq!(reference.to_purl().as_deref(), Some(purl));
+            assert_eq!(Reference::from_purl(purl).unwrap(), reference);
+        }
+
+        #[test]
+        fn to_purl_without_digest() {
+            let reference = Reference::try_from("quay.io/foo/bar:1.0").unwrap();
+            assert_eq!(reference.to_purl(), None);
+        }
+
+        #[rstest(purl, reference,
+            case("pkg:oci/debian@sha256%3Affffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff?arch=amd64&repository_url=https://docker.io/library/debian&tag=latest", "docker.io/library/debian:latest@sha256:ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"),
+            case("pkg:oci/Hello-Wasm?tag=v1", "docker.io/library/hello-wasm:v1"),
+            case("pkg:oci/bar?repository_url=quay.io%2Ffoo%2Fbar&tag=v1%2E2", "quay.io/foo/bar:v1.2"),
+        )]
+        fn from_purl(purl: &str, reference: &str) {
+            assert_eq!(
+                Reference::from_purl(purl).unwrap(),
+                Reference::try_from(reference).unwrap()
+            );
+        }
+
+        #[rstest(
+            purl,
+            err,
+            case("pkg:npm/foo", ParseError::PurlInvalidFormat),
+            case("pkg:oci/", ParseError::PurlInvalidFormat),
+            case("pkg:oci/foo@sha256%3Aabc", ParseError::DigestInvalidLength),
+            case("pkg:oci/foo%2", ParseError::PurlInvalidFormat),
+            case("pkg:oci/foo%zz", ParseError::PurlInvalidFormat),
+            case("pkg:oci/foo%ff", ParseError::PurlInvalidFormat)
+        )]
+        fn invalid_purl(purl: &str, err: ParseError) {
+            assert_eq!(Reference::from_purl(purl), Err(err));
+        }
+    }
+
+    mod builder {
+        use super::*;
+        use rstest::rstest;
+
+        #[test]
+        fn build_good_reference() {
+            let digest = "sha256:ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+            let reference = ReferenceBuilder::default()
+                .registry("test:5000")
+                .repository("repo")
+                .tag("tag")
+                .digest(Digest::from_str(digest).unwrap())
+                .build()
+                .expect("could not build reference");
+            assert_eq!("test:5000", reference.registry());
+            assert_eq!("repo", reference.repository());
+            assert_eq!(Some("tag"), reference.tag());
+            assert_eq!(Some(digest), reference.digest());
+
+            let reference = ReferenceBuilder::default()
+                .repository("busybox")
+                .build()
+                .expect("could not build reference");
+            assert_eq!(reference, Reference::try_from("busybox").unwrap());
+        }
+
+        #[rstest(
+            registry,
+            repository,
+            tag,
+            digest,
+            err,
+            case("docker.io", "", None, None, ParseError::NameEmpty),
+            case(
+                "docker.io",
+                "Uppercase",
+                None,
+                None,
+                ParseError::NameContainsUppercase
+            ),
+            case("docker.io", "repo/", None, None, ParseError::ReferenceInvalidFormat),
+            case("-invalid.com", "repo", None, None, ParseError::RegistryInvalidFormat),
+            case("test.com/foo", "repo", None, None, ParseError::RegistryInvalidFormat),
+            case("docker.io", "repo", Some(".tag"), None, ParseError::TagInvalidFormat),
+            case(
+                "docker.io",
+                "repo",
+                None,
+                Some("md5:ffffffffffffffffffffffffffffffff"),
+                ParseError::DigestUnsupported
+            )
+        )]
+        fn build_bad_reference(
+            registry: &str,
+            repository: &str,
+            tag: Option<&str>,
+            digest: Option<&str>,
+            err: ParseError,
+        ) {
+            let mut builder = ReferenceBuilder::default()
+                .registry(registry)
+                .repository(repository);
+            if let Some(tag) = tag {
+                builder = builder.tag(tag);
+            }
+            if let Some(digest) = digest {
+                builder = builder.digest(Digest::from_str(digest).unwrap());
+            }
+            assert_eq!(builder.build().unwrap_err(), err);
+        }
+    }
+
+    #[cfg(feature = "proptests")]
+    #[test]
+    fn reference_round_trip() {
+        fn prop(reference: Reference) -> bool {
+            reference.whole().parse::<Reference>().unwrap() == reference
+        }
+        quickcheck::quickcheck(prop as fn(Reference) -> bool);
+    }
 }
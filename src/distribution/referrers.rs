@@ -0,0 +1,234 @@
+//! Types of the referrers API, `/v2/<name>/referrers/<digest>`.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{
+    error::{oci_error, OciSpecError, Result},
+    image::{Descriptor, ImageIndex, MediaType},
+};
+
+/// The list of manifests referring to a subject, as returned by the referrers
+/// API. It is an [ImageIndex] whose manifests carry the `artifactType` and
+/// annotations of the referring manifests.
+///
+/// ```
+/// use oci_spec::distribution::{FiltersApplied, Referrers};
+/// use oci_spec::image::{Descriptor, Digest, MediaType};
+/// use std::str::FromStr;
+///
+/// let digest = Digest::from_str(
+///     "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356",
+/// ).unwrap();
+/// let sbom = MediaType::Other("application/spdx+json".to_string());
+/// let mut descriptor = Descriptor::new(MediaType::ImageManifest, 100, digest);
+/// descriptor.set_artifact_type(Some(sbom.clone()));
+///
+/// let referrers = Referrers::new(vec![descriptor]);
+/// assert_eq!(referrers.filter_by_artifact_type(&sbom).len(), 1);
+/// assert!(referrers
+///     .filtered(&sbom, &FiltersApplied::default())
+///     .iter()
+///     .all(|d| d.artifact_type().as_ref() == Some(&sbom)));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Referrers {
+    index: ImageIndex,
+}
+
+impl Referrers {
+    /// Creates a referrers response of the given manifest descriptors.
+    pub fn new(manifests: Vec<Descriptor>) -> Self {
+        let mut index = ImageIndex::default();
+        index.set_media_type(Some(MediaType::ImageIndex));
+        index.set_manifests(manifests);
+        Self { index }
+    }
+
+    /// Parses a referrers response from a stream.
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Self> {
+        ImageIndex::from_reader(reader)?.try_into()
+    }
+
+    /// Returns the descriptors of all referring manifests.
+    pub fn manifests(&self) -> &[Descriptor] {
+        self.index.manifests()
+    }
+
+    /// Returns the descriptors of the referring manifests with the given
+    /// artifact type.
+    pub fn filter_by_artifact_type(&self, artifact_type: &MediaType) -> Vec<&Descriptor> {
+        self.manifests()
+            .iter()
+            .filter(|d| d.artifact_type().as_ref() == Some(artifact_type))
+            .collect()
+    }
+
+    /// Returns the descriptors of the referring manifests with the given
+    /// annotation value.
+    pub fn filter_by_annotation(&self, key: &str, value: &str) -> Vec<&Descriptor> {
+        self.manifests()
+            .iter()
+            .filter(|d| {
+                d.annotations()
+                    .as_ref()
+                    .and_then(|a| a.get(key))
+                    .is_some_and(|v| v == value)
+            })
+            .collect()
+    }
+
+    /// Returns the descriptors with the given artifact type of a response to
+    /// a request filtered by `artifact_type`. If the registry did not apply
+    /// the filter, as indicated by the `OCI-Filters-Applied` header, it is
+    /// applied on the client side.
+    pub fn filtered(
+        &self,
+        artifact_type: &MediaType,
+        applied: &FiltersApplied,
+    ) -> Vec<&Descriptor> {
+        match applied.artifact_type() {
+            true => self.manifests().iter().collect(),
+            false => self.filter_by_artifact_type(artifact_type),
+        }
+    }
+
+    /// Returns the underlying [ImageIndex].
+    pub fn into_index(self) -> ImageIndex {
+        self.index
+    }
+}
+
+impl TryFrom<ImageIndex> for Referrers {
+    type Error = OciSpecError;
+
+    fn try_from(index: ImageIndex) -> Result<Self> {
+        match index.media_type() {
+            None | Some(MediaType::ImageIndex) => Ok(Self { index }),
+            Some(media_type) => Err(oci_error(format!(
+                "invalid media type of referrers response: {media_type}"
+            ))),
+        }
+    }
+}
+
+impl From<Referrers> for ImageIndex {
+    fn from(referrers: Referrers) -> Self {
+        referrers.index
+    }
+}
+
+/// The value of the `OCI-Filters-Applied` header, listing the filters a
+/// registry applied to a referrers response.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FiltersApplied {
+    filters: Vec<String>,
+}
+
+impl FiltersApplied {
+    /// The filter by artifact type.
+    pub const ARTIFACT_TYPE: &'static str = "artifactType";
+
+    /// Creates the value listing `filters`.
+    pub fn new<I, S>(filters: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            filters: filters.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns the applied filters.
+    pub fn filters(&self) -> &[String] {
+        &self.filters
+    }
+
+    /// Returns true if the filter by artifact type has been applied.
+    pub fn artifact_type(&self) -> bool {
+        self.filters.iter().any(|f| f == Self::ARTIFACT_TYPE)
+    }
+}
+
+impl FromStr for FiltersApplied {
+    type Err = OciSpecError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(Self::new(
+            s.split(',').map(str::trim).filter(|f| !f.is_empty()),
+        ))
+    }
+}
+
+impl Display for FiltersApplied {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.filters.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::image::Digest;
+
+    fn descriptor(artifact_type: &str, annotations: &[(&str, &str)]) -> Descriptor {
+        let digest = Digest::from_str(
+            "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356",
+        )
+        .unwrap();
+        let mut descriptor = Descriptor::new(MediaType::ImageManifest, 100, digest);
+        descriptor.set_artifact_type(Some(MediaType::from(artifact_type)));
+        let annotations: HashMap<_, _> = annotations
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        descriptor.set_annotations(Some(annotations));
+        descriptor
+    }
+
+    #[test]
+    fn filter_referrers() {
+        let referrers = Referrers::new(vec![
+            descriptor("application/spdx+json", &[("tool", "syft")]),
+            descriptor("application/vnd.dev.cosign.artifact.sig.v1+json", &[]),
+        ]);
+        let sbom = MediaType::from("application/spdx+json");
+
+        assert_eq!(referrers.filter_by_artifact_type(&sbom).len(), 1);
+        assert_eq!(referrers.filter_by_annotation("tool", "syft").len(), 1);
+        assert_eq!(referrers.filter_by_annotation("tool", "trivy").len(), 0);
+        assert_eq!(
+            referrers.filtered(&sbom, &FiltersApplied::default()).len(),
+            1
+        );
+        // A registry applying the filter is trusted.
+        assert_eq!(
+            referrers
+                .filtered(&sbom, &"artifactType".parse().unwrap())
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn parse_referrers() {
+        let json = br#"{"schemaVersion":2,"mediaType":"application/vnd.oci.image.index.v1+json","manifests":[]}"#;
+        let referrers = Referrers::from_reader(&json[..]).unwrap();
+        assert!(referrers.manifests().is_empty());
+
+        let json = br#"{"schemaVersion":2,"mediaType":"application/vnd.oci.image.manifest.v1+json","manifests":[]}"#;
+        assert!(Referrers::from_reader(&json[..]).is_err());
+    }
+
+    #[test]
+    fn filters_applied() {
+        let applied: FiltersApplied = "artifactType, foo".parse().unwrap();
+        assert!(applied.artifact_type());
+        assert_eq!(applied.filters(), ["artifactType", "foo"]);
+        assert_eq!(applied.to_string(), "artifactType,foo");
+        assert!(!FiltersApplied::default().artifact_type());
+    }
+}
@@ -0,0 +1,188 @@
+//! The reference grammar as regular expression patterns.
+//!
+//! The patterns are not anchored and contain no capturing groups, except for
+//! [REFERENCE], so they can be composed into larger expressions, for example
+//! for policy files matching references. They only use syntax shared by
+//! common regular expression engines, like the `regex` crate and Go's
+//! `regexp` package.
+//!
+//! The patterns are the canonical form of the grammar used when parsing a
+//! [Reference](super::Reference) and are covered by the semver guarantees of
+//! this crate: they only change in breaking releases, except for fixes which
+//! bring them in line with the distribution spec.
+//!
+//! ```
+//! use oci_spec::distribution::regexp;
+//! use regex::Regex;
+//!
+//! let quay = Regex::new(&format!("^quay\\.io/{}:{}$", regexp::REMOTE_NAME, regexp::TAG)).unwrap();
+//! assert!(quay.is_match("quay.io/containers/podman:v5"));
+//! assert!(!quay.is_match("docker.io/library/busybox:latest"));
+//! ```
+
+macro_rules! alpha_numeric {
+    () => {
+        "[a-z0-9]+"
+    };
+}
+
+macro_rules! separator {
+    () => {
+        "(?:[._]|__|[-]+)"
+    };
+}
+
+macro_rules! path_component {
+    () => {
+        concat!(
+            alpha_numeric!(),
+            "(?:",
+            separator!(),
+            alpha_numeric!(),
+            ")*"
+        )
+    };
+}
+
+macro_rules! remote_name {
+    () => {
+        concat!(path_component!(), "(?:/", path_component!(), ")*")
+    };
+}
+
+macro_rules! domain_component {
+    () => {
+        "(?:[a-zA-Z0-9]|[a-zA-Z0-9][a-zA-Z0-9-]*[a-zA-Z0-9])"
+    };
+}
+
+macro_rules! domain {
+    () => {
+        concat!(
+            domain_component!(),
+            "(?:\\.",
+            domain_component!(),
+            ")*(?::[0-9]+)?"
+        )
+    };
+}
+
+macro_rules! name {
+    () => {
+        concat!("(?:", domain!(), "/)?", remote_name!())
+    };
+}
+
+macro_rules! tag {
+    () => {
+        "[a-zA-Z0-9_][a-zA-Z0-9_.-]{0,127}"
+    };
+}
+
+macro_rules! digest {
+    () => {
+        "[A-Za-z][A-Za-z0-9]*(?:[-_+.][A-Za-z][A-Za-z0-9]*)*:[0-9a-fA-F]{32,}"
+    };
+}
+
+/// ALPHA_NUMERIC matches a run of lowercase letters and digits.
+pub const ALPHA_NUMERIC: &str = alpha_numeric!();
+/// SEPARATOR matches the separators allowed between the alpha-numeric runs of
+/// a path component: a period, one or two underscores, or dashes.
+pub const SEPARATOR: &str = separator!();
+/// PATH_COMPONENT matches a single component of a repository name.
+pub const PATH_COMPONENT: &str = path_component!();
+/// REMOTE_NAME matches a repository name without registry, like
+/// `library/busybox`.
+pub const REMOTE_NAME: &str = remote_name!();
+/// DOMAIN_COMPONENT matches a single label of a registry host name.
+pub const DOMAIN_COMPONENT: &str = domain_component!();
+/// DOMAIN matches a registry host name with an optional port.
+pub const DOMAIN: &str = domain!();
+/// NAME matches a repository name with an optional registry.
+pub const NAME: &str = name!();
+/// TAG matches a tag.
+pub const TAG: &str = tag!();
+/// DIGEST matches a digest. Whether the algorithm is supported and the
+/// encoded part has the right length is not checked.
+pub const DIGEST: &str = digest!();
+/// REFERENCE matches a whole reference, with capturing groups for the name,
+/// tag and digest.
+pub const REFERENCE: &str = concat!("(", name!(), ")(?::(", tag!(), "))?(?:@(", digest!(), "))?");
+
+#[cfg(test)]
+mod tests {
+    use super::super::reference::{is_digest, is_domain, is_name, is_remote_name, is_tag};
+    use super::*;
+    use regex::Regex;
+
+    fn anchored(pattern: &str) -> Regex {
+        Regex::new(&format!("^(?:{pattern})$")).unwrap()
+    }
+
+    const INPUTS: &[&str] = &[
+        "",
+        "a",
+        "A",
+        "busybox",
+        "library/busybox",
+        "foo__bar",
+        "foo___bar",
+        "a--b",
+        "a-",
+        "-a",
+        "a.b_c",
+        "a//b",
+        "/a",
+        "localhost",
+        "localhost:5000",
+        "localhost:",
+        "Registry-1.example.com",
+        "-bad.com",
+        "bad-.com",
+        "foo.com:5000/bar/baz",
+        "foo.com:port/bar",
+        "Foo/bar",
+        "foo/Bar",
+        "_tag",
+        ".tag",
+        "v1.0-rc_1",
+        "sha256:ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "sha256:ffffffffffffffffffffffffffffffff",
+        "sha256:fffffffffffffffffffffffffffffff",
+        "sha256+b64u.x:ffffffffffffffffffffffffffffffff",
+        "2sha:ffffffffffffffffffffffffffffffff",
+        "sha256:gggggggggggggggggggggggggggggggg",
+    ];
+
+    #[test]
+    fn patterns_match_parser() {
+        type Parser = fn(&str) -> bool;
+        let checks: [(&str, Parser); 5] = [
+            (DOMAIN, is_domain),
+            (REMOTE_NAME, is_remote_name),
+            (NAME, is_name),
+            (TAG, is_tag),
+            (DIGEST, is_digest),
+        ];
+        let long_tag = "a".repeat(129);
+        for (pattern, parser) in checks {
+            let re = anchored(pattern);
+            for input in INPUTS.iter().copied().chain([long_tag.as_str()]) {
+                assert_eq!(re.is_match(input), parser(input), "{pattern} on {input:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn reference_captures() {
+        let re = anchored(REFERENCE);
+        let captures = re
+            .captures("quay.io:443/foo/bar:v1@sha256:ffffffffffffffffffffffffffffffff")
+            .unwrap();
+        assert_eq!(&captures[1], "quay.io:443/foo/bar");
+        assert_eq!(&captures[2], "v1");
+        assert_eq!(&captures[3], "sha256:ffffffffffffffffffffffffffffffff");
+        assert!(!re.is_match("foo:bar:baz"));
+    }
+}
@@ -1,11 +1,13 @@
 //! Repository types of the distribution spec.
 
+use super::Pagination;
 use crate::error::OciSpecError;
 use derive_builder::Builder;
 use getset::{Getters, Setters};
 use serde::{Deserialize, Serialize};
 
 #[derive(Builder, Clone, Debug, Deserialize, Eq, Getters, Setters, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     pattern = "owned",
     setter(into, strip_option),
@@ -16,6 +18,39 @@ use serde::{Deserialize, Serialize};
 pub struct RepositoryList {
     /// The items of the RepositoryList.
     repositories: Vec<String>,
+
+    /// The continuation of the catalog announced by the registry in the
+    /// `Link` header of the response. It is not part of the response body.
+    #[serde(skip)]
+    #[builder(default)]
+    next: Option<Pagination>,
+}
+
+impl RepositoryList {
+    /// Returns the repositories in `namespace` or one of its nested
+    /// namespaces. A trailing slash of the namespace is ignored.
+    ///
+    /// ```
+    /// use oci_spec::distribution::RepositoryListBuilder;
+    ///
+    /// let list = RepositoryListBuilder::default()
+    ///     .repositories(vec!["library/busybox".into(), "libraryx/foo".into(), "library/sub/bar".into()])
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(list.repositories_in_namespace("library"), vec!["library/busybox", "library/sub/bar"]);
+    /// ```
+    pub fn repositories_in_namespace(&self, namespace: &str) -> Vec<&str> {
+        let namespace = namespace.trim_end_matches('/');
+        self.repositories
+            .iter()
+            .map(String::as_str)
+            .filter(|repository| {
+                repository
+                    .strip_prefix(namespace)
+                    .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -36,4 +71,31 @@ mod tests {
     fn repository_list_failure() {
         assert!(RepositoryListBuilder::default().build().is_err());
     }
+
+    #[test]
+    fn repositories_in_namespace() -> Result<()> {
+        let list = RepositoryListBuilder::default()
+            .repositories(vec![
+                "busybox".to_owned(),
+                "foo/bar".to_owned(),
+                "foo/baz/qux".to_owned(),
+                "foobar/baz".to_owned(),
+            ])
+            .next(Pagination::first(4))
+            .build()?;
+        assert_eq!(
+            list.repositories_in_namespace("foo/"),
+            vec!["foo/bar", "foo/baz/qux"]
+        );
+        assert_eq!(
+            list.repositories_in_namespace("foo/baz"),
+            vec!["foo/baz/qux"]
+        );
+        assert!(list.repositories_in_namespace("busybox").is_empty());
+        assert_eq!(
+            serde_json::to_string(&list)?,
+            r#"{"repositories":["busybox","foo/bar","foo/baz/qux","foobar/baz"]}"#
+        );
+        Ok(())
+    }
 }
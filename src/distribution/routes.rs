@@ -0,0 +1,313 @@
+//! Endpoints of the distribution API.
+//!
+//! ```
+//! use oci_spec::distribution::{routes::Routes, Reference};
+//!
+//! let reference: Reference = "quay.io/containers/podman:v5".parse().unwrap();
+//! let routes = Routes::new(&reference);
+//!
+//! assert_eq!(
+//!     routes.manifest().url(),
+//!     "https://quay.io/v2/containers/podman/manifests/v5"
+//! );
+//! assert_eq!(
+//!     routes.tags_list(Some(10), None).path_and_query(),
+//!     "/v2/containers/podman/tags/list?n=10"
+//! );
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+
+use super::{Pagination, Reference};
+use crate::image::{Digest, MediaType};
+
+/// An endpoint of the distribution API on a specific registry.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Route {
+    scheme: String,
+    host: String,
+    path: String,
+    query: Vec<(String, String)>,
+}
+
+impl Route {
+    /// Returns the path of the endpoint, e.g. `/v2/library/busybox/manifests/latest`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the unescaped query parameters of the endpoint.
+    pub fn query(&self) -> &[(String, String)] {
+        &self.query
+    }
+
+    /// Returns the host of the registry, including an optional port.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Returns the path followed by the escaped query string, if any.
+    pub fn path_and_query(&self) -> String {
+        let query = self
+            .query
+            .iter()
+            .map(|(k, v)| format!("{}={}", escape(k), escape(v)))
+            .collect::<Vec<_>>()
+            .join("&");
+        match query.is_empty() {
+            true => self.path.clone(),
+            false => format!("{}?{}", self.path, query),
+        }
+    }
+
+    /// Returns the full URL of the endpoint.
+    pub fn url(&self) -> String {
+        format!("{}://{}{}", self.scheme, self.host, self.path_and_query())
+    }
+
+    fn with_query(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.query.push((key.to_owned(), value.into()));
+        self
+    }
+}
+
+impl Display for Route {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.url())
+    }
+}
+
+/// Builds the [Route]s of the distribution API for the repository of a
+/// [Reference].
+///
+/// Requests are sent to the resolved registry of the reference. If the
+/// reference is pulled via a mirror, the original registry is passed with
+/// the `ns` query parameter.
+#[derive(Clone, Debug)]
+pub struct Routes<'a> {
+    reference: &'a Reference,
+    scheme: String,
+}
+
+impl<'a> Routes<'a> {
+    /// Creates the routes for `reference`, using `https`.
+    pub fn new(reference: &'a Reference) -> Self {
+        Self {
+            reference,
+            scheme: "https".to_owned(),
+        }
+    }
+
+    /// Sets the URL scheme, e.g. `http` for insecure registries.
+    pub fn scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.scheme = scheme.into();
+        self
+    }
+
+    /// The API version check endpoint, `/v2/`.
+    pub fn base(&self) -> Route {
+        self.route("/v2/".to_owned())
+    }
+
+    /// The manifest endpoint, `/v2/<name>/manifests/<reference>`. The digest
+    /// of the reference takes precedence over its tag.
+    pub fn manifest(&self) -> Route {
+        let target = self
+            .reference
+            .digest()
+            .or(self.reference.tag())
+            .unwrap_or("latest");
+        self.repository_route(&format!("manifests/{target}"))
+    }
+
+    /// The blob endpoint, `/v2/<name>/blobs/<digest>`.
+    pub fn blob(&self, digest: &Digest) -> Route {
+        self.repository_route(&format!("blobs/{digest}"))
+    }
+
+    /// The endpoint to start a blob upload, `/v2/<name>/blobs/uploads/`.
+    pub fn blob_upload(&self) -> Route {
+        self.repository_route("blobs/uploads/")
+    }
+
+    /// The endpoint to upload a blob in a single request,
+    /// `/v2/<name>/blobs/uploads/?digest=<digest>`.
+    pub fn blob_upload_monolithic(&self, digest: &Digest) -> Route {
+        self.blob_upload().with_query("digest", digest.to_string())
+    }
+
+    /// The endpoint to mount a blob from the repository `from` of the same
+    /// registry, `/v2/<name>/blobs/uploads/?mount=<digest>&from=<from>`.
+    pub fn blob_mount(&self, digest: &Digest, from: &str) -> Route {
+        self.blob_upload()
+            .with_query("mount", digest.to_string())
+            .with_query("from", from)
+    }
+
+    /// The tag listing endpoint, `/v2/<name>/tags/list`, optionally limited
+    /// to `n` results after the tag `last`.
+    pub fn tags_list(&self, n: Option<usize>, last: Option<&str>) -> Route {
+        let mut route = self.repository_route("tags/list");
+        if let Some(n) = n {
+            route = route.with_query("n", n.to_string());
+        }
+        if let Some(last) = last {
+            route = route.with_query("last", last);
+        }
+        route
+    }
+
+    /// The tag listing endpoint, `/v2/<name>/tags/list`, for the page
+    /// described by `page`.
+    pub fn tags_page(&self, page: &Pagination) -> Route {
+        self.tags_list(page.n, page.last.as_deref())
+    }
+
+    /// The catalog endpoint, `/v2/_catalog`, of the registry for the page
+    /// described by `page`.
+    pub fn catalog(&self, page: &Pagination) -> Route {
+        let mut route = self.route("/v2/_catalog".to_owned());
+        route.query.extend(page.query());
+        route
+    }
+
+    /// The referrers endpoint, `/v2/<name>/referrers/<digest>`, optionally
+    /// filtered by `artifact_type`.
+    pub fn referrers(&self, digest: &Digest, artifact_type: Option<&MediaType>) -> Route {
+        let route = self.repository_route(&format!("referrers/{digest}"));
+        match artifact_type {
+            Some(artifact_type) => route.with_query("artifactType", artifact_type.to_string()),
+            None => route,
+        }
+    }
+
+    fn repository_route(&self, endpoint: &str) -> Route {
+        self.route(format!("/v2/{}/{endpoint}", self.reference.repository()))
+    }
+
+    fn route(&self, path: String) -> Route {
+        let route = Route {
+            scheme: self.scheme.clone(),
+            host: self.reference.resolve_registry().to_owned(),
+            path,
+            query: Vec::new(),
+        };
+        match self.reference.namespace() {
+            Some(ns) => route.with_query("ns", ns),
+            None => route,
+        }
+    }
+}
+
+/// Percent-encodes everything but the unreserved characters of RFC 3986.
+pub(super) fn escape(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    const DIGEST: &str = "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356";
+
+    #[test]
+    fn routes() {
+        let reference = Reference::from_str("busybox").unwrap();
+        let routes = Routes::new(&reference);
+        let digest = Digest::from_str(DIGEST).unwrap();
+
+        assert_eq!(routes.base().url(), "https://index.docker.io/v2/");
+        assert_eq!(
+            routes.manifest().path(),
+            "/v2/library/busybox/manifests/latest"
+        );
+        assert_eq!(
+            routes.blob(&digest).path(),
+            format!("/v2/library/busybox/blobs/{DIGEST}")
+        );
+        assert_eq!(
+            routes.blob_upload().path(),
+            "/v2/library/busybox/blobs/uploads/"
+        );
+        assert_eq!(
+            routes.blob_upload_monolithic(&digest).path_and_query(),
+            format!(
+                "/v2/library/busybox/blobs/uploads/?digest=sha256%3A{}",
+                digest.digest()
+            )
+        );
+        assert_eq!(
+            routes
+                .blob_mount(&digest, "library/alpine")
+                .path_and_query(),
+            format!(
+                "/v2/library/busybox/blobs/uploads/?mount=sha256%3A{}&from=library%2Falpine",
+                digest.digest()
+            )
+        );
+        assert_eq!(
+            routes.tags_list(Some(5), Some("1.36")).path_and_query(),
+            "/v2/library/busybox/tags/list?n=5&last=1.36"
+        );
+        assert_eq!(
+            routes
+                .referrers(
+                    &digest,
+                    Some(&MediaType::Other("application/spdx+json".into()))
+                )
+                .path_and_query(),
+            format!(
+                "/v2/library/busybox/referrers/{DIGEST}?artifactType=application%2Fspdx%2Bjson"
+            )
+        );
+    }
+
+    #[test]
+    fn paginated() {
+        let reference = Reference::from_str("localhost:5000/repo").unwrap();
+        let routes = Routes::new(&reference);
+        let page = Pagination {
+            n: Some(2),
+            last: Some("a/b".to_owned()),
+        };
+        assert_eq!(
+            routes.catalog(&page).path_and_query(),
+            "/v2/_catalog?n=2&last=a%2Fb"
+        );
+        assert_eq!(
+            routes.tags_page(&Pagination::first(2)).path_and_query(),
+            "/v2/repo/tags/list?n=2"
+        );
+    }
+
+    #[test]
+    fn manifest_by_digest() {
+        let reference = Reference::from_str(&format!("localhost:5000/repo:tag@{DIGEST}")).unwrap();
+        let routes = Routes::new(&reference).scheme("http");
+        assert_eq!(
+            routes.manifest().url(),
+            format!("http://localhost:5000/v2/repo/manifests/{DIGEST}")
+        );
+    }
+
+    #[test]
+    fn mirror_namespace() {
+        let mut reference = Reference::from_str("quay.io/containers/podman").unwrap();
+        reference.set_mirror_registry("mirror.example.com".to_owned());
+        let route = Routes::new(&reference).tags_list(Some(1), None);
+        assert_eq!(route.host(), "mirror.example.com");
+        assert_eq!(
+            route.url(),
+            "https://mirror.example.com/v2/containers/podman/tags/list?ns=quay.io&n=1"
+        );
+    }
+}
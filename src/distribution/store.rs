@@ -0,0 +1,227 @@
+//! Transport-agnostic traits for storing and retrieving content.
+//!
+//! These traits are defined against the types of this crate, so that registry
+//! clients and local stores, e.g. an OCI image layout on disk, can be used
+//! interchangeably.
+
+use std::io::Read;
+
+use super::Reference;
+use crate::{
+    error::OciSpecError,
+    image::{Descriptor, Digest, ImageIndex, ImageManifest, MediaType},
+};
+
+/// A store of content-addressed blobs, like layers and configurations.
+///
+/// Blobs are scoped to the repository of the passed [Reference]. Stores which
+/// do not distinguish between repositories may ignore it.
+pub trait BlobStore {
+    /// The error returned by the store.
+    type Error: From<OciSpecError>;
+
+    /// The reader returned for the content of a blob.
+    type Reader: Read;
+
+    /// Returns the size of the blob with the given digest, or `None` if the
+    /// store does not contain it.
+    fn stat_blob(&self, reference: &Reference, digest: &Digest)
+        -> Result<Option<u64>, Self::Error>;
+
+    /// Returns a reader for the content of the blob described by `descriptor`.
+    fn get_blob(
+        &self,
+        reference: &Reference,
+        descriptor: &Descriptor,
+    ) -> Result<Self::Reader, Self::Error>;
+
+    /// Stores the blob described by `descriptor`, reading it from `content`.
+    /// Implementations SHOULD verify the digest and size of the content
+    /// against the descriptor.
+    fn put_blob(
+        &mut self,
+        reference: &Reference,
+        descriptor: &Descriptor,
+        content: &mut dyn Read,
+    ) -> Result<(), Self::Error>;
+
+    /// Returns true if the store contains the blob with the given digest.
+    fn has_blob(&self, reference: &Reference, digest: &Digest) -> Result<bool, Self::Error> {
+        Ok(self.stat_blob(reference, digest)?.is_some())
+    }
+}
+
+/// A store of manifests, addressable by tag or digest.
+pub trait ManifestStore {
+    /// The error returned by the store.
+    type Error: From<OciSpecError>;
+
+    /// Returns the descriptor and raw content of the manifest the
+    /// [Reference] points to. The digest of the reference takes precedence
+    /// over its tag. `accept` lists the media types the caller is able to
+    /// handle, an empty list accepts all of them.
+    fn get_manifest(
+        &self,
+        reference: &Reference,
+        accept: &[MediaType],
+    ) -> Result<(Descriptor, Vec<u8>), Self::Error>;
+
+    /// Stores the raw manifest content under the [Reference] and returns its
+    /// descriptor.
+    fn put_manifest(
+        &mut self,
+        reference: &Reference,
+        media_type: &MediaType,
+        content: &[u8],
+    ) -> Result<Descriptor, Self::Error>;
+
+    /// Resolves the [Reference] to the descriptor of its manifest.
+    fn resolve(&self, reference: &Reference) -> Result<Descriptor, Self::Error> {
+        Ok(self.get_manifest(reference, &[])?.0)
+    }
+
+    /// Returns the [ImageManifest] the [Reference] points to.
+    fn get_image_manifest(
+        &self,
+        reference: &Reference,
+    ) -> Result<(Descriptor, ImageManifest), Self::Error> {
+        let (descriptor, content) = self.get_manifest(reference, &[MediaType::ImageManifest])?;
+        let manifest = ImageManifest::from_reader(content.as_slice())?;
+        Ok((descriptor, manifest))
+    }
+
+    /// Returns the [ImageIndex] the [Reference] points to.
+    fn get_image_index(
+        &self,
+        reference: &Reference,
+    ) -> Result<(Descriptor, ImageIndex), Self::Error> {
+        let (descriptor, content) = self.get_manifest(reference, &[MediaType::ImageIndex])?;
+        let index = ImageIndex::from_reader(content.as_slice())?;
+        Ok((descriptor, index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::Cursor;
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::error::{oci_error, Result};
+    use crate::image::ImageManifestBuilder;
+
+    const DIGEST: &str = "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356";
+
+    #[derive(Default)]
+    struct MemoryStore {
+        blobs: HashMap<Digest, Vec<u8>>,
+        manifests: HashMap<String, (Descriptor, Vec<u8>)>,
+    }
+
+    impl BlobStore for MemoryStore {
+        type Error = OciSpecError;
+        type Reader = Cursor<Vec<u8>>;
+
+        fn stat_blob(&self, _: &Reference, digest: &Digest) -> Result<Option<u64>> {
+            Ok(self.blobs.get(digest).map(|b| b.len() as u64))
+        }
+
+        fn get_blob(&self, _: &Reference, descriptor: &Descriptor) -> Result<Self::Reader> {
+            match self.blobs.get(descriptor.digest()) {
+                Some(blob) => Ok(Cursor::new(blob.clone())),
+                None => Err(oci_error("blob unknown")),
+            }
+        }
+
+        fn put_blob(
+            &mut self,
+            _: &Reference,
+            descriptor: &Descriptor,
+            content: &mut dyn Read,
+        ) -> Result<()> {
+            let mut blob = Vec::new();
+            content.read_to_end(&mut blob)?;
+            if blob.len() as u64 != descriptor.size() {
+                return Err(oci_error("size invalid"));
+            }
+            self.blobs.insert(descriptor.digest().clone(), blob);
+            Ok(())
+        }
+    }
+
+    impl ManifestStore for MemoryStore {
+        type Error = OciSpecError;
+
+        fn get_manifest(
+            &self,
+            reference: &Reference,
+            accept: &[MediaType],
+        ) -> Result<(Descriptor, Vec<u8>)> {
+            let (descriptor, content) = self
+                .manifests
+                .get(&reference.whole())
+                .ok_or_else(|| oci_error("manifest unknown"))?;
+            if !accept.is_empty() && !accept.contains(descriptor.media_type()) {
+                return Err(oci_error("manifest invalid"));
+            }
+            Ok((descriptor.clone(), content.clone()))
+        }
+
+        fn put_manifest(
+            &mut self,
+            reference: &Reference,
+            media_type: &MediaType,
+            content: &[u8],
+        ) -> Result<Descriptor> {
+            let descriptor = Descriptor::new(
+                media_type.clone(),
+                content.len() as u64,
+                Digest::from_str(DIGEST)?,
+            );
+            self.manifests
+                .insert(reference.whole(), (descriptor.clone(), content.to_owned()));
+            Ok(descriptor)
+        }
+    }
+
+    #[test]
+    fn blob_store() -> Result<()> {
+        let mut store = MemoryStore::default();
+        let reference = Reference::from_str("busybox").unwrap();
+        let descriptor = Descriptor::new(MediaType::ImageLayerGzip, 4, Digest::from_str(DIGEST)?);
+
+        assert!(!store.has_blob(&reference, descriptor.digest())?);
+        store.put_blob(&reference, &descriptor, &mut "blob".as_bytes())?;
+        assert_eq!(store.stat_blob(&reference, descriptor.digest())?, Some(4));
+
+        let mut content = String::new();
+        store
+            .get_blob(&reference, &descriptor)?
+            .read_to_string(&mut content)?;
+        assert_eq!(content, "blob");
+        Ok(())
+    }
+
+    #[test]
+    fn manifest_store() -> Result<()> {
+        let mut store = MemoryStore::default();
+        let reference = Reference::from_str("busybox").unwrap();
+        let config = Descriptor::new(MediaType::ImageConfig, 2, Digest::from_str(DIGEST)?);
+        let manifest = ImageManifestBuilder::default()
+            .schema_version(2u32)
+            .config(config)
+            .layers(vec![])
+            .build()?;
+
+        let content = manifest.to_string()?;
+        let descriptor =
+            store.put_manifest(&reference, &MediaType::ImageManifest, content.as_bytes())?;
+        assert_eq!(store.resolve(&reference)?, descriptor);
+
+        let (_, loaded) = store.get_image_manifest(&reference)?;
+        assert_eq!(loaded, manifest);
+        assert!(store.get_image_index(&reference).is_err());
+        Ok(())
+    }
+}
@@ -1,11 +1,13 @@
 //! Tag types of the distribution spec.
 
-use crate::error::OciSpecError;
+use super::Pagination;
+use crate::{error::OciSpecError, semver::SemVer};
 use derive_builder::Builder;
 use getset::{Getters, Setters};
 use serde::{Deserialize, Serialize};
 
 #[derive(Builder, Clone, Debug, Deserialize, Eq, Getters, Setters, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     pattern = "owned",
     setter(into, strip_option),
@@ -19,6 +21,56 @@ pub struct TagList {
 
     /// Each tags on the repository.
     tags: Vec<String>,
+
+    /// The continuation of the listing announced by the registry in the
+    /// `Link` header of the response. It is not part of the response body.
+    #[serde(skip)]
+    #[builder(default)]
+    next: Option<Pagination>,
+}
+
+impl TagList {
+    /// Returns the tags which are semantic versions, optionally prefixed
+    /// with `v`, in ascending order of precedence. Other tags are omitted.
+    /// Tags which only differ in build metadata keep their order.
+    ///
+    /// ```
+    /// use oci_spec::distribution::TagListBuilder;
+    ///
+    /// let list = TagListBuilder::default()
+    ///     .name("busybox")
+    ///     .tags(vec!["latest".into(), "v1.10.0".into(), "1.9.0".into(), "1.10.0-rc1".into()])
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(list.tags_sorted_semver(), vec!["1.9.0", "1.10.0-rc1", "v1.10.0"]);
+    /// ```
+    pub fn tags_sorted_semver(&self) -> Vec<&str> {
+        let mut tags: Vec<(SemVer, &str)> = self
+            .tags
+            .iter()
+            .filter_map(|tag| {
+                let version = SemVer::parse(tag.strip_prefix('v').unwrap_or(tag))?;
+                Some((version, tag.as_str()))
+            })
+            .collect();
+        tags.sort_by(|(a, _), (b, _)| a.cmp_precedence(b));
+        tags.into_iter().map(|(_, tag)| tag).collect()
+    }
+
+    /// Returns the tags starting with `prefix`.
+    pub fn tags_with_prefix(&self, prefix: &str) -> Vec<&str> {
+        self.tags_matching(|tag| tag.starts_with(prefix))
+    }
+
+    /// Returns the tags for which `predicate` returns true, e.g. the tags
+    /// matching a regular expression.
+    pub fn tags_matching(&self, mut predicate: impl FnMut(&str) -> bool) -> Vec<&str> {
+        self.tags
+            .iter()
+            .map(String::as_str)
+            .filter(|tag| predicate(tag))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -41,4 +93,57 @@ mod tests {
     fn tag_list_failure() {
         assert!(TagListBuilder::default().build().is_err());
     }
+
+    #[test]
+    fn tag_list_next_not_serialized() -> Result<()> {
+        let list = TagListBuilder::default()
+            .name("name")
+            .tags(vec!["a".to_owned()])
+            .next(Pagination::first(1))
+            .build()?;
+        let json = serde_json::to_string(&list)?;
+        assert_eq!(json, r#"{"name":"name","tags":["a"]}"#);
+        let parsed: TagList = serde_json::from_str(&json)?;
+        assert_eq!(parsed.next(), &None);
+        Ok(())
+    }
+
+    #[test]
+    fn tags_sorted_semver() -> Result<()> {
+        let list = TagListBuilder::default()
+            .name("name")
+            .tags(vec![
+                "v2.0.0".to_owned(),
+                "1.0.0+b".to_owned(),
+                "1.0.0-rc.1".to_owned(),
+                "v1.0.0+a".to_owned(),
+                "1.2".to_owned(),
+                "vv1.0.0".to_owned(),
+            ])
+            .build()?;
+        assert_eq!(
+            list.tags_sorted_semver(),
+            vec!["1.0.0-rc.1", "1.0.0+b", "v1.0.0+a", "v2.0.0"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tag_filters() -> Result<()> {
+        let list = TagListBuilder::default()
+            .name("name")
+            .tags(vec![
+                "v1".to_owned(),
+                "v2-alpine".to_owned(),
+                "edge".to_owned(),
+            ])
+            .build()?;
+        assert_eq!(list.tags_with_prefix("v"), vec!["v1", "v2-alpine"]);
+        assert_eq!(
+            list.tags_matching(|tag| tag.ends_with("alpine")),
+            vec!["v2-alpine"]
+        );
+        assert!(list.tags_sorted_semver().is_empty());
+        Ok(())
+    }
 }
@@ -0,0 +1,171 @@
+//! Blob upload sessions of the distribution API.
+
+use super::{headers::ContentRange, routes::escape};
+use crate::{
+    error::{oci_error, Result},
+    image::Digest,
+};
+
+/// The state of a blob upload, as started with a `POST` to
+/// `/v2/<name>/blobs/uploads/`.
+///
+/// The registry returns the location to continue the upload at with every
+/// response, as well as the range of bytes it received so far.
+///
+/// ```
+/// use std::str::FromStr;
+/// use oci_spec::distribution::BlobUploadSession;
+/// use oci_spec::image::Digest;
+///
+/// let mut session = BlobUploadSession::new("/v2/foo/blobs/uploads/1234", None);
+/// assert_eq!(session.uuid(), Some("1234"));
+/// assert_eq!(session.content_range(1024), "0-1023");
+///
+/// session.update("/v2/foo/blobs/uploads/1234?state=abc", Some("0-1023")).unwrap();
+/// assert_eq!(session.offset(), 1024);
+///
+/// let digest = Digest::from_str(
+///     "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356",
+/// ).unwrap();
+/// assert_eq!(
+///     session.completion_url(&digest),
+///     "/v2/foo/blobs/uploads/1234?state=abc&digest=sha256%3Ac2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356"
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlobUploadSession {
+    location: String,
+    uuid: Option<String>,
+    offset: u64,
+}
+
+impl BlobUploadSession {
+    /// Creates a session from the `Location` and optional
+    /// `Docker-Upload-UUID` headers of the response starting the upload. If
+    /// no UUID is given, it is taken from the last path segment of the
+    /// location.
+    pub fn new(location: impl Into<String>, uuid: Option<&str>) -> Self {
+        let location = location.into();
+        let uuid = uuid.map(str::to_owned).or_else(|| uuid_of(&location));
+        Self {
+            location,
+            uuid,
+            offset: 0,
+        }
+    }
+
+    /// Returns the URL to continue the upload at.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// Returns the UUID of the upload, if known.
+    pub fn uuid(&self) -> Option<&str> {
+        self.uuid.as_deref()
+    }
+
+    /// Returns the number of bytes the registry received so far, which is
+    /// the offset of the next chunk.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Updates the session from the `Location` and `Range` headers of a
+    /// response to an upload request.
+    pub fn update(&mut self, location: impl Into<String>, range: Option<&str>) -> Result<()> {
+        self.location = location.into();
+        if let Some(uuid) = uuid_of(&self.location) {
+            self.uuid.get_or_insert(uuid);
+        }
+        if let Some(range) = range {
+            let (_, end) = parse_range(range)?;
+            self.offset = end + 1;
+        }
+        Ok(())
+    }
+
+    /// Returns the value of the `Content-Range` header for uploading the
+    /// next chunk of `len` bytes, which must not be empty.
+    pub fn content_range(&self, len: u64) -> String {
+        ContentRange::at(self.offset, len).to_string()
+    }
+
+    /// Returns the query parameters which complete the upload of the blob
+    /// with the given digest.
+    pub fn completion_query(digest: &Digest) -> Vec<(String, String)> {
+        vec![("digest".to_owned(), digest.to_string())]
+    }
+
+    /// Returns the URL to complete the upload of the blob with the given
+    /// digest at with a `PUT` request.
+    pub fn completion_url(&self, digest: &Digest) -> String {
+        let separator = match self.location.contains('?') {
+            true => '&',
+            false => '?',
+        };
+        let query = Self::completion_query(digest)
+            .iter()
+            .map(|(k, v)| format!("{}={}", escape(k), escape(v)))
+            .collect::<Vec<_>>()
+            .join("&");
+        format!("{}{separator}{query}", self.location)
+    }
+}
+
+/// Parses the value of a `Range` header returned for an upload, e.g.
+/// `0-1023`, into the inclusive start and end offsets.
+pub fn parse_range(range: &str) -> Result<(u64, u64)> {
+    let range = range.trim();
+    let range = range.strip_prefix("bytes=").unwrap_or(range);
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| oci_error(format!("invalid range: {range}")))?;
+    match (start.parse(), end.parse()) {
+        (Ok(start), Ok(end)) if start <= end => Ok((start, end)),
+        _ => Err(oci_error(format!("invalid range: {range}"))),
+    }
+}
+
+fn uuid_of(location: &str) -> Option<String> {
+    let path = location.split(['?', '#']).next()?;
+    let (prefix, uuid) = path.trim_end_matches('/').rsplit_once('/')?;
+    match prefix.ends_with("/blobs/uploads") && !uuid.is_empty() {
+        true => Some(uuid.to_owned()),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upload_session() -> Result<()> {
+        let mut session = BlobUploadSession::new(
+            "https://registry.example.com/v2/foo/blobs/uploads/",
+            Some("abc"),
+        );
+        assert_eq!(session.uuid(), Some("abc"));
+        assert_eq!(session.offset(), 0);
+
+        session.update(
+            "https://registry.example.com/v2/foo/blobs/uploads/abc",
+            Some("0-99"),
+        )?;
+        assert_eq!(session.offset(), 100);
+        assert_eq!(session.content_range(50), "100-149");
+        assert!(session.update("x", Some("10")).is_err());
+        assert!(session.update("x", Some("10-5")).is_err());
+
+        let session = BlobUploadSession::new("/v2/foo/blobs/uploads/", None);
+        assert_eq!(session.uuid(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn range() {
+        assert_eq!(parse_range("0-1023").unwrap(), (0, 1023));
+        assert_eq!(parse_range("bytes=0-0").unwrap(), (0, 0));
+        assert!(parse_range("a-b").is_err());
+    }
+}
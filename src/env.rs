@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+/// Accessors of environment variables in the `NAME=value` form, implemented
+/// for the `env` fields of the runtime `Process` and the image `Config`.
+///
+/// If a variable is set multiple times, the last value takes effect, as when
+/// Docker and containerd merge environment lists. Note that the list is passed
+/// to `execve` as is, and `getenv` of glibc returns the first value, so
+/// [EnvExt::set_env] removes duplicates to keep both views consistent.
+/// Entries without `=` have no value.
+///
+/// ```
+/// use oci_spec::EnvExt;
+///
+/// let mut env = Some(vec!["PATH=/bin".to_string(), "TERM=xterm".to_string()]);
+/// env.set_env("PATH", "/usr/bin:/bin");
+/// env.set_env("HOME", "/root");
+/// assert_eq!(env.get_env("PATH"), Some("/usr/bin:/bin"));
+/// assert_eq!(env.remove_env("TERM").as_deref(), Some("xterm"));
+/// assert_eq!(
+///     env.unwrap(),
+///     ["PATH=/usr/bin:/bin", "HOME=/root"],
+/// );
+/// ```
+pub trait EnvExt {
+    /// Returns the value of the variable `name`, if it is set.
+    fn get_env(&self, name: &str) -> Option<&str>;
+
+    /// Sets the variable `name` to `value`, replacing all previous values.
+    /// The variable keeps the position of its first occurrence, new variables
+    /// are appended.
+    fn set_env(&mut self, name: &str, value: &str);
+
+    /// Removes all occurrences of the variable `name` and returns its value,
+    /// if it was set.
+    fn remove_env(&mut self, name: &str) -> Option<String>;
+
+    /// Returns all variables with a value as a map.
+    fn env_map(&self) -> HashMap<&str, &str>;
+}
+
+impl EnvExt for Option<Vec<String>> {
+    fn get_env(&self, name: &str) -> Option<&str> {
+        self.iter()
+            .flatten()
+            .rev()
+            .filter_map(|var| var.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+
+    fn set_env(&mut self, name: &str, value: &str) {
+        let env = self.get_or_insert_with(Vec::new);
+        let var = format!("{name}={value}");
+        match env.iter().position(|v| env_key(v) == name) {
+            Some(first) => {
+                let mut duplicate = false;
+                env.retain(|v| {
+                    let keep = !duplicate || env_key(v) != name;
+                    duplicate |= env_key(v) == name;
+                    keep
+                });
+                env[first] = var;
+            }
+            None => env.push(var),
+        }
+    }
+
+    fn remove_env(&mut self, name: &str) -> Option<String> {
+        let value = self.get_env(name).map(str::to_owned);
+        if let Some(env) = self {
+            env.retain(|v| env_key(v) != name);
+        }
+        value
+    }
+
+    fn env_map(&self) -> HashMap<&str, &str> {
+        self.iter()
+            .flatten()
+            .filter_map(|var| var.split_once('='))
+            .collect()
+    }
+}
+
+/// Returns the name of the environment variable `var`.
+pub(crate) fn env_key(var: &str) -> &str {
+    var.split_once('=').map_or(var, |(key, _)| key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_ext() {
+        let mut env = Some(
+            ["A=1", "B=2", "A=3", "C", "D=x=y"]
+                .map(String::from)
+                .to_vec(),
+        );
+        assert_eq!(env.get_env("A"), Some("3"));
+        assert_eq!(env.get_env("C"), None);
+        assert_eq!(env.get_env("D"), Some("x=y"));
+        assert_eq!(env.env_map().len(), 3);
+        assert_eq!(env.env_map()["A"], "3");
+
+        env.set_env("A", "4");
+        env.set_env("C", "5");
+        assert_eq!(env.as_deref().unwrap(), ["A=4", "B=2", "C=5", "D=x=y"]);
+
+        assert_eq!(env.remove_env("B").as_deref(), Some("2"));
+        assert_eq!(env.remove_env("B"), None);
+
+        let mut unset = None;
+        assert_eq!(unset.get_env("A"), None);
+        unset.set_env("A", "");
+        assert_eq!(unset.get_env("A"), Some(""));
+    }
+}
@@ -1,6 +1,6 @@
 //! Error types of the crate.
 
-use std::{borrow::Cow, io};
+use std::{borrow::Cow, io, path::Path};
 use thiserror::Error;
 
 /// Spezialized result type for oci spec operations. It is
@@ -12,6 +12,7 @@ pub type Result<T> = std::result::Result<T, OciSpecError>;
 
 /// Error type for oci spec errors.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum OciSpecError {
     /// Will be returned if an error occurs that cannot
     /// be mapped to a more specialized error variant.
@@ -25,12 +26,134 @@ pub enum OciSpecError {
 
     /// Will be returned when an error happens during
     /// serialization or deserialization.
-    #[error("serde failed")]
+    #[error("serde failed: {0}")]
     SerDe(#[from] serde_json::Error),
 
     /// Builder specific errors.
     #[error("uninitialized field")]
     Builder(#[from] derive_builder::UninitializedFieldError),
+
+    /// Wraps another error together with the file path or field
+    /// it was caused by. The wrapped error is displayed after the
+    /// context and is available as its [source](std::error::Error::source).
+    #[error("{context}: {source}")]
+    Context {
+        /// The offending file path or field.
+        context: String,
+        /// The underlying error.
+        source: Box<OciSpecError>,
+    },
+
+    /// Several independent errors, e.g. of all failed transforms of a
+    /// pipeline collecting its errors.
+    #[error("{}", display_all(.0))]
+    Multiple(Vec<OciSpecError>),
+}
+
+/// Joins the errors, each followed by the sources of its underlying error.
+fn display_all(errors: &[OciSpecError]) -> String {
+    errors
+        .iter()
+        .map(|err| {
+            let mut message = err.to_string();
+            let mut source = std::error::Error::source(err.root());
+            while let Some(err) = source {
+                message = format!("{message}: {err}");
+                source = err.source();
+            }
+            message
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// The category of an [OciSpecError], independent of any attached
+/// context.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// See [OciSpecError::Other].
+    Other,
+    /// See [OciSpecError::Io].
+    Io,
+    /// See [OciSpecError::SerDe].
+    SerDe,
+    /// See [OciSpecError::Builder].
+    Builder,
+    /// See [OciSpecError::Multiple].
+    Multiple,
+}
+
+impl OciSpecError {
+    /// Returns the kind of the error. Attached context is looked through,
+    /// so the kind is the one of the underlying error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Other(_) => ErrorKind::Other,
+            Self::Io(_) => ErrorKind::Io,
+            Self::SerDe(_) => ErrorKind::SerDe,
+            Self::Builder(_) => ErrorKind::Builder,
+            Self::Context { source, .. } => source.kind(),
+            Self::Multiple(_) => ErrorKind::Multiple,
+        }
+    }
+
+    /// Attaches the offending file path or field to the error.
+    ///
+    /// ```
+    /// use std::error::Error;
+    /// use oci_spec::{ErrorKind, OciSpecError};
+    ///
+    /// let err = OciSpecError::Other("invalid value".into()).context("process.args");
+    /// assert_eq!(err.kind(), ErrorKind::Other);
+    /// assert_eq!(err.to_string(), "process.args: invalid value");
+    /// assert_eq!(err.source().unwrap().to_string(), "invalid value");
+    /// ```
+    pub fn context(self, context: impl Into<String>) -> Self {
+        Self::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Attaches the offending file path to the error.
+    pub fn with_path(self, path: impl AsRef<Path>) -> Self {
+        self.context(path.as_ref().display().to_string())
+    }
+
+    /// Returns the outermost context attached to the error, if any.
+    pub fn get_context(&self) -> Option<&str> {
+        match self {
+            Self::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying error with all attached context removed.
+    pub fn root(&self) -> &OciSpecError {
+        match self {
+            Self::Context { source, .. } => source.root(),
+            _ => self,
+        }
+    }
+}
+
+impl From<OciSpecError> for io::Error {
+    fn from(err: OciSpecError) -> Self {
+        let kind = match err.root() {
+            OciSpecError::Io(e) => e.kind(),
+            OciSpecError::SerDe(e) => match e.io_error_kind() {
+                Some(kind) => kind,
+                None => io::ErrorKind::InvalidData,
+            },
+            OciSpecError::Builder(_) => io::ErrorKind::InvalidInput,
+            _ => io::ErrorKind::Other,
+        };
+        match err {
+            OciSpecError::Io(e) => e,
+            err => io::Error::new(kind, err),
+        }
+    }
 }
 
 pub(crate) fn oci_error<'a, M>(message: M) -> OciSpecError
@@ -43,3 +166,41 @@ where
         Cow::Owned(s) => OciSpecError::Other(s),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn kind_looks_through_context() {
+        let err = OciSpecError::from(io::Error::from(io::ErrorKind::NotFound))
+            .with_path("/tmp/config.json")
+            .context("loading bundle");
+        assert_eq!(err.kind(), ErrorKind::Io);
+        assert_eq!(err.get_context(), Some("loading bundle"));
+        assert!(matches!(err.root(), OciSpecError::Io(_)));
+        assert_eq!(
+            err.to_string(),
+            "loading bundle: /tmp/config.json: io operation failed"
+        );
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            "/tmp/config.json: io operation failed"
+        );
+    }
+
+    #[test]
+    fn into_io_error() {
+        let err: io::Error = OciSpecError::from(io::Error::from(io::ErrorKind::NotFound)).into();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        let serde_err = serde_json::from_str::<u32>("\"a\"").unwrap_err();
+        let err: io::Error = OciSpecError::from(serde_err).with_path("a.json").into();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("a.json: serde failed:"));
+
+        let err: io::Error = oci_error("bad").into();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+    }
+}
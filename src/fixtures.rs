@@ -0,0 +1,56 @@
+//! Known-good specification documents for interoperability tests.
+//!
+//! These are the documents the crate is tested against, embedded as raw
+//! bytes, so that downstream crates can test against them without copying
+//! them.
+//!
+//! ```
+//! use oci_spec::{fixtures, image::ImageManifest};
+//!
+//! let manifest = ImageManifest::from_reader(fixtures::IMAGE_MANIFEST).unwrap();
+//! assert_eq!(manifest.layers().len(), 3);
+//! ```
+
+/// An image configuration.
+pub const IMAGE_CONFIG: &[u8] = include_bytes!("../test/data/config.json");
+
+/// An image manifest.
+pub const IMAGE_MANIFEST: &[u8] = include_bytes!("../test/data/manifest.json");
+
+/// An image index.
+pub const IMAGE_INDEX: &[u8] = include_bytes!("../test/data/index.json");
+
+/// An artifact manifest.
+pub const ARTIFACT_MANIFEST: &[u8] = include_bytes!("../test/data/artifact_manifest.json");
+
+/// A runtime configuration of a Linux container.
+pub const RUNTIME_CONFIG: &[u8] = include_bytes!("runtime/test/fixture/sample.json");
+
+/// A runtime configuration of a Windows container.
+pub const RUNTIME_CONFIG_WINDOWS: &[u8] =
+    include_bytes!("runtime/test/fixture/sample_windows.json");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn image_fixtures() {
+        use crate::image::{ArtifactManifest, ImageConfiguration, ImageIndex, ImageManifest};
+
+        ImageConfiguration::from_reader(IMAGE_CONFIG).expect("image config");
+        ImageManifest::from_reader(IMAGE_MANIFEST).expect("image manifest");
+        ImageIndex::from_reader(IMAGE_INDEX).expect("image index");
+        ArtifactManifest::from_reader(ARTIFACT_MANIFEST).expect("artifact manifest");
+    }
+
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn runtime_fixtures() {
+        use crate::runtime::Spec;
+
+        serde_json::from_slice::<Spec>(RUNTIME_CONFIG).expect("runtime config");
+        serde_json::from_slice::<Spec>(RUNTIME_CONFIG_WINDOWS).expect("windows runtime config");
+    }
+}
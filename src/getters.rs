@@ -0,0 +1,37 @@
+//! Helpers shared by the getters of the crate.
+
+/// Generates getters returning `Option<&T>` for optional fields, in addition
+/// to the getset getters returning `&Option<T>`.
+///
+/// Each getter is listed with the field it borrows and the type the field is
+/// borrowed as, for example `str` for a `String`, `[T]` for a `Vec<T>` or
+/// `Path` for a `PathBuf`:
+///
+/// ```text
+/// option_getters! {
+///     Spec {
+///         hostname_ref => hostname: str,
+///         mounts_ref => mounts: [Mount],
+///     }
+/// }
+///
+/// let hostname: Option<&str> = spec.hostname_ref();
+/// ```
+#[allow(unused_macros)]
+macro_rules! option_getters {
+    (
+        $target:ident {
+            $($(#[$meta:meta])* $name:ident => $field:ident: $value:ty),* $(,)?
+        }
+    ) => {
+        impl $target {
+            $(
+                $(#[$meta])*
+                #[doc = concat!("Returns the `", stringify!($field), "` field, if it is set.")]
+                pub fn $name(&self) -> Option<&$value> {
+                    self.$field.as_ref().map(std::borrow::Borrow::borrow)
+                }
+            )*
+        }
+    };
+}
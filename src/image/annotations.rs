@@ -1,3 +1,7 @@
+use super::ImageConfiguration;
+use crate::error::{oci_error, OciSpecError, Result};
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
 /// AnnotationCreated is the annotation key for the date and time on which the
 /// image was built (date-time string as defined by RFC 3339).
 pub const ANNOTATION_CREATED: &str = "org.opencontainers.image.created";
@@ -55,3 +59,484 @@ pub const ANNOTATION_BASE_IMAGE_DIGEST: &str = "org.opencontainers.image.base.di
 /// AnnotationBaseImageName is the annotation key for the image reference of the
 /// image's base image.
 pub const ANNOTATION_BASE_IMAGE_NAME: &str = "org.opencontainers.image.base.name";
+
+/// AnnotationArtifactCreated is the annotation key for the date and time on
+/// which an artifact was built (date-time string as defined by RFC 3339).
+/// It was defined for the artifact manifest and is still set by referrers
+/// producers on the manifests of artifacts.
+pub const ANNOTATION_ARTIFACT_CREATED: &str = "org.opencontainers.artifact.created";
+
+/// AnnotationArtifactDescription is the annotation key for the human-readable
+/// description of an artifact. Like [ANNOTATION_ARTIFACT_CREATED], it is set
+/// on the manifests of artifacts returned by the referrers API.
+pub const ANNOTATION_ARTIFACT_DESCRIPTION: &str = "org.opencontainers.artifact.description";
+
+/// The pre-defined annotation keys of the image spec.
+///
+/// ```
+/// use oci_spec::image::{PredefinedAnnotation, ANNOTATION_BASE_IMAGE_NAME};
+///
+/// let key: PredefinedAnnotation = ANNOTATION_BASE_IMAGE_NAME.parse().unwrap();
+/// assert_eq!(key, PredefinedAnnotation::BaseImageName);
+/// assert_eq!(key.as_str(), ANNOTATION_BASE_IMAGE_NAME);
+/// assert!(PredefinedAnnotation::ALL.contains(&key));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PredefinedAnnotation {
+    /// [ANNOTATION_CREATED]
+    Created,
+    /// [ANNOTATION_AUTHORS]
+    Authors,
+    /// [ANNOTATION_URL]
+    Url,
+    /// [ANNOTATION_DOCUMENTATION]
+    Documentation,
+    /// [ANNOTATION_SOURCE]
+    Source,
+    /// [ANNOTATION_VERSION]
+    Version,
+    /// [ANNOTATION_REVISION]
+    Revision,
+    /// [ANNOTATION_VENDOR]
+    Vendor,
+    /// [ANNOTATION_LICENSES]
+    Licenses,
+    /// [ANNOTATION_REF_NAME]
+    RefName,
+    /// [ANNOTATION_TITLE]
+    Title,
+    /// [ANNOTATION_DESCRIPTION]
+    Description,
+    /// [ANNOTATION_BASE_IMAGE_DIGEST]
+    BaseImageDigest,
+    /// [ANNOTATION_BASE_IMAGE_NAME]
+    BaseImageName,
+    /// [ANNOTATION_ARTIFACT_CREATED]
+    ArtifactCreated,
+    /// [ANNOTATION_ARTIFACT_DESCRIPTION]
+    ArtifactDescription,
+}
+
+impl PredefinedAnnotation {
+    /// All pre-defined annotation keys.
+    pub const ALL: [Self; 16] = [
+        Self::Created,
+        Self::Authors,
+        Self::Url,
+        Self::Documentation,
+        Self::Source,
+        Self::Version,
+        Self::Revision,
+        Self::Vendor,
+        Self::Licenses,
+        Self::RefName,
+        Self::Title,
+        Self::Description,
+        Self::BaseImageDigest,
+        Self::BaseImageName,
+        Self::ArtifactCreated,
+        Self::ArtifactDescription,
+    ];
+
+    /// Returns the annotation key.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => ANNOTATION_CREATED,
+            Self::Authors => ANNOTATION_AUTHORS,
+            Self::Url => ANNOTATION_URL,
+            Self::Documentation => ANNOTATION_DOCUMENTATION,
+            Self::Source => ANNOTATION_SOURCE,
+            Self::Version => ANNOTATION_VERSION,
+            Self::Revision => ANNOTATION_REVISION,
+            Self::Vendor => ANNOTATION_VENDOR,
+            Self::Licenses => ANNOTATION_LICENSES,
+            Self::RefName => ANNOTATION_REF_NAME,
+            Self::Title => ANNOTATION_TITLE,
+            Self::Description => ANNOTATION_DESCRIPTION,
+            Self::BaseImageDigest => ANNOTATION_BASE_IMAGE_DIGEST,
+            Self::BaseImageName => ANNOTATION_BASE_IMAGE_NAME,
+            Self::ArtifactCreated => ANNOTATION_ARTIFACT_CREATED,
+            Self::ArtifactDescription => ANNOTATION_ARTIFACT_DESCRIPTION,
+        }
+    }
+}
+
+impl AsRef<str> for PredefinedAnnotation {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for PredefinedAnnotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PredefinedAnnotation {
+    type Err = OciSpecError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|key| key.as_str() == s)
+            .ok_or_else(|| oci_error(format!("{s} is not a pre-defined annotation")))
+    }
+}
+
+/// AnnotationUncompressedDigest is the annotation key for the digest of the
+/// uncompressed content of a compressed layer, i.e. its DiffID. It is set by
+/// containerd on layer descriptors.
+pub const ANNOTATION_UNCOMPRESSED_DIGEST: &str = "io.containerd.uncompressed";
+
+/// AnnotationEstargzUncompressedSize is the annotation key for the size in
+/// bytes of the uncompressed content of an eStargz layer. It is set by the
+/// eStargz converter of containerd/stargz-snapshotter on layer descriptors.
+pub const ANNOTATION_ESTARGZ_UNCOMPRESSED_SIZE: &str = "io.containers.estargz.uncompressed-size";
+
+/// AnnotationEncKeysPrefix is the prefix of the annotation keys holding the
+/// wrapped keys of an encrypted layer, followed by the name of the key
+/// wrapping protocol. The value is a comma separated list of base64 encoded
+/// wrapped keys, as defined by
+/// [ocicrypt](https://github.com/containers/ocicrypt/blob/main/docs/spec.md).
+pub const ANNOTATION_ENC_KEYS_PREFIX: &str = "org.opencontainers.image.enc.keys.";
+
+/// AnnotationEncKeysJwe is the annotation key for the keys of an encrypted
+/// layer wrapped with JSON Web Encryption.
+pub const ANNOTATION_ENC_KEYS_JWE: &str = "org.opencontainers.image.enc.keys.jwe";
+
+/// AnnotationEncKeysPkcs7 is the annotation key for the keys of an encrypted
+/// layer wrapped with PKCS#7.
+pub const ANNOTATION_ENC_KEYS_PKCS7: &str = "org.opencontainers.image.enc.keys.pkcs7";
+
+/// AnnotationEncKeysPgp is the annotation key for the keys of an encrypted
+/// layer wrapped with OpenPGP.
+pub const ANNOTATION_ENC_KEYS_PGP: &str = "org.opencontainers.image.enc.keys.pgp";
+
+/// AnnotationEncKeysPkcs11 is the annotation key for the keys of an encrypted
+/// layer wrapped with a PKCS#11 token.
+pub const ANNOTATION_ENC_KEYS_PKCS11: &str = "org.opencontainers.image.enc.keys.pkcs11";
+
+/// AnnotationEncPubOpts is the annotation key for the base64 encoded public
+/// options of the cipher used to encrypt a layer.
+pub const ANNOTATION_ENC_PUB_OPTS: &str = "org.opencontainers.image.enc.pubopts";
+
+/// AnnotationZstdChunkedManifestChecksum is the annotation key for the digest
+/// of the table of contents of a zstd:chunked layer.
+pub const ANNOTATION_ZSTD_CHUNKED_MANIFEST_CHECKSUM: &str =
+    "io.github.containers.zstd-chunked.manifest-checksum";
+
+/// AnnotationZstdChunkedManifestPosition is the annotation key for the
+/// position of the table of contents within a zstd:chunked layer, in the form
+/// `offset:length:uncompressedLength:type`.
+pub const ANNOTATION_ZSTD_CHUNKED_MANIFEST_POSITION: &str =
+    "io.github.containers.zstd-chunked.manifest-position";
+
+/// AnnotationZstdChunkedTarSplitPosition is the annotation key for the
+/// position of the tar-split data within a zstd:chunked layer, in the form
+/// `offset:length:uncompressedLength`.
+pub const ANNOTATION_ZSTD_CHUNKED_TARSPLIT_POSITION: &str =
+    "io.github.containers.zstd-chunked.tarsplit-position";
+
+/// AnnotationEstargzTocDigest is the annotation key for the digest of the
+/// table of contents of an eStargz layer. The uncompressed size of eStargz
+/// layers is stored in [ANNOTATION_ESTARGZ_UNCOMPRESSED_SIZE].
+pub const ANNOTATION_ESTARGZ_TOC_DIGEST: &str = "containerd.io/snapshot/stargz/toc.digest";
+
+/// Types which carry a map of OCI annotations, such as manifests, indexes and
+/// descriptors.
+pub trait Annotated {
+    /// Returns the annotations of the object, if any are set.
+    fn annotation_map(&self) -> Option<&HashMap<String, String>>;
+
+    /// Returns a mutable reference to the annotations of the object,
+    /// initializing them to an empty map if they are unset.
+    fn annotation_map_mut(&mut self) -> &mut HashMap<String, String>;
+}
+
+macro_rules! annotations_ext {
+    ($($key:ident => $get:ident, $set:ident;)*) => {
+        /// Typed accessors of the pre-defined `org.opencontainers.image.*`
+        /// annotations, implemented for the annotation fields of manifests,
+        /// indexes and descriptors.
+        ///
+        /// ```
+        /// use oci_spec::image::{AnnotationsExt, ImageIndex};
+        ///
+        /// let mut index = ImageIndex::default();
+        /// index.annotations_mut().set_source("https://github.com/youki-dev/oci-spec-rs");
+        /// assert_eq!(
+        ///     index.annotations().source(),
+        ///     Some("https://github.com/youki-dev/oci-spec-rs")
+        /// );
+        /// assert_eq!(index.annotations().revision(), None);
+        /// ```
+        pub trait AnnotationsExt {
+            /// Returns the value of the annotation `key`, if it is set.
+            fn annotation(&self, key: &str) -> Option<&str>;
+
+            /// Sets the annotation `key` to `value`.
+            fn set_annotation(&mut self, key: &str, value: String);
+
+            $(
+                #[doc = concat!("Returns the value of the [", stringify!($key), "] annotation.")]
+                fn $get(&self) -> Option<&str> {
+                    self.annotation($key)
+                }
+
+                #[doc = concat!("Sets the [", stringify!($key), "] annotation.")]
+                fn $set(&mut self, value: impl Into<String>) {
+                    self.set_annotation($key, value.into())
+                }
+            )*
+        }
+    };
+}
+
+annotations_ext! {
+    ANNOTATION_CREATED => created, set_created;
+    ANNOTATION_AUTHORS => authors, set_authors;
+    ANNOTATION_URL => url, set_url;
+    ANNOTATION_DOCUMENTATION => documentation, set_documentation;
+    ANNOTATION_SOURCE => source, set_source;
+    ANNOTATION_VERSION => version, set_version;
+    ANNOTATION_REVISION => revision, set_revision;
+    ANNOTATION_VENDOR => vendor, set_vendor;
+    ANNOTATION_LICENSES => licenses, set_licenses;
+    ANNOTATION_REF_NAME => ref_name, set_ref_name;
+    ANNOTATION_TITLE => title, set_title;
+    ANNOTATION_DESCRIPTION => description, set_description;
+    ANNOTATION_BASE_IMAGE_DIGEST => base_image_digest, set_base_image_digest;
+    ANNOTATION_BASE_IMAGE_NAME => base_image_name, set_base_image_name;
+    ANNOTATION_ARTIFACT_CREATED => artifact_created, set_artifact_created;
+    ANNOTATION_ARTIFACT_DESCRIPTION => artifact_description, set_artifact_description;
+}
+
+impl AnnotationsExt for Option<HashMap<String, String>> {
+    fn annotation(&self, key: &str) -> Option<&str> {
+        self.as_ref()?.get(key).map(String::as_str)
+    }
+
+    fn set_annotation(&mut self, key: &str, value: String) {
+        self.get_or_insert_with(HashMap::new)
+            .insert(key.to_owned(), value);
+    }
+}
+
+/// Copies the annotations named in `keys` from `from` to `to`, overwriting
+/// existing values. Keys which are not set on `from` are skipped.
+///
+/// [ANNOTATION_REF_NAME] is never copied, because it names one specific
+/// target and is only valid on descriptors within an image layout's
+/// `index.json`.
+/// # Example
+/// ```
+/// use oci_spec::image::{propagate, ImageIndex, ImageManifest, ANNOTATION_SOURCE};
+///
+/// # fn example(manifest: &ImageManifest) {
+/// let mut index = ImageIndex::default();
+/// propagate(manifest, &mut index, [ANNOTATION_SOURCE]);
+/// # }
+/// ```
+pub fn propagate<F, T, I, K>(from: &F, to: &mut T, keys: I)
+where
+    F: Annotated + ?Sized,
+    T: Annotated + ?Sized,
+    I: IntoIterator<Item = K>,
+    K: AsRef<str>,
+{
+    let Some(source) = from.annotation_map() else {
+        return;
+    };
+    copy_keys(source, to, keys);
+}
+
+/// Copies the annotations named in `keys` to `to` if they are set to the same
+/// value on every object in `from`. This is useful to promote metadata shared
+/// by all per-platform manifests to the image index which references them.
+///
+/// Like [propagate], [ANNOTATION_REF_NAME] is never copied.
+pub fn promote<F, T, I, K>(from: &[F], to: &mut T, keys: I)
+where
+    F: Annotated,
+    T: Annotated + ?Sized,
+    I: IntoIterator<Item = K>,
+    K: AsRef<str>,
+{
+    for key in keys {
+        let key = key.as_ref();
+        if key == ANNOTATION_REF_NAME {
+            continue;
+        }
+        let mut values = from
+            .iter()
+            .map(|f| f.annotation_map().and_then(|a| a.get(key)));
+        let Some(Some(first)) = values.next() else {
+            continue;
+        };
+        if values.all(|v| v == Some(first)) {
+            to.annotation_map_mut()
+                .insert(key.to_owned(), first.to_owned());
+        }
+    }
+}
+
+/// Copies the labels named in `keys` from the image configuration `from` to
+/// the annotations of `to`, overwriting existing values. Labels which are not
+/// set on `from` are skipped.
+///
+/// Like [propagate], [ANNOTATION_REF_NAME] is never copied.
+pub fn propagate_labels<T, I, K>(from: &ImageConfiguration, to: &mut T, keys: I)
+where
+    T: Annotated + ?Sized,
+    I: IntoIterator<Item = K>,
+    K: AsRef<str>,
+{
+    let Some(source) = from.labels_of_config() else {
+        return;
+    };
+    copy_keys(source, to, keys);
+}
+
+fn copy_keys<T, I, K>(source: &HashMap<String, String>, to: &mut T, keys: I)
+where
+    T: Annotated + ?Sized,
+    I: IntoIterator<Item = K>,
+    K: AsRef<str>,
+{
+    for key in keys {
+        let key = key.as_ref();
+        if key == ANNOTATION_REF_NAME {
+            continue;
+        }
+        if let Some(value) = source.get(key) {
+            to.annotation_map_mut()
+                .insert(key.to_owned(), value.to_owned());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::{
+        ConfigBuilder, Descriptor, ImageConfigurationBuilder, ImageIndex, ImageManifest,
+        ImageManifestBuilder, MediaType, Sha256Digest,
+    };
+    use std::str::FromStr;
+
+    fn manifest(annotations: &[(&str, &str)]) -> ImageManifest {
+        let digest = Sha256Digest::from_str(
+            "b5b2b2c507a0944348e0303114d8d93aaaa081732b86451d9bce1f432a537bc7",
+        )
+        .unwrap();
+        ImageManifestBuilder::default()
+            .schema_version(2u32)
+            .config(Descriptor::new(MediaType::ImageConfig, 2, digest))
+            .layers(vec![])
+            .annotations(
+                annotations
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect::<HashMap<_, _>>(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn predefined_annotations() {
+        for key in PredefinedAnnotation::ALL {
+            assert_eq!(
+                key.to_string().parse::<PredefinedAnnotation>().unwrap(),
+                key
+            );
+            assert!(key.as_str().starts_with("org.opencontainers."));
+        }
+        assert!(ANNOTATION_UNCOMPRESSED_DIGEST
+            .parse::<PredefinedAnnotation>()
+            .is_err());
+    }
+
+    #[test]
+    fn annotations_ext() {
+        let mut annotations = None;
+        assert_eq!(annotations.created(), None);
+
+        annotations.set_created("2024-01-01T00:00:00Z");
+        annotations.set_base_image_name("docker.io/library/alpine:3");
+        assert_eq!(annotations.created(), Some("2024-01-01T00:00:00Z"));
+        assert_eq!(
+            annotations.as_ref().unwrap()[ANNOTATION_BASE_IMAGE_NAME],
+            "docker.io/library/alpine:3"
+        );
+
+        let mut manifest = manifest(&[(ANNOTATION_REVISION, "abc")]);
+        assert_eq!(manifest.annotations().revision(), Some("abc"));
+        manifest.annotations_mut().set_revision("def");
+        assert_eq!(manifest.annotations().revision(), Some("def"));
+    }
+
+    #[test]
+    fn propagate_manifest_to_index() {
+        let manifest = manifest(&[
+            (ANNOTATION_SOURCE, "https://example.com"),
+            (ANNOTATION_REF_NAME, "latest"),
+            (ANNOTATION_TITLE, "title"),
+        ]);
+        let mut index = ImageIndex::default();
+
+        propagate(
+            &manifest,
+            &mut index,
+            [ANNOTATION_SOURCE, ANNOTATION_REF_NAME, ANNOTATION_VENDOR],
+        );
+
+        let annotations = index.annotations().as_ref().unwrap();
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[ANNOTATION_SOURCE], "https://example.com");
+    }
+
+    #[test]
+    fn promote_common_annotations() {
+        let manifests = [
+            manifest(&[(ANNOTATION_SOURCE, "src"), (ANNOTATION_REVISION, "a")]),
+            manifest(&[(ANNOTATION_SOURCE, "src"), (ANNOTATION_REVISION, "b")]),
+        ];
+        let mut index = ImageIndex::default();
+
+        promote(
+            &manifests,
+            &mut index,
+            [ANNOTATION_SOURCE, ANNOTATION_REVISION, ANNOTATION_VENDOR],
+        );
+
+        let annotations = index.annotations().as_ref().unwrap();
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[ANNOTATION_SOURCE], "src");
+    }
+
+    #[test]
+    fn propagate_config_labels() {
+        let config = ImageConfigurationBuilder::default()
+            .config(
+                ConfigBuilder::default()
+                    .labels(HashMap::from([(
+                        ANNOTATION_VERSION.to_string(),
+                        "1.0".to_string(),
+                    )]))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let mut manifest = manifest(&[]);
+
+        propagate_labels(&config, &mut manifest, [ANNOTATION_VERSION]);
+
+        assert_eq!(
+            manifest.annotations().as_ref().unwrap()[ANNOTATION_VERSION],
+            "1.0"
+        );
+    }
+}
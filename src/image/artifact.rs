@@ -1,22 +1,24 @@
-use super::{Descriptor, MediaType};
+use super::{Annotated, Descriptor, Digest, MediaType, ANNOTATION_TITLE};
 use crate::error::{OciSpecError, Result};
 use derive_builder::Builder;
 use getset::{Getters, MutGetters, Setters};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "filesystem")]
+use std::path::Path;
 use std::{
     collections::HashMap,
     io::{Read, Write},
-    path::Path,
 };
 
 #[derive(
     Builder, Clone, Debug, Deserialize, Eq, Getters, MutGetters, Setters, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     pattern = "owned",
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 /// The OCI Artifact manifest describes content addressable artifacts
 /// in order to store them along side container images in a registry.
@@ -33,7 +35,7 @@ pub struct ArtifactManifest {
     /// If defined, the value MUST comply with RFC 6838,
     /// including the naming requirements in its section 4.2,
     /// and MAY be registered with IANA.
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     artifact_type: MediaType,
 
     /// This OPTIONAL property is an array of objects and each item
@@ -48,7 +50,7 @@ pub struct ArtifactManifest {
     /// This value, used by the referrers API, indicates a relationship
     /// to the specified manifest.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     #[builder(default)]
     subject: Option<Descriptor>,
 
@@ -62,6 +64,58 @@ pub struct ArtifactManifest {
     annotations: Option<HashMap<String, String>>,
 }
 
+option_getters! {
+    ArtifactManifest {
+        subject_ref => subject: Descriptor,
+        annotations_ref => annotations: HashMap<String, String>,
+    }
+}
+
+impl ArtifactManifestBuilder {
+    /// Appends a single blob to the blobs of the artifact manifest.
+    pub fn add_blob(self, blob: Descriptor) -> Self {
+        self.add_blobs([blob])
+    }
+
+    /// Appends all `blobs` to the blobs of the artifact manifest.
+    pub fn add_blobs<I: IntoIterator<Item = Descriptor>>(mut self, blobs: I) -> Self {
+        self.blobs.get_or_insert_with(Vec::new).extend(blobs);
+        self
+    }
+
+    /// Appends a blob representing a file to the blobs of the artifact
+    /// manifest. The file name is stored in the [ANNOTATION_TITLE]
+    /// annotation of the blob descriptor.
+    pub fn add_file_blob(
+        self,
+        title: impl Into<String>,
+        media_type: MediaType,
+        digest: impl Into<Digest>,
+        size: u64,
+    ) -> Self {
+        let mut blob = Descriptor::new(media_type, size, digest);
+        blob.annotation_map_mut()
+            .insert(ANNOTATION_TITLE.to_owned(), title.into());
+        self.add_blob(blob)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(artifact_type) = &self.artifact_type {
+            let reserved = match artifact_type {
+                MediaType::Other(media_type) => media_type.starts_with("application/vnd.oci."),
+                _ => true,
+            };
+            if reserved {
+                return Err(OciSpecError::Other(format!(
+                    "artifact type {artifact_type} is reserved by the OCI image spec"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl ArtifactManifest {
     /// Attempts to load an image manifest from a file.
     ///
@@ -77,6 +131,7 @@ impl ArtifactManifest {
     ///
     /// let artifact_manifest = ArtifactManifest::from_file("manifest.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
         crate::from_file(path)
     }
@@ -115,6 +170,7 @@ impl ArtifactManifest {
     /// let artifact_manifest = ArtifactManifest::from_file("manifest.json").unwrap();
     /// artifact_manifest.to_file("my-manifest.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         crate::to_file(&self, path, false)
     }
@@ -134,6 +190,7 @@ impl ArtifactManifest {
     /// let artifact_manifest = ArtifactManifest::from_file("manifest.json").unwrap();
     /// artifact_manifest.to_file_pretty("my-manifest.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn to_file_pretty<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         crate::to_file(&self, path, true)
     }
@@ -176,6 +233,58 @@ impl ArtifactManifest {
         crate::to_writer(&self, writer, true)
     }
 
+    /// Attempts to load an artifact manifest from a file asynchronously.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
+    /// if the file does not exist or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the artifact manifest
+    /// cannot be deserialized.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::image::ArtifactManifest;
+    ///
+    /// # async fn example() {
+    /// let artifact_manifest = ArtifactManifest::from_file_async("manifest.json").await.unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn from_file_async<P: AsRef<Path>>(path: P) -> Result<Self> {
+        crate::from_file_async(path).await
+    }
+
+    /// Attempts to load an artifact manifest from an asynchronous stream.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
+    /// if the stream cannot be read or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the artifact manifest
+    /// cannot be deserialized.
+    #[cfg(feature = "async")]
+    pub async fn from_reader_async<R: tokio::io::AsyncRead + Unpin>(reader: R) -> Result<Self> {
+        crate::from_reader_async(reader).await
+    }
+
+    /// Attempts to write an artifact manifest to a file as JSON asynchronously. If the file
+    /// already exists, it will be overwritten.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
+    /// the artifact manifest cannot be serialized.
+    #[cfg(feature = "async")]
+    pub async fn to_file_async<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        crate::to_file_async(self, path, false).await
+    }
+
+    /// Attempts to write an artifact manifest to an asynchronous stream as JSON.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
+    /// the artifact manifest cannot be serialized.
+    #[cfg(feature = "async")]
+    pub async fn to_writer_async<W: tokio::io::AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+    ) -> Result<()> {
+        crate::to_writer_async(self, writer, false).await
+    }
+
     /// Attempts to write an image manifest to a string as JSON.
     ///
     /// # Errors
@@ -211,20 +320,39 @@ impl ArtifactManifest {
     pub fn to_string_pretty(&self) -> Result<String> {
         crate::to_string(&self, true)
     }
+
+    /// Serializes the artifact manifest into its canonical form, compact JSON with
+    /// sorted object keys. These are the bytes [Self::descriptor] refers to.
+    pub fn to_canonical_vec(&self) -> Result<Vec<u8>> {
+        crate::to_sorted_vec(self)
+    }
+
+    /// Returns the descriptor of the artifact manifest in its
+    /// [canonical form](Self::to_canonical_vec), ready to be embedded into
+    /// another document.
+    pub fn descriptor(&self) -> Result<Descriptor> {
+        let mut descriptor =
+            Descriptor::from_content(self.media_type.clone(), &self.to_canonical_vec()?);
+        descriptor.set_artifact_type(Some(self.artifact_type.clone()));
+        Ok(descriptor)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::image::{DescriptorBuilder, Sha256Digest};
-    use std::{path::PathBuf, str::FromStr};
+    use crate::image::Sha256Digest;
+    use std::str::FromStr;
 
-    fn get_manifest_path() -> PathBuf {
-        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/data/artifact_manifest.json")
+    #[cfg(feature = "filesystem")]
+    fn get_manifest_path() -> std::path::PathBuf {
+        std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("test/data/artifact_manifest.json")
     }
 
+    #[cfg(feature = "filesystem")]
     fn create_manifest() -> ArtifactManifest {
-        let blob = DescriptorBuilder::default()
+        let blob = crate::image::DescriptorBuilder::default()
             .media_type(MediaType::Other("application/gzip".to_string()))
             .size(123u64)
             .digest(
@@ -235,7 +363,7 @@ mod tests {
             )
             .build()
             .unwrap();
-        let subject = DescriptorBuilder::default()
+        let subject = crate::image::DescriptorBuilder::default()
             .media_type(MediaType::ImageManifest)
             .size(1234u64)
             .digest(
@@ -264,6 +392,7 @@ mod tests {
             .unwrap()
     }
 
+    #[cfg(feature = "filesystem")]
     #[test]
     fn load_manifest_from_file() {
         // arrange
@@ -276,4 +405,46 @@ mod tests {
         // assert
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn add_blobs() {
+        let digest = Sha256Digest::from_str(
+            "87923725d74f4bfb94c9e86d64170f7521aad8221a5de834851470ca142da630",
+        )
+        .unwrap();
+        let blob = Descriptor::new(MediaType::ImageLayer, 1, digest.clone());
+        let manifest = ArtifactManifestBuilder::default()
+            .artifact_type(MediaType::Other("application/vnd.example+type".into()))
+            .add_blob(blob.clone())
+            .add_blobs(vec![blob.clone(), blob])
+            .add_file_blob(
+                "sbom.json",
+                MediaType::Other("application/spdx+json".into()),
+                digest,
+                123,
+            )
+            .build()
+            .expect("build artifact manifest");
+
+        assert_eq!(manifest.blobs().len(), 4);
+        let file = &manifest.blobs()[3];
+        assert_eq!(file.size(), 123);
+        assert_eq!(
+            file.annotations().as_ref().unwrap()[ANNOTATION_TITLE],
+            "sbom.json"
+        );
+    }
+
+    #[test]
+    fn reserved_artifact_type() {
+        for artifact_type in [
+            MediaType::ImageManifest,
+            MediaType::Other("application/vnd.oci.image.foo".into()),
+        ] {
+            let result = ArtifactManifestBuilder::default()
+                .artifact_type(artifact_type)
+                .build();
+            assert!(result.is_err());
+        }
+    }
 }
@@ -0,0 +1,420 @@
+//! Conversion between Docker Image Manifest V2 Schema 2 documents and their
+//! OCI equivalents.
+//!
+//! The Docker manifest and manifest list share the layout of the OCI image
+//! manifest and index, but use Docker media types. Converting a document
+//! rewrites the media types of the document, its config and its layers or
+//! manifests, while all other fields, including annotations, are preserved.
+//!
+//! ```
+//! use oci_spec::image::compat::{DockerManifest, DOCKER_MANIFEST};
+//! use oci_spec::image::{ImageManifest, MediaType};
+//!
+//! let docker = DockerManifest::from_reader(
+//!     r#"{
+//!         "schemaVersion": 2,
+//!         "mediaType": "application/vnd.docker.distribution.manifest.v2+json",
+//!         "config": {
+//!             "mediaType": "application/vnd.docker.container.image.v1+json",
+//!             "size": 7023,
+//!             "digest": "sha256:b5b2b2c507a0944348e0303114d8d93aaaa081732b86451d9bce1f432a537bc7"
+//!         },
+//!         "layers": [{
+//!             "mediaType": "application/vnd.docker.image.rootfs.diff.tar.gzip",
+//!             "size": 32654,
+//!             "digest": "sha256:e692418e4cbaf90ca69d05a66403747baa33ee08806650b51fab815ad7fc331f"
+//!         }]
+//!     }"#
+//!     .as_bytes(),
+//! )
+//! .unwrap();
+//!
+//! let manifest = ImageManifest::from(docker.clone());
+//! assert_eq!(manifest.media_type(), &Some(MediaType::ImageManifest));
+//! assert_eq!(manifest.layers()[0].media_type(), &MediaType::ImageLayerGzip);
+//!
+//! let back = DockerManifest::try_from(manifest).unwrap();
+//! assert_eq!(back, docker);
+//! ```
+
+use std::{collections::HashMap, io::Read};
+
+use derive_builder::Builder;
+use getset::{CopyGetters, Getters, MutGetters, Setters};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Descriptor, ImageIndex, ImageManifest, ImageManifestBuilder, MediaType, SCHEMA_VERSION,
+};
+use crate::{
+    error::{oci_error, OciSpecError, Result},
+    from_reader, to_string,
+};
+
+/// The media type of a Docker image manifest.
+pub const DOCKER_MANIFEST: &str = "application/vnd.docker.distribution.manifest.v2+json";
+/// The media type of a Docker manifest list.
+pub const DOCKER_MANIFEST_LIST: &str = "application/vnd.docker.distribution.manifest.list.v2+json";
+/// The media type of a Docker image config.
+pub const DOCKER_CONFIG: &str = "application/vnd.docker.container.image.v1+json";
+/// The media type of an uncompressed Docker layer.
+pub const DOCKER_LAYER: &str = "application/vnd.docker.image.rootfs.diff.tar";
+/// The media type of a gzip compressed Docker layer.
+pub const DOCKER_LAYER_GZIP: &str = "application/vnd.docker.image.rootfs.diff.tar.gzip";
+/// The media type of a zstd compressed Docker layer. It is not part of the
+/// original Docker schema, but produced and accepted by containerd and the
+/// Docker Engine.
+pub const DOCKER_LAYER_ZSTD: &str = "application/vnd.docker.image.rootfs.diff.tar.zstd";
+/// The media type of an uncompressed foreign Docker layer.
+pub const DOCKER_FOREIGN_LAYER: &str = "application/vnd.docker.image.rootfs.foreign.diff.tar";
+/// The media type of a gzip compressed foreign Docker layer.
+pub const DOCKER_FOREIGN_LAYER_GZIP: &str =
+    "application/vnd.docker.image.rootfs.foreign.diff.tar.gzip";
+
+/// The Docker media types and their OCI equivalents. The conversions of this
+/// module and [interop](super::interop) are derived from it.
+/// [ToDockerV2S2](super::ToDockerV2S2) keeps its own, narrower mapping.
+pub(super) const MEDIA_TYPES: [(&str, MediaType); 8] = [
+    (DOCKER_MANIFEST, MediaType::ImageManifest),
+    (DOCKER_MANIFEST_LIST, MediaType::ImageIndex),
+    (DOCKER_CONFIG, MediaType::ImageConfig),
+    (DOCKER_LAYER, MediaType::ImageLayer),
+    (DOCKER_LAYER_GZIP, MediaType::ImageLayerGzip),
+    (DOCKER_LAYER_ZSTD, MediaType::ImageLayerZstd),
+    (DOCKER_FOREIGN_LAYER, MediaType::ImageLayerNonDistributable),
+    (
+        DOCKER_FOREIGN_LAYER_GZIP,
+        MediaType::ImageLayerNonDistributableGzip,
+    ),
+];
+
+/// Returns the OCI equivalent of a Docker media type. Other media types are
+/// returned unchanged.
+pub fn to_oci_media_type(media_type: &MediaType) -> MediaType {
+    MEDIA_TYPES
+        .iter()
+        .find(|(docker, _)| media_type.as_ref() == *docker)
+        .map_or_else(|| media_type.clone(), |(_, oci)| oci.clone())
+}
+
+/// Returns the Docker equivalent of an OCI media type. Media types without a
+/// Docker equivalent, like artifact manifests, are returned unchanged.
+pub fn to_docker_media_type(media_type: &MediaType) -> MediaType {
+    MEDIA_TYPES
+        .iter()
+        .find(|(_, oci)| media_type == oci)
+        .map_or_else(|| media_type.clone(), |(docker, _)| (*docker).into())
+}
+
+fn map_media_types(descriptors: &mut [Descriptor], f: fn(&MediaType) -> MediaType) {
+    for descriptor in descriptors {
+        let media_type = f(descriptor.media_type());
+        descriptor.set_media_type(media_type);
+    }
+}
+
+fn docker_manifest_media_type() -> MediaType {
+    DOCKER_MANIFEST.into()
+}
+
+fn docker_manifest_list_media_type() -> MediaType {
+    DOCKER_MANIFEST_LIST.into()
+}
+
+#[derive(
+    Builder,
+    Clone,
+    CopyGetters,
+    Debug,
+    Deserialize,
+    Eq,
+    Getters,
+    MutGetters,
+    Setters,
+    PartialEq,
+    Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[builder(
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+/// A Docker Image Manifest V2 Schema 2.
+pub struct DockerManifest {
+    /// The schema version, which MUST be 2.
+    #[getset(get_mut = "pub", get_copy = "pub", set = "pub")]
+    #[builder(default = "SCHEMA_VERSION")]
+    schema_version: u32,
+    /// The media type of the manifest, which MUST be [DOCKER_MANIFEST].
+    #[serde(default = "docker_manifest_media_type")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
+    #[builder(default = "docker_manifest_media_type()")]
+    media_type: MediaType,
+    /// The image config.
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
+    config: Descriptor,
+    /// The layers of the image, starting with the base layer.
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
+    layers: Vec<Descriptor>,
+    /// Arbitrary metadata of the manifest, which Docker tooling ignores.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
+    #[builder(default)]
+    annotations: Option<HashMap<String, String>>,
+}
+
+option_getters! {
+    DockerManifest {
+        annotations_ref => annotations: HashMap<String, String>,
+    }
+}
+
+impl DockerManifest {
+    /// Attempts to load a Docker manifest from a stream.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        from_reader(reader)
+    }
+
+    /// Attempts to write the Docker manifest to a string as JSON.
+    pub fn to_string(&self) -> Result<String> {
+        to_string(&self, false)
+    }
+}
+
+impl From<DockerManifest> for ImageManifest {
+    fn from(docker: DockerManifest) -> Self {
+        let mut config = docker.config;
+        config.set_media_type(to_oci_media_type(config.media_type()));
+        let mut layers = docker.layers;
+        map_media_types(&mut layers, to_oci_media_type);
+
+        let mut manifest = ImageManifestBuilder::default()
+            .schema_version(docker.schema_version)
+            .media_type(MediaType::ImageManifest)
+            .config(config)
+            .layers(layers)
+            .build()
+            .expect("all required fields are set");
+        manifest.set_annotations(docker.annotations);
+        manifest
+    }
+}
+
+impl TryFrom<ImageManifest> for DockerManifest {
+    type Error = OciSpecError;
+
+    /// Fails if the manifest has an `artifactType` or `subject`, which Docker
+    /// manifests cannot represent.
+    fn try_from(manifest: ImageManifest) -> Result<Self> {
+        if manifest.artifact_type().is_some() || manifest.subject().is_some() {
+            return Err(oci_error(
+                "artifactType and subject cannot be represented in a Docker manifest",
+            ));
+        }
+        let mut config = manifest.config().clone();
+        config.set_media_type(to_docker_media_type(config.media_type()));
+        let mut layers = manifest.layers().clone();
+        map_media_types(&mut layers, to_docker_media_type);
+
+        Ok(Self {
+            schema_version: manifest.schema_version(),
+            media_type: docker_manifest_media_type(),
+            config,
+            layers,
+            annotations: manifest.annotations().clone(),
+        })
+    }
+}
+
+#[derive(
+    Builder,
+    Clone,
+    CopyGetters,
+    Debug,
+    Deserialize,
+    Eq,
+    Getters,
+    MutGetters,
+    Setters,
+    PartialEq,
+    Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[builder(
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+/// A Docker manifest list, referencing the manifests of an image for
+/// multiple platforms.
+pub struct DockerManifestList {
+    /// The schema version, which MUST be 2.
+    #[getset(get_mut = "pub", get_copy = "pub", set = "pub")]
+    #[builder(default = "SCHEMA_VERSION")]
+    schema_version: u32,
+    /// The media type of the manifest list, which MUST be
+    /// [DOCKER_MANIFEST_LIST].
+    #[serde(default = "docker_manifest_list_media_type")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
+    #[builder(default = "docker_manifest_list_media_type()")]
+    media_type: MediaType,
+    /// The platform specific manifests.
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
+    manifests: Vec<Descriptor>,
+    /// Arbitrary metadata of the manifest list, which Docker tooling ignores.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
+    #[builder(default)]
+    annotations: Option<HashMap<String, String>>,
+}
+
+option_getters! {
+    DockerManifestList {
+        annotations_ref => annotations: HashMap<String, String>,
+    }
+}
+
+impl DockerManifestList {
+    /// Attempts to load a Docker manifest list from a stream.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        from_reader(reader)
+    }
+
+    /// Attempts to write the Docker manifest list to a string as JSON.
+    pub fn to_string(&self) -> Result<String> {
+        to_string(&self, false)
+    }
+}
+
+impl From<DockerManifestList> for ImageIndex {
+    fn from(docker: DockerManifestList) -> Self {
+        let mut manifests = docker.manifests;
+        map_media_types(&mut manifests, to_oci_media_type);
+
+        let mut index = ImageIndex::default();
+        index.set_schema_version(docker.schema_version);
+        index.set_media_type(Some(MediaType::ImageIndex));
+        index.set_manifests(manifests);
+        index.set_annotations(docker.annotations);
+        index
+    }
+}
+
+impl TryFrom<ImageIndex> for DockerManifestList {
+    type Error = OciSpecError;
+
+    /// Fails if the index has an `artifactType` or `subject`, which Docker
+    /// manifest lists cannot represent.
+    fn try_from(index: ImageIndex) -> Result<Self> {
+        if index.artifact_type().is_some() || index.subject().is_some() {
+            return Err(oci_error(
+                "artifactType and subject cannot be represented in a Docker manifest list",
+            ));
+        }
+        let mut manifests = index.manifests().clone();
+        map_media_types(&mut manifests, to_docker_media_type);
+
+        Ok(Self {
+            schema_version: index.schema_version(),
+            media_type: docker_manifest_list_media_type(),
+            manifests,
+            annotations: index.annotations().clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::image::{Digest, ImageIndexBuilder, PlatformBuilder, ToDockerV2S2};
+
+    fn descriptor(media_type: impl Into<MediaType>) -> Descriptor {
+        let digest = Digest::from_str(
+            "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356",
+        )
+        .unwrap();
+        Descriptor::new(media_type.into(), 7, digest)
+    }
+
+    #[test]
+    fn media_types() {
+        for (docker, oci) in MEDIA_TYPES {
+            assert_eq!(to_oci_media_type(&docker.into()), oci);
+            assert_eq!(to_docker_media_type(&oci), MediaType::from(docker));
+        }
+        for (docker, oci) in &MEDIA_TYPES[..3] {
+            assert_eq!(oci.to_docker_v2s2(), Ok(*docker));
+        }
+        assert_eq!(
+            MediaType::ImageLayerGzip.to_docker_v2s2(),
+            Ok(DOCKER_LAYER_GZIP)
+        );
+        for oci in [
+            MediaType::ImageLayer,
+            MediaType::ImageLayerZstd,
+            MediaType::ImageLayerNonDistributable,
+            MediaType::ImageLayerNonDistributableGzip,
+            MediaType::ArtifactManifest,
+        ] {
+            assert!(oci.to_docker_v2s2().is_err());
+        }
+        assert_eq!(
+            to_docker_media_type(&MediaType::ArtifactManifest),
+            MediaType::ArtifactManifest
+        );
+    }
+
+    #[test]
+    fn manifest_round_trip() -> Result<()> {
+        let docker = DockerManifestBuilder::default()
+            .config(descriptor(DOCKER_CONFIG))
+            .layers(vec![
+                descriptor(DOCKER_LAYER_GZIP),
+                descriptor(DOCKER_FOREIGN_LAYER_GZIP),
+                descriptor(DOCKER_LAYER_ZSTD),
+            ])
+            .annotations(HashMap::from([("a".to_owned(), "b".to_owned())]))
+            .build()?;
+        let manifest = ImageManifest::from(docker.clone());
+        assert_eq!(manifest.config().media_type(), &MediaType::ImageConfig);
+        assert_eq!(
+            manifest.layers()[1].media_type(),
+            &MediaType::ImageLayerNonDistributableGzip
+        );
+        assert_eq!(manifest.annotations(), docker.annotations());
+        assert_eq!(DockerManifest::try_from(manifest.clone())?, docker);
+
+        let mut manifest = manifest;
+        manifest.set_subject(Some(descriptor(MediaType::ImageManifest)));
+        assert!(DockerManifest::try_from(manifest).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn manifest_list_round_trip() -> Result<()> {
+        let mut manifest = descriptor(MediaType::ImageManifest);
+        manifest.set_platform(Some(
+            PlatformBuilder::default()
+                .architecture("arm64")
+                .os("linux")
+                .build()?,
+        ));
+        let index = ImageIndexBuilder::default()
+            .schema_version(SCHEMA_VERSION)
+            .media_type(MediaType::ImageIndex)
+            .manifests(vec![manifest])
+            .build()?;
+
+        let docker = DockerManifestList::try_from(index.clone())?;
+        assert_eq!(docker.media_type().as_ref(), DOCKER_MANIFEST_LIST);
+        assert_eq!(docker.manifests()[0].media_type().as_ref(), DOCKER_MANIFEST);
+        assert!(docker.manifests()[0].platform().is_some());
+        assert_eq!(ImageIndex::from(docker), index);
+        Ok(())
+    }
+}
@@ -1,18 +1,23 @@
-use super::{Arch, Os};
+use super::{Arch, Descriptor, Digest, MediaType, Os, Signal};
 use crate::{
-    error::{OciSpecError, Result},
-    from_file, from_reader, to_file, to_string, to_writer,
+    error::{oci_error, OciSpecError, Result},
+    from_reader, from_reader_strict, to_string, to_writer,
 };
+#[cfg(feature = "filesystem")]
+use crate::{from_file, from_file_strict, to_file};
 use derive_builder::Builder;
 use getset::{CopyGetters, Getters, MutGetters, Setters};
 use serde::{ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
 #[cfg(test)]
 use std::collections::BTreeMap;
+#[cfg(feature = "filesystem")]
+use std::path::Path;
 use std::{
     collections::HashMap,
     fmt::Display,
     io::{Read, Write},
-    path::Path,
+    str::FromStr,
+    time::Duration,
 };
 
 /// In theory, this key is not standard.  In practice, it's used by at least the
@@ -32,13 +37,14 @@ pub const LABEL_VERSION: &str = "version";
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
     build_fn(error = "OciSpecError")
 )]
-#[getset(get = "pub", set = "pub")]
+#[getset(get_mut = "pub", get = "pub", set = "pub")]
 /// The image configuration is associated with an image and describes some
 /// basic information about the image such as date created, author, as
 /// well as execution/runtime configuration like its entrypoint, default
@@ -98,6 +104,17 @@ pub struct ImageConfiguration {
     history: Vec<History>,
 }
 
+option_getters! {
+    ImageConfiguration {
+        created_ref => created: str,
+        author_ref => author: str,
+        os_version_ref => os_version: str,
+        os_features_ref => os_features: [String],
+        variant_ref => variant: str,
+        config_ref => config: Config,
+    }
+}
+
 impl ImageConfiguration {
     /// Attempts to load an image configuration from a file.
     /// # Errors
@@ -111,6 +128,7 @@ impl ImageConfiguration {
     ///
     /// let image_index = ImageConfiguration::from_file("config.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<ImageConfiguration> {
         from_file(path)
     }
@@ -131,6 +149,42 @@ impl ImageConfiguration {
         from_reader(reader)
     }
 
+    /// Attempts to load an image configuration from a file, rejecting any field which is
+    /// not defined by the specification.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
+    /// if the file does not exist or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the image configuration
+    /// cannot be deserialized, contains unknown fields or duplicate keys.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::image::ImageConfiguration;
+    ///
+    /// let image_configuration = ImageConfiguration::from_file_strict("config.json").unwrap();
+    /// ```
+    #[cfg(feature = "filesystem")]
+    pub fn from_file_strict<P: AsRef<Path>>(path: P) -> Result<ImageConfiguration> {
+        from_file_strict(path)
+    }
+
+    /// Attempts to load an image configuration from a stream, rejecting any field which is
+    /// not defined by the specification.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe)
+    /// if the image configuration cannot be deserialized, contains unknown fields or duplicate
+    /// keys.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::image::ImageConfiguration;
+    /// use std::fs::File;
+    ///
+    /// let reader = File::open("config.json").unwrap();
+    /// let image_configuration = ImageConfiguration::from_reader_strict(reader).unwrap();
+    /// ```
+    pub fn from_reader_strict<R: Read>(reader: R) -> Result<ImageConfiguration> {
+        from_reader_strict(reader)
+    }
+
     /// Attempts to write an image configuration to a file as JSON. If the file already exists, it
     /// will be overwritten.
     /// # Errors
@@ -143,6 +197,7 @@ impl ImageConfiguration {
     /// let image_index = ImageConfiguration::from_file("config.json").unwrap();
     /// image_index.to_file("my-config.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         to_file(&self, path, false)
     }
@@ -159,6 +214,7 @@ impl ImageConfiguration {
     /// let image_index = ImageConfiguration::from_file("config.json").unwrap();
     /// image_index.to_file_pretty("my-config.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn to_file_pretty<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         to_file(&self, path, true)
     }
@@ -195,6 +251,60 @@ impl ImageConfiguration {
         to_writer(&self, writer, true)
     }
 
+    /// Attempts to load an image configuration from a file asynchronously.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
+    /// if the file does not exist or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the image configuration
+    /// cannot be deserialized.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::image::ImageConfiguration;
+    ///
+    /// # async fn example() {
+    /// let image_configuration = ImageConfiguration::from_file_async("config.json").await.unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn from_file_async<P: AsRef<Path>>(path: P) -> Result<ImageConfiguration> {
+        crate::from_file_async(path).await
+    }
+
+    /// Attempts to load an image configuration from an asynchronous stream.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
+    /// if the stream cannot be read or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the image configuration
+    /// cannot be deserialized.
+    #[cfg(feature = "async")]
+    pub async fn from_reader_async<R: tokio::io::AsyncRead + Unpin>(
+        reader: R,
+    ) -> Result<ImageConfiguration> {
+        crate::from_reader_async(reader).await
+    }
+
+    /// Attempts to write an image configuration to a file as JSON asynchronously. If the file
+    /// already exists, it will be overwritten.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
+    /// the image configuration cannot be serialized.
+    #[cfg(feature = "async")]
+    pub async fn to_file_async<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        crate::to_file_async(self, path, false).await
+    }
+
+    /// Attempts to write an image configuration to an asynchronous stream as JSON.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
+    /// the image configuration cannot be serialized.
+    #[cfg(feature = "async")]
+    pub async fn to_writer_async<W: tokio::io::AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+    ) -> Result<()> {
+        crate::to_writer_async(self, writer, false).await
+    }
+
     /// Attempts to write an image configuration to a string as JSON.
     /// # Errors
     /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
@@ -225,6 +335,22 @@ impl ImageConfiguration {
         to_string(&self, true)
     }
 
+    /// Serializes the image configuration into its canonical form, compact JSON with
+    /// sorted object keys. These are the bytes [Self::descriptor] refers to.
+    pub fn to_canonical_vec(&self) -> Result<Vec<u8>> {
+        crate::to_sorted_vec(self)
+    }
+
+    /// Returns the descriptor of the image configuration in its
+    /// [canonical form](Self::to_canonical_vec), ready to be embedded into
+    /// another document.
+    pub fn descriptor(&self) -> Result<Descriptor> {
+        Ok(Descriptor::from_content(
+            MediaType::ImageConfig,
+            &self.to_canonical_vec()?,
+        ))
+    }
+
     /// Extract the labels of the configuration, if present.
     pub fn labels_of_config(&self) -> Option<&HashMap<String, String>> {
         self.config().as_ref().and_then(|c| c.labels().as_ref())
@@ -249,6 +375,61 @@ impl ImageConfiguration {
         self.labels_of_config()
             .and_then(|v| v.get(key).map(|s| s.as_str()))
     }
+
+    /// Appends a layer with the given diff ID and the history entry which
+    /// created it, keeping `rootfs.diff_ids` and `history` consistent.
+    ///
+    /// # Errors
+    /// Fails if the history entry is marked as `empty_layer`, use
+    /// [`add_empty_layer`](Self::add_empty_layer) for those.
+    ///
+    /// ```
+    /// use oci_spec::image::{Digest, HistoryBuilder, ImageConfiguration};
+    /// use std::str::FromStr;
+    ///
+    /// let mut config = ImageConfiguration::default();
+    /// config.add_layer(
+    ///     Digest::from_str(
+    ///         "sha256:5f70bf18a086007016e948b04aed3b82103a36bea41755b6cddfaf10ace3c6ef",
+    ///     ).unwrap(),
+    ///     HistoryBuilder::default().created_by("COPY . /app").build().unwrap(),
+    /// ).unwrap();
+    /// config.add_empty_layer(HistoryBuilder::default().created_by("ENV A=b").build().unwrap());
+    ///
+    /// assert_eq!(config.rootfs().diff_ids().len(), 1);
+    /// assert_eq!(config.history().len(), 2);
+    /// assert!(config.layers_consistent());
+    /// ```
+    pub fn add_layer(&mut self, diff_id: Digest, history: History) -> Result<()> {
+        if history.empty_layer() == Some(true) {
+            return Err(oci_error(
+                "history entry of a layer must not be marked as empty_layer",
+            ));
+        }
+        self.rootfs.diff_ids_mut().push(diff_id);
+        self.history.push(history);
+        Ok(())
+    }
+
+    /// Appends a history entry which did not create a layer, e.g. of an
+    /// `ENV` instruction, and marks it as `empty_layer`.
+    pub fn add_empty_layer(&mut self, mut history: History) {
+        history.set_empty_layer(Some(true));
+        self.history.push(history);
+    }
+
+    /// Returns true if the history entries which are not marked as
+    /// `empty_layer` correspond one to one to `rootfs.diff_ids`. A config
+    /// without history is considered consistent, as history is optional.
+    pub fn layers_consistent(&self) -> bool {
+        self.history.is_empty()
+            || self
+                .history
+                .iter()
+                .filter(|h| h.empty_layer() != Some(true))
+                .count()
+                == self.rootfs.diff_ids().len()
+    }
 }
 
 /// This ToString trait is automatically implemented for any type which implements the Display trait.
@@ -280,6 +461,7 @@ impl Display for ImageConfiguration {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "PascalCase")]
 #[builder(
     default,
@@ -287,7 +469,7 @@ impl Display for ImageConfiguration {
     setter(into, strip_option),
     build_fn(error = "OciSpecError")
 )]
-#[getset(get = "pub", set = "pub")]
+#[getset(get_mut = "pub", get = "pub", set = "pub")]
 /// The execution parameters which SHOULD be used as a base when
 /// running a container using the image.
 pub struct Config {
@@ -356,8 +538,374 @@ pub struct Config {
     /// The field contains the system call signal that will be
     /// sent to the container to exit. The signal can be a signal
     /// name in the format SIGNAME, for instance SIGKILL or SIGRTMIN+3.
+    /// It is kept as written, see [Config::parsed_stop_signal] for the
+    /// parsed signal.
     #[serde(skip_serializing_if = "Option::is_none")]
     stop_signal: Option<String>,
+    /// Docker extension: whether the command line of a Windows image is
+    /// already escaped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    args_escaped: Option<bool>,
+    /// Docker extension: the check run to determine whether a container
+    /// of the image is healthy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    healthcheck: Option<HealthCheck>,
+    /// Docker extension: the build instructions to run when the image is
+    /// used as the base of another build.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    on_build: Option<Vec<String>>,
+    /// Docker extension: the shell used for the shell form of `RUN`, `CMD`
+    /// and `ENTRYPOINT`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shell: Option<Vec<String>>,
+    /// Docker extension: the MAC address of the container. Deprecated in
+    /// Docker, but still emitted by older builders.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mac_address: Option<String>,
+}
+
+option_getters! {
+    Config {
+        user_ref => user: str,
+        exposed_ports_ref => exposed_ports: [String],
+        env_ref => env: [String],
+        entrypoint_ref => entrypoint: [String],
+        cmd_ref => cmd: [String],
+        volumes_ref => volumes: [String],
+        working_dir_ref => working_dir: str,
+        labels_ref => labels: HashMap<String, String>,
+        stop_signal_ref => stop_signal: str,
+        healthcheck_ref => healthcheck: HealthCheck,
+        on_build_ref => on_build: [String],
+        shell_ref => shell: [String],
+        mac_address_ref => mac_address: str,
+    }
+}
+
+impl Config {
+    /// Parse the `StopSignal`, returning `None` if it is unset.
+    /// # Errors
+    /// This function will return an [OciSpecError::Other] if it is not a valid signal.
+    /// # Example
+    /// ```
+    /// use oci_spec::image::{Config, Signal};
+    ///
+    /// let config: Config = serde_json::from_str(r#"{"StopSignal": "SIGQUIT"}"#).unwrap();
+    /// assert_eq!(config.parsed_stop_signal().unwrap(), Some(Signal::Quit));
+    /// ```
+    pub fn parsed_stop_signal(&self) -> Result<Option<Signal>> {
+        self.stop_signal_ref().map(str::parse).transpose()
+    }
+}
+
+#[derive(
+    Builder,
+    Clone,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Getters,
+    MutGetters,
+    Setters,
+    PartialEq,
+    Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "PascalCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+#[getset(get_mut = "pub", get = "pub", set = "pub")]
+/// The Docker health check of an image. Zero or unset durations inherit the
+/// defaults of the container engine.
+pub struct HealthCheck {
+    /// The check to run: `["NONE"]` disables an inherited check,
+    /// `["CMD", args...]` executes a command and `["CMD-SHELL", command]`
+    /// runs a command with the shell of the image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    test: Option<Vec<String>>,
+    /// The time to wait between two checks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<i64>"))]
+    interval: Option<GoDuration>,
+    /// The time after which a check is considered to have hung.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<i64>"))]
+    timeout: Option<GoDuration>,
+    /// The time the container needs to bootstrap, during which failed checks
+    /// are not counted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<i64>"))]
+    start_period: Option<GoDuration>,
+    /// The time to wait between two checks during the start period.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<i64>"))]
+    start_interval: Option<GoDuration>,
+    /// The number of consecutive failures after which the container is
+    /// considered unhealthy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retries: Option<i64>,
+}
+
+option_getters! {
+    HealthCheck {
+        test_ref => test: [String],
+    }
+}
+
+impl HealthCheck {
+    /// Returns the time to wait between two checks, or `None` if the
+    /// default of the engine applies.
+    pub fn interval_duration(&self) -> Option<Duration> {
+        self.interval.as_ref().and_then(GoDuration::to_duration)
+    }
+
+    /// Returns the time after which a check is considered to have hung, or
+    /// `None` if the default of the engine applies.
+    pub fn timeout_duration(&self) -> Option<Duration> {
+        self.timeout.as_ref().and_then(GoDuration::to_duration)
+    }
+
+    /// Returns the start period of the container, or `None` if the default
+    /// of the engine applies.
+    pub fn start_period_duration(&self) -> Option<Duration> {
+        self.start_period.as_ref().and_then(GoDuration::to_duration)
+    }
+
+    /// Returns the time to wait between two checks during the start period,
+    /// or `None` if the default of the engine applies.
+    pub fn start_interval_duration(&self) -> Option<Duration> {
+        self.start_interval
+            .as_ref()
+            .and_then(GoDuration::to_duration)
+    }
+
+    /// Sets the time to wait between two checks.
+    pub fn set_interval_duration(&mut self, interval: Option<Duration>) -> &mut Self {
+        self.interval = interval.map(GoDuration::from);
+        self
+    }
+
+    /// Sets the time after which a check is considered to have hung.
+    pub fn set_timeout_duration(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.timeout = timeout.map(GoDuration::from);
+        self
+    }
+
+    /// Sets the start period of the container.
+    pub fn set_start_period_duration(&mut self, start_period: Option<Duration>) -> &mut Self {
+        self.start_period = start_period.map(GoDuration::from);
+        self
+    }
+
+    /// Sets the time to wait between two checks during the start period.
+    pub fn set_start_interval_duration(&mut self, start_interval: Option<Duration>) -> &mut Self {
+        self.start_interval = start_interval.map(GoDuration::from);
+        self
+    }
+
+    /// Returns true if the check disables a health check inherited from the
+    /// base image.
+    pub fn is_disabled(&self) -> bool {
+        self.test
+            .as_ref()
+            .and_then(|test| test.first())
+            .is_some_and(|kind| kind == "NONE")
+    }
+}
+
+/// A duration of a [HealthCheck] in nanoseconds. Docker stores these as
+/// integers, but some tools write them in the string format of Go's
+/// `time.Duration` instead. The representation a duration was deserialized
+/// from is kept when serializing it again, so documents round-trip unchanged.
+///
+/// ```
+/// use oci_spec::image::GoDuration;
+///
+/// let interval: GoDuration = serde_json::from_str(r#""1m30s""#).unwrap();
+/// assert_eq!(interval.nanos(), 90_000_000_000);
+/// assert_eq!(serde_json::to_string(&interval).unwrap(), r#""1m30s""#);
+/// assert_eq!(GoDuration::from(1_000).to_string(), "1000");
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GoDuration {
+    nanos: i64,
+    text: Option<String>,
+}
+
+impl GoDuration {
+    /// Returns the duration in nanoseconds.
+    pub fn nanos(&self) -> i64 {
+        self.nanos
+    }
+
+    /// Returns the Go duration string the duration was parsed from, or
+    /// `None` if it is represented as an integer.
+    pub fn as_go_str(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    /// Returns the duration, or `None` if it is not positive and the
+    /// default of the engine applies.
+    pub fn to_duration(&self) -> Option<Duration> {
+        u64::try_from(self.nanos)
+            .ok()
+            .filter(|nanos| *nanos > 0)
+            .map(Duration::from_nanos)
+    }
+}
+
+impl From<i64> for GoDuration {
+    fn from(nanos: i64) -> Self {
+        Self { nanos, text: None }
+    }
+}
+
+impl From<Duration> for GoDuration {
+    fn from(duration: Duration) -> Self {
+        i64::try_from(duration.as_nanos())
+            .unwrap_or(i64::MAX)
+            .into()
+    }
+}
+
+impl FromStr for GoDuration {
+    type Err = OciSpecError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(Self {
+            nanos: parse_go_duration(s)?,
+            text: Some(s.to_owned()),
+        })
+    }
+}
+
+impl Display for GoDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.text {
+            Some(text) => f.write_str(text),
+            None => write!(f, "{}", self.nanos),
+        }
+    }
+}
+
+impl Serialize for GoDuration {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match &self.text {
+            Some(text) => serializer.serialize_str(text),
+            None => serializer.serialize_i64(self.nanos),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for GoDuration {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Nanos {
+            Int(i64),
+            Str(String),
+        }
+
+        match Nanos::deserialize(deserializer)? {
+            Nanos::Int(nanos) => Ok(nanos.into()),
+            Nanos::Str(s) => s.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Parses a duration in the format of Go's `time.Duration`, e.g. `1m30s`,
+/// into nanoseconds. Like Go, digits of fractions beyond nanosecond
+/// precision are truncated.
+///
+/// ```
+/// use oci_spec::image::parse_go_duration;
+///
+/// assert_eq!(parse_go_duration("1m30s").unwrap(), 90_000_000_000);
+/// assert_eq!(parse_go_duration("1.5ms").unwrap(), 1_500_000);
+/// assert!(parse_go_duration("30").is_err());
+/// ```
+pub fn parse_go_duration(s: &str) -> Result<i64> {
+    let invalid = || oci_error(format!("invalid duration: {s}"));
+    let (negative, mut rest) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    if rest == "0" {
+        return Ok(0);
+    }
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut total: u64 = 0;
+    while !rest.is_empty() {
+        let int_end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (int, after_int) = rest.split_at(int_end);
+        let (frac, after_frac) = match after_int.strip_prefix('.') {
+            Some(after_dot) => {
+                let frac_end = after_dot
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(after_dot.len());
+                after_dot.split_at(frac_end)
+            }
+            None => ("", after_int),
+        };
+        if int.is_empty() && frac.is_empty() {
+            return Err(invalid());
+        }
+        let unit_end = after_frac
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(after_frac.len());
+        let unit: u64 = match &after_frac[..unit_end] {
+            "ns" => 1,
+            "us" | "\u{b5}s" | "\u{3bc}s" => 1_000,
+            "ms" => 1_000_000,
+            "s" => 1_000_000_000,
+            "m" => 60_000_000_000,
+            "h" => 3_600_000_000_000,
+            _ => return Err(invalid()),
+        };
+        rest = &after_frac[unit_end..];
+
+        let mut value = if int.is_empty() {
+            0
+        } else {
+            int.parse::<u64>()
+                .ok()
+                .and_then(|int| int.checked_mul(unit))
+                .ok_or_else(invalid)?
+        };
+        // No unit exceeds 10^16 nanoseconds, so fractional digits beyond the
+        // 16th cannot add a nanosecond.
+        let (mut numerator, mut scale) = (0u64, 1u64);
+        for digit in frac.bytes().take(16) {
+            numerator = numerator * 10 + u64::from(digit - b'0');
+            scale *= 10;
+        }
+        value = value
+            .checked_add((u128::from(numerator) * u128::from(unit) / u128::from(scale)) as u64)
+            .ok_or_else(invalid)?;
+        total = total.checked_add(value).ok_or_else(invalid)?;
+    }
+
+    if negative {
+        0i64.checked_sub_unsigned(total).ok_or_else(invalid)
+    } else {
+        i64::try_from(total).map_err(|_| invalid())
+    }
 }
 
 // Some fields of the image configuration are a json serialization of a
@@ -370,6 +918,7 @@ pub struct Config {
 // }
 // Instead we treat this as a list
 #[derive(Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 struct GoMapSerde {}
 
 fn deserialize_as_vec<'de, D>(deserializer: D) -> std::result::Result<Option<Vec<String>>, D::Error>
@@ -418,13 +967,14 @@ where
 #[derive(
     Builder, Clone, Debug, Deserialize, Eq, Getters, MutGetters, Setters, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
     build_fn(error = "OciSpecError")
 )]
-#[getset(get = "pub", set = "pub")]
+#[getset(get_mut = "pub", get = "pub", set = "pub")]
 /// RootFs references the layer content addresses used by the image.
 pub struct RootFs {
     /// MUST be set to layers.
@@ -433,7 +983,7 @@ pub struct RootFs {
     /// An array of layer content hashes (DiffIDs), in order
     /// from first to last.
     #[getset(get_mut = "pub", get = "pub", set = "pub")]
-    diff_ids: Vec<String>,
+    diff_ids: Vec<Digest>,
 }
 
 impl Default for RootFs {
@@ -454,10 +1004,12 @@ impl Default for RootFs {
     Eq,
     CopyGetters,
     Getters,
+    MutGetters,
     Setters,
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -469,31 +1021,40 @@ pub struct History {
     /// A combined date and time at which the layer was created,
     /// formatted as defined by [RFC 3339, section 5.6.](https://tools.ietf.org/html/rfc3339#section-5.6).
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     created: Option<String>,
     /// The author of the build point.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     author: Option<String>,
     /// The command which created the layer.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     created_by: Option<String>,
     /// A custom message set when creating the layer.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     comment: Option<String>,
     /// This field is used to mark if the history item created
     /// a filesystem diff. It is set to true if this history item
     /// doesn't correspond to an actual layer in the rootfs section
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get_copy = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get_copy = "pub", set = "pub")]
     empty_layer: Option<bool>,
 }
 
+option_getters! {
+    History {
+        created_ref => created: str,
+        author_ref => author: str,
+        created_by_ref => created_by: str,
+        comment_ref => comment: str,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::{fs, path::PathBuf};
+    use std::{fs, path::PathBuf, str::FromStr};
 
     use super::*;
     use crate::image::{ANNOTATION_CREATED, ANNOTATION_VERSION};
@@ -530,8 +1091,8 @@ mod tests {
             )
             .rootfs(RootFsBuilder::default()
             .diff_ids(vec![
-                "sha256:c6f988f4874bb0add23a778f753c65efe992244e148a1d2ec2a8b664fb66bbd1".to_owned(),
-                "sha256:5f70bf18a086007016e948b04aed3b82103a36bea41755b6cddfaf10ace3c6ef".to_owned(),
+                Digest::from_str("sha256:c6f988f4874bb0add23a778f753c65efe992244e148a1d2ec2a8b664fb66bbd1").unwrap(),
+                Digest::from_str("sha256:5f70bf18a086007016e948b04aed3b82103a36bea41755b6cddfaf10ace3c6ef").unwrap(),
             ])
             .build()
             .expect("build rootfs"))
@@ -575,6 +1136,7 @@ mod tests {
         PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/data/config.json")
     }
 
+    #[cfg(feature = "filesystem")]
     #[test]
     fn load_configuration_from_file() {
         // arrange
@@ -598,6 +1160,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn add_layers() {
+        let mut config = ImageConfiguration::default();
+        assert!(config.layers_consistent());
+        let diff_ids = config.rootfs().diff_ids().len();
+
+        let history = HistoryBuilder::default()
+            .created_by("RUN make")
+            .build()
+            .expect("build history");
+        let digest = |hex: char| {
+            Digest::from_str(&format!("sha256:{}", hex.to_string().repeat(64))).unwrap()
+        };
+        config
+            .add_layer(digest('a'), history.clone())
+            .expect("add layer");
+        config.add_empty_layer(history.clone());
+        assert_eq!(config.rootfs().diff_ids().len(), diff_ids + 1);
+        assert_eq!(config.history().last().unwrap().empty_layer(), Some(true));
+        assert!(config.layers_consistent());
+
+        let mut empty = history;
+        empty.set_empty_layer(Some(true));
+        assert!(config.add_layer(digest('d'), empty).is_err());
+        config.rootfs_mut().diff_ids_mut().push(digest('d'));
+        assert!(!config.layers_consistent());
+    }
+
+    #[test]
+    fn stop_signal() {
+        let config: Config = serde_json::from_str(r#"{"StopSignal":"SIGRTMIN+3"}"#).unwrap();
+        assert_eq!(config.parsed_stop_signal().unwrap(), Some(Signal::RtMin(3)));
+        assert_eq!(config.stop_signal_ref(), Some("SIGRTMIN+3"));
+        assert_eq!(config.user_ref(), None);
+
+        let json = r#"{"StopSignal":"term"}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&config).unwrap(), json);
+        assert_eq!(config.parsed_stop_signal().unwrap(), Some(Signal::Term));
+
+        let config: Config = serde_json::from_str(r#"{"StopSignal":"SIGFOO"}"#).unwrap();
+        assert!(config.parsed_stop_signal().is_err());
+        assert_eq!(Config::default().parsed_stop_signal().unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_diff_id() {
+        let json = r#"{"type":"layers","diff_ids":["sha256:abc"]}"#;
+        assert!(serde_json::from_str::<RootFs>(json).is_err());
+    }
+
     #[test]
     fn load_configuration_from_reader() {
         // arrange
@@ -614,6 +1227,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "filesystem")]
     #[test]
     fn save_config_to_file() {
         // arrange
@@ -660,4 +1274,74 @@ mod tests {
         let expected = fs::read_to_string(get_config_path()).expect("read expected");
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn docker_config_round_trip() {
+        // arrange
+        let json = r#"{"User":"app","Cmd":["/bin/sh"],"ArgsEscaped":true,"Healthcheck":{"Test":["CMD-SHELL","curl -f http://localhost/ || exit 1"],"Interval":30000000000,"Timeout":5000000000,"Retries":3},"OnBuild":["RUN make"],"Shell":["/bin/bash","-c"],"MacAddress":"02:42:ac:11:00:02"}"#;
+
+        // act
+        let config: Config = serde_json::from_str(json).expect("deserialize config");
+
+        // assert
+        assert_eq!(config.args_escaped(), &Some(true));
+        let healthcheck = config.healthcheck().as_ref().expect("healthcheck");
+        assert_eq!(
+            healthcheck.interval().as_ref().map(GoDuration::nanos),
+            Some(30_000_000_000)
+        );
+        assert_eq!(healthcheck.retries(), &Some(3));
+        assert_eq!(config.shell().as_ref().map(Vec::len), Some(2));
+        assert_eq!(
+            serde_json::to_string(&config).expect("serialize config"),
+            json
+        );
+    }
+
+    #[test]
+    fn healthcheck_durations() {
+        let json =
+            r#"{"Test":["CMD","true"],"Interval":"1m30s","Timeout":5000000000,"StartPeriod":0}"#;
+        let mut healthcheck: HealthCheck = serde_json::from_str(json).expect("deserialize");
+        assert_eq!(
+            healthcheck.interval().as_ref().map(GoDuration::nanos),
+            Some(90_000_000_000)
+        );
+        assert_eq!(healthcheck.timeout_duration(), Some(Duration::from_secs(5)));
+        assert_eq!(healthcheck.start_period_duration(), None);
+        assert!(!healthcheck.is_disabled());
+        assert_eq!(
+            serde_json::to_string(&healthcheck).expect("serialize"),
+            json
+        );
+
+        healthcheck.set_interval_duration(Some(Duration::from_millis(1500)));
+        assert_eq!(
+            healthcheck.interval().as_ref().map(GoDuration::nanos),
+            Some(1_500_000_000)
+        );
+        assert!(serde_json::to_string(&healthcheck)
+            .expect("serialize")
+            .contains(r#""Interval":1500000000"#));
+
+        assert!(serde_json::from_str::<HealthCheck>(r#"{"Interval":"5x"}"#).is_err());
+        assert_eq!(parse_go_duration("-2h").unwrap(), -7_200_000_000_000);
+        assert_eq!(parse_go_duration("10\u{b5}s").unwrap(), 10_000);
+        assert_eq!(parse_go_duration(".5s").unwrap(), 500_000_000);
+        assert_eq!(
+            parse_go_duration("2562047h47m16.854775807s").unwrap(),
+            i64::MAX
+        );
+        assert_eq!(
+            parse_go_duration("-2562047h47m16.854775808s").unwrap(),
+            i64::MIN
+        );
+        assert_eq!(
+            parse_go_duration("1.0000000019999s").unwrap(),
+            1_000_000_001
+        );
+        assert!(parse_go_duration("2562047h47m16.854775808s").is_err());
+        assert!(parse_go_duration("").is_err());
+        assert!(parse_go_duration(".s").is_err());
+    }
 }
@@ -1,13 +1,37 @@
-use super::{Arch, Digest, MediaType, Os};
-use crate::error::OciSpecError;
+use super::{
+    Annotated, Arch, Digest, MediaType, Os, Sha256Digest, ANNOTATION_ESTARGZ_UNCOMPRESSED_SIZE,
+    ANNOTATION_UNCOMPRESSED_DIGEST,
+};
+use crate::error::{oci_error, OciSpecError, Result};
+#[cfg(feature = "proptests")]
+use crate::some_none_generator_util;
 use derive_builder::Builder;
-use getset::{CopyGetters, Getters, Setters};
+use getset::{CopyGetters, Getters, MutGetters, Setters};
+#[cfg(feature = "proptests")]
+use quickcheck::{Arbitrary, Gen};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display, Formatter},
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
 
 #[derive(
-    Builder, Clone, CopyGetters, Debug, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
+    Builder,
+    Clone,
+    CopyGetters,
+    Debug,
+    Deserialize,
+    Eq,
+    Getters,
+    MutGetters,
+    Setters,
+    PartialEq,
+    Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     pattern = "owned",
@@ -23,41 +47,41 @@ pub struct Descriptor {
     /// This REQUIRED property contains the media type of the referenced
     /// content. Values MUST comply with RFC 6838, including the naming
     /// requirements in its section 4.2.
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     media_type: MediaType,
     /// This REQUIRED property is the digest of the targeted content,
     /// conforming to the requirements outlined in Digests. Retrieved
     /// content SHOULD be verified against this digest when consumed via
     /// untrusted sources.
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     digest: Digest,
     /// This REQUIRED property specifies the size, in bytes, of the raw
     /// content. This property exists so that a client will have an
     /// expected size for the content before processing. If the
     /// length of the retrieved content does not match the specified
     /// length, the content SHOULD NOT be trusted.
-    #[getset(get_copy = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get_copy = "pub", set = "pub")]
     size: u64,
     /// This OPTIONAL property specifies a list of URIs from which this
     /// object MAY be downloaded. Each entry MUST conform to [RFC 3986](https://tools.ietf.org/html/rfc3986).
     /// Entries SHOULD use the http and https schemes, as defined
     /// in [RFC 7230](https://tools.ietf.org/html/rfc7230#section-2.7).
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     #[builder(default)]
     urls: Option<Vec<String>>,
     /// This OPTIONAL property contains arbitrary metadata for this
     /// descriptor. This OPTIONAL property MUST use the annotation
     /// rules.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     #[builder(default)]
     annotations: Option<HashMap<String, String>>,
     /// This OPTIONAL property describes the minimum runtime requirements of
     /// the image. This property SHOULD be present if its target is
     /// platform-specific.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     #[builder(default)]
     platform: Option<Platform>,
     /// This OPTIONAL property contains the type of an artifact when the descriptor points to an
@@ -65,7 +89,7 @@ pub struct Descriptor {
     /// references an image manifest. If defined, the value MUST comply with RFC 6838, including
     /// the naming requirements in its section 4.2, and MAY be registered with IANA.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     #[builder(default)]
     artifact_type: Option<MediaType>,
     /// This OPTIONAL property contains an embedded representation of the referenced content.
@@ -73,20 +97,42 @@ pub struct Descriptor {
     /// be identical to the referenced content and SHOULD be verified against the digest and size
     /// fields by content consumers. See Embedded Content for when this is appropriate.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     #[builder(default)]
     data: Option<String>,
 }
 
+option_getters! {
+    Descriptor {
+        urls_ref => urls: [String],
+        annotations_ref => annotations: HashMap<String, String>,
+        platform_ref => platform: Platform,
+        artifact_type_ref => artifact_type: MediaType,
+        data_ref => data: str,
+    }
+}
+
 #[derive(
-    Builder, Clone, Debug, Default, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
+    Builder,
+    Clone,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Getters,
+    Hash,
+    MutGetters,
+    Setters,
+    PartialEq,
+    Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     pattern = "owned",
     setter(into, strip_option),
     build_fn(error = "OciSpecError")
 )]
-#[getset(get = "pub", set = "pub")]
+#[getset(get_mut = "pub", get = "pub", set = "pub")]
 /// Describes the minimum runtime requirements of the image.
 pub struct Platform {
     /// This REQUIRED property specifies the CPU architecture.
@@ -126,11 +172,246 @@ pub struct Platform {
     #[builder(default)]
     variant: Option<String>,
     /// This property is RESERVED for future versions of the specification.
+    /// It is preserved as-is when deserializing and serializing again, but
+    /// producers SHOULD NOT set it. See [Platform::uses_reserved_features].
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default)]
     features: Option<Vec<String>>,
 }
 
+option_getters! {
+    Platform {
+        os_version_ref => os_version: str,
+        os_features_ref => os_features: [String],
+        variant_ref => variant: str,
+        features_ref => features: [String],
+    }
+}
+
+/// A policy for fetching content from the `urls` of a [Descriptor].
+///
+/// The URLs of a descriptor are provided by the producer of a manifest and
+/// point to arbitrary hosts, so clients should restrict which of them they
+/// are willing to fetch from.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct UrlPolicy {
+    /// Whether plain `http` URLs are allowed in addition to `https` ones.
+    pub allow_http: bool,
+    /// The hosts URLs may point to. Entries starting with `*.` match all
+    /// subdomains. `None` allows any host.
+    pub allowed_hosts: Option<Vec<String>>,
+    /// The maximum size of content fetched from URLs. `None` allows any size.
+    pub max_size: Option<u64>,
+}
+
+impl UrlPolicy {
+    /// Returns true if `url` may be fetched according to the policy.
+    ///
+    /// URLs containing backslashes, whitespace or control characters, or
+    /// percent-encoded characters in the authority, are rejected, because
+    /// URL parsers disagree on their host and could be tricked into
+    /// fetching from a host the policy does not allow.
+    pub fn allows_url(&self, url: &str) -> bool {
+        if url
+            .chars()
+            .any(|c| c == '\\' || c.is_whitespace() || c.is_control())
+        {
+            return false;
+        }
+        let Some((scheme, rest)) = url.split_once("://") else {
+            return false;
+        };
+        match scheme.to_ascii_lowercase().as_str() {
+            "https" => {}
+            "http" if self.allow_http => {}
+            _ => return false,
+        }
+
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        if authority.contains('%') {
+            return false;
+        }
+        let host_port = authority.rsplit('@').next().unwrap_or_default();
+        let host = match host_port.strip_prefix('[') {
+            Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+            None => host_port.split(':').next().unwrap_or_default(),
+        };
+        if host.is_empty()
+            || !host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':'))
+        {
+            return false;
+        }
+
+        match &self.allowed_hosts {
+            None => true,
+            Some(hosts) => hosts
+                .iter()
+                .any(|allowed| match allowed.strip_prefix("*.") {
+                    Some(domain) => host
+                        .to_ascii_lowercase()
+                        .strip_suffix(&domain.to_ascii_lowercase())
+                        .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+                    None => allowed.eq_ignore_ascii_case(host),
+                }),
+        }
+    }
+}
+
+impl Annotated for Descriptor {
+    fn annotation_map(&self) -> Option<&HashMap<String, String>> {
+        self.annotations.as_ref()
+    }
+
+    fn annotation_map_mut(&mut self) -> &mut HashMap<String, String> {
+        self.annotations.get_or_insert_with(HashMap::new)
+    }
+}
+
+impl Platform {
+    /// Returns the platform of the host this program was compiled for,
+    /// without variant, OS version or features, as those depend on the
+    /// machine running it.
+    ///
+    /// ```
+    /// use oci_spec::image::{Arch, Os, Platform};
+    ///
+    /// let host = Platform::host();
+    /// assert_eq!(host.os(), &Os::host());
+    /// assert_eq!(host.architecture(), &Arch::host());
+    /// ```
+    pub fn host() -> Self {
+        Self {
+            architecture: Arch::host(),
+            os: Os::host(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the platform of WebAssembly images, `wasip1/wasm`.
+    ///
+    /// ```
+    /// use oci_spec::image::Platform;
+    ///
+    /// assert_eq!(Platform::wasm().to_string(), "wasip1/wasm");
+    /// assert!(Platform::wasm().is_wasm());
+    /// ```
+    pub fn wasm() -> Self {
+        Self {
+            architecture: Arch::Wasm,
+            os: Os::Wasi,
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if the platform is a WebAssembly one, i.e. its
+    /// architecture is `wasm`. The OS is not checked, as images in the wild
+    /// use `wasi`, `wasip1` or the OS of the host running the module.
+    pub fn is_wasm(&self) -> bool {
+        self.architecture == Arch::Wasm
+    }
+
+    /// Returns true if the reserved `features` property is set. Validators
+    /// can use this to warn producers which misuse the property for custom
+    /// data, as its meaning may be defined by a future specification.
+    pub fn uses_reserved_features(&self) -> bool {
+        self.features.is_some()
+    }
+}
+
+/// Formats the platform as `os/arch[/variant]`, the notation used by
+/// container tools, e.g. `linux/arm64/v8`.
+impl Display for Platform {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.os, self.architecture)?;
+        match &self.variant {
+            Some(variant) => write!(f, "/{variant}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses a platform in the `os/arch[/variant]` notation.
+///
+/// ```
+/// use oci_spec::image::{Arch, Os, Platform};
+///
+/// let platform: Platform = "linux/arm64/v8".parse().unwrap();
+/// assert_eq!(platform.os(), &Os::Linux);
+/// assert_eq!(platform.architecture(), &Arch::ARM64);
+/// assert_eq!(platform.variant().as_deref(), Some("v8"));
+/// assert_eq!(platform.to_string(), "linux/arm64/v8");
+/// ```
+impl FromStr for Platform {
+    type Err = OciSpecError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<_> = s.split('/').collect();
+        if parts.iter().any(|p| p.is_empty()) {
+            return Err(oci_error(format!("invalid platform {s}")));
+        }
+        let (os, arch, variant) = match parts[..] {
+            [os, arch] => (os, arch, None),
+            [os, arch, variant] => (os, arch, Some(variant.to_owned())),
+            _ => return Err(oci_error(format!("invalid platform {s}"))),
+        };
+        Ok(Self {
+            architecture: arch.into(),
+            os: os.into(),
+            variant,
+            ..Default::default()
+        })
+    }
+}
+
+/// Platforms are ordered by their `os/arch[/variant]` notation first, so
+/// lists of platforms are sorted the way they are displayed.
+impl Ord for Platform {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Every field is compared so that only equal platforms are ordered
+        // equal. An `Other` name can spell a known os or architecture, so the
+        // variants are told apart after their names.
+        let key = |p: &Self| {
+            (
+                p.os.to_string(),
+                p.architecture.to_string(),
+                p.variant.clone(),
+                p.os_version.clone(),
+                p.os_features.clone(),
+                p.features.clone(),
+                matches!(p.os, Os::Other(_)),
+                matches!(p.architecture, Arch::Other(_)),
+            )
+        };
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for Platform {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Annotations are hashed in the order of their keys, so that equal
+/// descriptors have equal hashes.
+impl Hash for Descriptor {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.media_type.hash(state);
+        self.digest.hash(state);
+        self.size.hash(state);
+        self.urls.hash(state);
+        self.annotations
+            .as_ref()
+            .map(|a| a.iter().collect::<BTreeMap<_, _>>())
+            .hash(state);
+        self.platform.hash(state);
+        self.artifact_type.hash(state);
+        self.data.hash(state);
+    }
+}
+
 impl Descriptor {
     /// Construct a new descriptor with the required fields.
     pub fn new(media_type: MediaType, size: u64, digest: impl Into<Digest>) -> Self {
@@ -146,6 +427,60 @@ impl Descriptor {
         }
     }
 
+    /// Creates the descriptor of `content`, with its size and SHA-256 digest.
+    ///
+    /// ```
+    /// use oci_spec::image::{Descriptor, MediaType};
+    ///
+    /// let descriptor = Descriptor::from_content(MediaType::EmptyJSON, b"{}");
+    /// assert_eq!(descriptor.size(), 2);
+    /// assert_eq!(descriptor.digest(), Descriptor::empty_config().digest());
+    /// ```
+    pub fn from_content(media_type: MediaType, content: &[u8]) -> Self {
+        use sha2::{Digest as _, Sha256};
+
+        let digest = Sha256Digest::from_str(&format!("{:x}", Sha256::digest(content)))
+            .expect("valid SHA-256 digest");
+        Self::new(media_type, content.len() as u64, digest)
+    }
+
+    /// Returns the descriptor of the empty JSON object `{}` with the
+    /// [MediaType::EmptyJSON] media type and embedded data, which image-spec
+    /// 1.1 defines as config of artifacts without configuration.
+    ///
+    /// ```
+    /// use oci_spec::image::{Descriptor, MediaType};
+    ///
+    /// let config = Descriptor::empty_config();
+    /// assert_eq!(config.media_type(), &MediaType::EmptyJSON);
+    /// assert_eq!(config.size(), 2);
+    /// assert_eq!(config.data().as_deref(), Some("e30="));
+    /// ```
+    pub fn empty_config() -> Self {
+        let digest = Digest::from_str(
+            "sha256:44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a",
+        )
+        .expect("valid digest of the empty JSON object");
+        let mut descriptor = Self::new(MediaType::EmptyJSON, 2, digest);
+        descriptor.data = Some("e30=".to_owned());
+        descriptor
+    }
+
+    /// Returns the [`Self::urls()`] which may be fetched according to
+    /// `policy`. No URL is allowed if the size of the content exceeds the
+    /// maximum size of the policy.
+    pub fn urls_allowed_by(&self, policy: &UrlPolicy) -> Vec<&str> {
+        if policy.max_size.is_some_and(|max| self.size > max) {
+            return Vec::new();
+        }
+        self.urls
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|url| policy.allows_url(url))
+            .collect()
+    }
+
     /// Return a view of [`Self::digest()`] that has been parsed as a valid SHA-256.
     pub fn as_digest_sha256(&self) -> Option<&str> {
         match self.digest.algorithm() {
@@ -153,6 +488,81 @@ impl Descriptor {
             _ => None,
         }
     }
+
+    /// Returns the digest of the uncompressed content as stored in the
+    /// [ANNOTATION_UNCOMPRESSED_DIGEST] annotation, if it is set and valid.
+    pub fn uncompressed_digest(&self) -> Option<Digest> {
+        self.annotation_map()?
+            .get(ANNOTATION_UNCOMPRESSED_DIGEST)
+            .and_then(|d| Digest::from_str(d).ok())
+    }
+
+    /// Sets the [ANNOTATION_UNCOMPRESSED_DIGEST] annotation.
+    pub fn set_uncompressed_digest(&mut self, digest: impl Into<Digest>) -> &mut Self {
+        self.annotation_map_mut().insert(
+            ANNOTATION_UNCOMPRESSED_DIGEST.to_owned(),
+            digest.into().to_string(),
+        );
+        self
+    }
+
+    /// Returns the size in bytes of the uncompressed content of an eStargz
+    /// layer as stored in the [ANNOTATION_ESTARGZ_UNCOMPRESSED_SIZE] annotation,
+    /// if it is set and valid.
+    pub fn estargz_uncompressed_size(&self) -> Option<u64> {
+        self.annotation_map()?
+            .get(ANNOTATION_ESTARGZ_UNCOMPRESSED_SIZE)
+            .and_then(|s| s.parse().ok())
+    }
+
+    /// Sets the [ANNOTATION_ESTARGZ_UNCOMPRESSED_SIZE] annotation.
+    pub fn set_estargz_uncompressed_size(&mut self, size: u64) -> &mut Self {
+        self.annotation_map_mut().insert(
+            ANNOTATION_ESTARGZ_UNCOMPRESSED_SIZE.to_owned(),
+            size.to_string(),
+        );
+        self
+    }
+
+    /// Returns the ratio between the uncompressed and the compressed size of
+    /// an eStargz layer, if its uncompressed size is known and the content is
+    /// not empty.
+    pub fn compression_ratio(&self) -> Option<f64> {
+        match (self.estargz_uncompressed_size()?, self.size) {
+            (_, 0) => None,
+            (uncompressed, size) => Some(uncompressed as f64 / size as f64),
+        }
+    }
+}
+
+#[cfg(feature = "proptests")]
+impl Arbitrary for Platform {
+    fn arbitrary(g: &mut Gen) -> Platform {
+        Platform {
+            architecture: Arch::arbitrary(g),
+            os: Os::arbitrary(g),
+            os_version: some_none_generator_util(g),
+            os_features: some_none_generator_util(g),
+            variant: some_none_generator_util(g),
+            features: some_none_generator_util(g),
+        }
+    }
+}
+
+#[cfg(feature = "proptests")]
+impl Arbitrary for Descriptor {
+    fn arbitrary(g: &mut Gen) -> Descriptor {
+        Descriptor {
+            media_type: MediaType::arbitrary(g),
+            digest: Digest::arbitrary(g),
+            size: u64::arbitrary(g),
+            urls: some_none_generator_util(g),
+            annotations: some_none_generator_util(g),
+            platform: some_none_generator_util(g),
+            artifact_type: some_none_generator_util(g),
+            data: None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -192,6 +602,151 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_empty_config() {
+        use sha2::{Digest as _, Sha256};
+
+        let config = Descriptor::empty_config();
+        let hash = format!("{:x}", Sha256::digest(b"{}"));
+        assert_eq!(config.as_digest_sha256(), Some(hash.as_str()));
+        assert_eq!(
+            serde_json::to_string(&config).unwrap(),
+            r#"{"mediaType":"application/vnd.oci.empty.v1+json","digest":"sha256:44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a","size":2,"data":"e30="}"#
+        );
+    }
+
+    #[test]
+    fn test_uncompressed_annotations() {
+        let digest = Digest::from_str(
+            "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356",
+        )
+        .unwrap();
+        let mut descriptor = Descriptor::new(MediaType::ImageLayerGzip, 1000, digest.clone());
+        assert_eq!(descriptor.uncompressed_digest(), None);
+        assert_eq!(descriptor.estargz_uncompressed_size(), None);
+        assert_eq!(descriptor.compression_ratio(), None);
+
+        descriptor.set_uncompressed_digest(digest.clone());
+        descriptor.set_estargz_uncompressed_size(2500);
+        assert_eq!(descriptor.uncompressed_digest(), Some(digest));
+        assert_eq!(descriptor.estargz_uncompressed_size(), Some(2500));
+        assert_eq!(descriptor.compression_ratio(), Some(2.5));
+        assert_eq!(
+            descriptor.annotations().as_ref().unwrap()[ANNOTATION_ESTARGZ_UNCOMPRESSED_SIZE],
+            "2500"
+        );
+    }
+
+    #[test]
+    fn test_platform_reserved_features() {
+        let platform_str = r#"{"architecture":"amd64","os":"linux","features":["sse4"]}"#;
+        let platform: Platform = serde_json::from_str(platform_str).unwrap();
+        assert!(platform.uses_reserved_features());
+        assert_eq!(serde_json::to_string(&platform).unwrap(), platform_str);
+
+        let platform: Platform =
+            serde_json::from_str(r#"{"architecture":"amd64","os":"linux"}"#).unwrap();
+        assert!(!platform.uses_reserved_features());
+    }
+
+    #[test]
+    fn test_platform_notation() {
+        let mut platforms: Vec<Platform> = [
+            "linux/arm64/v8",
+            "Linux/AMD64",
+            "windows/amd64",
+            "linux/arm64",
+        ]
+        .iter()
+        .map(|p| p.parse().unwrap())
+        .collect();
+        platforms.sort();
+        let platforms: Vec<_> = platforms.iter().map(Platform::to_string).collect();
+        assert_eq!(
+            platforms,
+            [
+                "linux/amd64",
+                "linux/arm64",
+                "linux/arm64/v8",
+                "windows/amd64"
+            ]
+        );
+
+        for invalid in ["", "linux", "linux/", "/amd64", "linux/arm/v7/x"] {
+            assert!(invalid.parse::<Platform>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_platform_ord_consistent_with_eq() {
+        let base: Platform = "windows/amd64".parse().unwrap();
+        let mut with_version = base.clone();
+        with_version.set_os_version(Some("10.0.17763.1040".to_string()));
+        let mut with_features = base.clone();
+        with_features.set_os_features(Some(vec!["win32k".to_string()]));
+        let mut other_os = base.clone();
+        other_os.set_os(Os::Other("windows".to_string()));
+        let mut other_arch = base.clone();
+        other_arch.set_architecture(Arch::Other("amd64".to_string()));
+
+        let platforms = [base, with_version, with_features, other_os, other_arch];
+        for a in &platforms {
+            for b in &platforms {
+                assert_eq!(a.cmp(b) == Ordering::Equal, a == b, "{a:?} {b:?}");
+            }
+        }
+        let set: std::collections::BTreeSet<_> = platforms.iter().collect();
+        assert_eq!(set.len(), platforms.len());
+    }
+
+    #[test]
+    fn test_urls_allowed_by() {
+        let digest = Digest::from_str(
+            "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356",
+        )
+        .unwrap();
+        let mut descriptor =
+            Descriptor::new(MediaType::ImageLayerNonDistributableGzip, 1000, digest);
+        descriptor.set_urls(Some(vec![
+            "https://mcr.microsoft.com/layer".to_string(),
+            "http://mcr.microsoft.com/layer".to_string(),
+            "https://user@cdn.example.com:8443/layer".to_string(),
+            "https://example.com.evil.org/layer".to_string(),
+            "file:///etc/passwd".to_string(),
+            "https://evil.com\\@mcr.microsoft.com/x".to_string(),
+            "https://evil.com%2f@mcr.microsoft.com/x".to_string(),
+            "https://mcr.microsoft.com%2eevil.com/x".to_string(),
+            "https://mcr.microsoft.com\t/x".to_string(),
+        ]));
+
+        let policy = UrlPolicy::default();
+        assert_eq!(descriptor.urls_allowed_by(&policy).len(), 3);
+
+        let policy = UrlPolicy {
+            allow_http: true,
+            allowed_hosts: Some(vec![
+                "mcr.microsoft.com".to_string(),
+                "*.example.com".to_string(),
+            ]),
+            max_size: None,
+        };
+        assert_eq!(
+            descriptor.urls_allowed_by(&policy),
+            [
+                "https://mcr.microsoft.com/layer",
+                "http://mcr.microsoft.com/layer",
+                "https://user@cdn.example.com:8443/layer",
+            ]
+        );
+        assert!(!policy.allows_url("https://evil.com\\@mcr.microsoft.com/x"));
+
+        let policy = UrlPolicy {
+            max_size: Some(999),
+            ..Default::default()
+        };
+        assert!(descriptor.urls_allowed_by(&policy).is_empty());
+    }
+
     #[test]
     fn test_malformed_digest() {
         let descriptor_str = r#"{
@@ -202,4 +757,30 @@ mod tests {
             "artifactType":"application/spdx+json"}"#;
         assert!(serde_json::from_str::<Descriptor>(descriptor_str).is_err());
     }
+
+    #[test]
+    fn hash_descriptors() {
+        let digest = Digest::from_str(
+            "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356",
+        )
+        .unwrap();
+        let mut a = Descriptor::new(MediaType::ImageManifest, 100, digest.clone());
+        let mut b = a.clone();
+        for i in 0..16 {
+            a.annotation_map_mut().insert(i.to_string(), i.to_string());
+        }
+        for i in (0..16).rev() {
+            b.annotation_map_mut().insert(i.to_string(), i.to_string());
+        }
+        b.set_platform(Some(Platform::default()));
+        a.set_platform(Some(Platform::default()));
+        let set: std::collections::HashSet<_> = [a.clone(), b].into_iter().collect();
+        assert_eq!(set.len(), 1);
+
+        a.set_size(101);
+        let set: std::collections::HashSet<_> = [a.clone(), set.into_iter().next().unwrap()]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 2);
+    }
 }
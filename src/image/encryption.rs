@@ -0,0 +1,173 @@
+//! Media types and annotations of encrypted layers, as defined by
+//! [ocicrypt](https://github.com/containers/ocicrypt/blob/main/docs/spec.md).
+
+use super::{
+    Annotated, Descriptor, MediaType, ANNOTATION_ENC_KEYS_PREFIX, ANNOTATION_ENC_PUB_OPTS,
+};
+
+/// The suffix appended to the media type of encrypted content, e.g.
+/// `application/vnd.oci.image.layer.v1.tar+gzip+encrypted`.
+pub const MEDIA_TYPE_ENCRYPTED_SUFFIX: &str = "+encrypted";
+
+impl MediaType {
+    /// Returns true if this is the media type of encrypted content.
+    pub fn is_encrypted(&self) -> bool {
+        self.as_ref().ends_with(MEDIA_TYPE_ENCRYPTED_SUFFIX)
+    }
+
+    /// Returns the media type of this content once encrypted. Encrypted
+    /// media types are returned unchanged.
+    ///
+    /// ```
+    /// use oci_spec::image::MediaType;
+    ///
+    /// let encrypted = MediaType::ImageLayerGzip.to_encrypted();
+    /// assert_eq!(
+    ///     encrypted.as_ref(),
+    ///     "application/vnd.oci.image.layer.v1.tar+gzip+encrypted"
+    /// );
+    /// assert!(encrypted.is_encrypted());
+    /// assert_eq!(encrypted.to_decrypted(), Some(MediaType::ImageLayerGzip));
+    /// ```
+    pub fn to_encrypted(&self) -> MediaType {
+        match self.is_encrypted() {
+            true => self.clone(),
+            false => format!("{self}{MEDIA_TYPE_ENCRYPTED_SUFFIX}")
+                .as_str()
+                .into(),
+        }
+    }
+
+    /// Returns the media type of the decrypted content, or `None` if this
+    /// is not the media type of encrypted content.
+    pub fn to_decrypted(&self) -> Option<MediaType> {
+        self.as_ref()
+            .strip_suffix(MEDIA_TYPE_ENCRYPTED_SUFFIX)
+            .map(MediaType::from)
+    }
+}
+
+impl Descriptor {
+    /// Returns true if the descriptor references encrypted content.
+    pub fn is_encrypted(&self) -> bool {
+        self.media_type().is_encrypted()
+    }
+
+    /// Returns the names of the key wrapping protocols, like `jwe` or
+    /// `pkcs7`, which have wrapped keys in the annotations, sorted by name.
+    pub fn encryption_protocols(&self) -> Vec<&str> {
+        let mut protocols: Vec<_> = self
+            .annotation_map()
+            .into_iter()
+            .flatten()
+            .filter_map(|(key, _)| key.strip_prefix(ANNOTATION_ENC_KEYS_PREFIX))
+            .collect();
+        protocols.sort_unstable();
+        protocols
+    }
+
+    /// Returns the base64 encoded keys wrapped with `protocol`.
+    pub fn encryption_keys(&self, protocol: &str) -> Vec<&str> {
+        self.annotation_map()
+            .and_then(|a| a.get(&format!("{ANNOTATION_ENC_KEYS_PREFIX}{protocol}")))
+            .map(|keys| keys.split(',').filter(|k| !k.is_empty()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Sets the base64 encoded keys wrapped with `protocol`. The annotation
+    /// is removed if `keys` is empty.
+    ///
+    /// ```
+    /// use oci_spec::image::{Descriptor, Digest, MediaType};
+    /// use std::str::FromStr;
+    ///
+    /// let digest = Digest::from_str(
+    ///     "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356",
+    /// ).unwrap();
+    /// let mut layer = Descriptor::new(MediaType::ImageLayerGzip.to_encrypted(), 100, digest);
+    /// layer.set_encryption_keys("jwe", ["a2V5MQ==", "a2V5Mg=="]);
+    ///
+    /// assert_eq!(layer.encryption_protocols(), ["jwe"]);
+    /// assert_eq!(layer.encryption_keys("jwe"), ["a2V5MQ==", "a2V5Mg=="]);
+    /// ```
+    pub fn set_encryption_keys<I, S>(&mut self, protocol: &str, keys: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let key = format!("{ANNOTATION_ENC_KEYS_PREFIX}{protocol}");
+        let keys: Vec<_> = keys.into_iter().map(|k| k.as_ref().to_owned()).collect();
+        match keys.is_empty() {
+            true => {
+                self.annotation_map_mut().remove(&key);
+            }
+            false => {
+                self.annotation_map_mut().insert(key, keys.join(","));
+            }
+        }
+        self
+    }
+
+    /// Returns the base64 encoded public options of the cipher the content
+    /// was encrypted with, as stored in the [ANNOTATION_ENC_PUB_OPTS]
+    /// annotation.
+    pub fn encryption_pub_opts(&self) -> Option<&str> {
+        self.annotation_map()?
+            .get(ANNOTATION_ENC_PUB_OPTS)
+            .map(String::as_str)
+    }
+
+    /// Sets the [ANNOTATION_ENC_PUB_OPTS] annotation.
+    pub fn set_encryption_pub_opts(&mut self, pub_opts: impl Into<String>) -> &mut Self {
+        self.annotation_map_mut()
+            .insert(ANNOTATION_ENC_PUB_OPTS.to_owned(), pub_opts.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::image::{Digest, ANNOTATION_ENC_KEYS_PKCS7};
+
+    #[test]
+    fn encrypted_media_types() {
+        let json = r#""application/vnd.oci.image.layer.v1.tar+zstd+encrypted""#;
+        let media_type: MediaType = serde_json::from_str(json).unwrap();
+        assert!(media_type.is_encrypted());
+        assert_eq!(media_type, MediaType::ImageLayerZstd.to_encrypted());
+        assert_eq!(media_type.to_encrypted(), media_type);
+        assert_eq!(media_type.to_decrypted(), Some(MediaType::ImageLayerZstd));
+        assert!(!MediaType::ImageLayerZstd.is_encrypted());
+        assert_eq!(MediaType::ImageLayerZstd.to_decrypted(), None);
+    }
+
+    #[test]
+    fn encryption_annotations() {
+        let digest = Digest::from_str(
+            "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356",
+        )
+        .unwrap();
+        let mut layer = Descriptor::new(MediaType::ImageLayer.to_encrypted(), 100, digest);
+        assert!(layer.is_encrypted());
+        assert!(layer.encryption_protocols().is_empty());
+        assert_eq!(layer.encryption_pub_opts(), None);
+
+        layer
+            .set_encryption_keys("pkcs7", ["a2V5"])
+            .set_encryption_keys("jwe", ["a2V5"])
+            .set_encryption_pub_opts("e30=");
+        assert_eq!(layer.encryption_protocols(), ["jwe", "pkcs7"]);
+        assert_eq!(
+            layer.annotations().as_ref().unwrap()[ANNOTATION_ENC_KEYS_PKCS7],
+            "a2V5"
+        );
+        assert_eq!(layer.encryption_pub_opts(), Some("e30="));
+
+        layer.set_encryption_keys("jwe", Vec::<String>::new());
+        assert_eq!(layer.encryption_protocols(), ["pkcs7"]);
+        assert!(layer.encryption_keys("jwe").is_empty());
+    }
+}
@@ -1,24 +1,38 @@
-use super::{Descriptor, MediaType};
+use super::{Annotated, AnnotationsExt, Descriptor, ImageManifest, MediaType, Platform};
 use crate::{
-    error::{OciSpecError, Result},
-    from_file, from_reader, to_file, to_string, to_writer,
+    error::{oci_error, OciSpecError, Result},
+    from_reader, from_reader_strict, to_string, to_writer,
 };
+#[cfg(feature = "filesystem")]
+use crate::{from_file, from_file_strict, to_file};
 use derive_builder::Builder;
-use getset::{CopyGetters, Getters, Setters};
+use getset::{CopyGetters, Getters, MutGetters, Setters};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "filesystem")]
+use std::path::Path;
 use std::{
     collections::HashMap,
     fmt::Display,
     io::{Read, Write},
-    path::Path,
 };
 
 /// The expected schema version; equals 2 for compatibility with older versions of Docker.
 pub const SCHEMA_VERSION: u32 = 2;
 
 #[derive(
-    Builder, Clone, CopyGetters, Debug, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
+    Builder,
+    Clone,
+    CopyGetters,
+    Debug,
+    Deserialize,
+    Eq,
+    Getters,
+    MutGetters,
+    Setters,
+    PartialEq,
+    Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     pattern = "owned",
@@ -35,20 +49,20 @@ pub struct ImageIndex {
     /// backward compatibility with older versions of Docker. The
     /// value of this field will not change. This field MAY be
     /// removed in a future version of the specification.
-    #[getset(get_copy = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get_copy = "pub", set = "pub")]
     schema_version: u32,
     /// This property is reserved for use, to maintain compatibility. When
     /// used, this field contains the media type of this document,
     /// which differs from the descriptor use of mediaType.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     #[builder(default)]
     media_type: Option<MediaType>,
     /// This OPTIONAL property contains the type of an artifact when the manifest is used for an
     /// artifact. If defined, the value MUST comply with RFC 6838, including the naming
     /// requirements in its section 4.2, and MAY be registered with IANA.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     #[builder(default)]
     artifact_type: Option<MediaType>,
     /// This REQUIRED property contains a list of manifests for specific
@@ -59,7 +73,7 @@ pub struct ImageIndex {
     /// This OPTIONAL property specifies a descriptor of another manifest. This value, used by the
     /// referrers API, indicates a relationship to the specified manifest.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     #[builder(default)]
     subject: Option<Descriptor>,
     /// This OPTIONAL property contains arbitrary metadata for the image
@@ -70,6 +84,54 @@ pub struct ImageIndex {
     annotations: Option<HashMap<String, String>>,
 }
 
+option_getters! {
+    ImageIndex {
+        media_type_ref => media_type: MediaType,
+        artifact_type_ref => artifact_type: MediaType,
+        subject_ref => subject: Descriptor,
+        annotations_ref => annotations: HashMap<String, String>,
+    }
+}
+
+impl ImageIndexBuilder {
+    /// Creates a builder of a multi-platform index referencing `manifests`.
+    /// The descriptor of each manifest carries its platform and refers to
+    /// the [canonical form](ImageManifest::to_canonical_vec) of the manifest,
+    /// so the manifests have to be pushed in exactly that form.
+    ///
+    /// ```
+    /// use oci_spec::image::{Arch, ImageIndexBuilder, ImageManifest, Os, PlatformBuilder};
+    ///
+    /// # fn example(amd64: ImageManifest, arm64: ImageManifest) -> oci_spec::Result<()> {
+    /// let platform = |arch| PlatformBuilder::default().os(Os::Linux).architecture(arch).build();
+    /// let index = ImageIndexBuilder::from_manifests([
+    ///     (platform(Arch::Amd64)?, amd64),
+    ///     (platform(Arch::ARM64)?, arm64),
+    /// ])?
+    /// .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_manifests<I>(manifests: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (Platform, ImageManifest)>,
+    {
+        let manifests = manifests
+            .into_iter()
+            .map(|(platform, manifest)| {
+                let mut descriptor = manifest.descriptor()?;
+                descriptor.set_platform(Some(platform));
+                Ok(descriptor)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self::default()
+            .schema_version(SCHEMA_VERSION)
+            .media_type(MediaType::ImageIndex)
+            .manifests(manifests))
+    }
+}
+
 impl ImageIndex {
     /// Attempts to load an image index from a file.
     /// # Errors
@@ -83,6 +145,7 @@ impl ImageIndex {
     ///
     /// let image_index = ImageIndex::from_file("index.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<ImageIndex> {
         from_file(path)
     }
@@ -103,6 +166,42 @@ impl ImageIndex {
         from_reader(reader)
     }
 
+    /// Attempts to load an image index from a file, rejecting any field which is
+    /// not defined by the specification.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
+    /// if the file does not exist or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the image index
+    /// cannot be deserialized, contains unknown fields or duplicate keys.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::image::ImageIndex;
+    ///
+    /// let image_index = ImageIndex::from_file_strict("index.json").unwrap();
+    /// ```
+    #[cfg(feature = "filesystem")]
+    pub fn from_file_strict<P: AsRef<Path>>(path: P) -> Result<ImageIndex> {
+        from_file_strict(path)
+    }
+
+    /// Attempts to load an image index from a stream, rejecting any field which is
+    /// not defined by the specification.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe)
+    /// if the image index cannot be deserialized, contains unknown fields or duplicate
+    /// keys.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::image::ImageIndex;
+    /// use std::fs::File;
+    ///
+    /// let reader = File::open("index.json").unwrap();
+    /// let image_index = ImageIndex::from_reader_strict(reader).unwrap();
+    /// ```
+    pub fn from_reader_strict<R: Read>(reader: R) -> Result<ImageIndex> {
+        from_reader_strict(reader)
+    }
+
     /// Attempts to write an image index to a file as JSON. If the file already exists, it
     /// will be overwritten.
     /// # Errors
@@ -115,6 +214,7 @@ impl ImageIndex {
     /// let image_index = ImageIndex::from_file("index.json").unwrap();
     /// image_index.to_file("my-index.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         to_file(&self, path, false)
     }
@@ -131,6 +231,7 @@ impl ImageIndex {
     /// let image_index = ImageIndex::from_file("index.json").unwrap();
     /// image_index.to_file_pretty("my-index.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn to_file_pretty<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         to_file(&self, path, true)
     }
@@ -167,6 +268,60 @@ impl ImageIndex {
         to_writer(&self, writer, true)
     }
 
+    /// Attempts to load an image index from a file asynchronously.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
+    /// if the file does not exist or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the image index
+    /// cannot be deserialized.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::image::ImageIndex;
+    ///
+    /// # async fn example() {
+    /// let image_index = ImageIndex::from_file_async("index.json").await.unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn from_file_async<P: AsRef<Path>>(path: P) -> Result<ImageIndex> {
+        crate::from_file_async(path).await
+    }
+
+    /// Attempts to load an image index from an asynchronous stream.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
+    /// if the stream cannot be read or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the image index
+    /// cannot be deserialized.
+    #[cfg(feature = "async")]
+    pub async fn from_reader_async<R: tokio::io::AsyncRead + Unpin>(
+        reader: R,
+    ) -> Result<ImageIndex> {
+        crate::from_reader_async(reader).await
+    }
+
+    /// Attempts to write an image index to a file as JSON asynchronously. If the file
+    /// already exists, it will be overwritten.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
+    /// the image index cannot be serialized.
+    #[cfg(feature = "async")]
+    pub async fn to_file_async<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        crate::to_file_async(self, path, false).await
+    }
+
+    /// Attempts to write an image index to an asynchronous stream as JSON.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
+    /// the image index cannot be serialized.
+    #[cfg(feature = "async")]
+    pub async fn to_writer_async<W: tokio::io::AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+    ) -> Result<()> {
+        crate::to_writer_async(self, writer, false).await
+    }
+
     /// Attempts to write an image index to a string as JSON.
     /// # Errors
     /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
@@ -196,6 +351,120 @@ impl ImageIndex {
     pub fn to_string_pretty(&self) -> Result<String> {
         to_string(&self, true)
     }
+
+    /// Serializes the image index into its canonical form, compact JSON with
+    /// sorted object keys. These are the bytes [Self::descriptor] refers to.
+    pub fn to_canonical_vec(&self) -> Result<Vec<u8>> {
+        crate::to_sorted_vec(self)
+    }
+
+    /// Returns the descriptor of the image index in its
+    /// [canonical form](Self::to_canonical_vec), ready to be embedded into
+    /// another document.
+    pub fn descriptor(&self) -> Result<Descriptor> {
+        let media_type = self.media_type.clone().unwrap_or(MediaType::ImageIndex);
+        let mut descriptor = Descriptor::from_content(media_type, &self.to_canonical_vec()?);
+        descriptor.set_artifact_type(self.artifact_type.clone());
+        Ok(descriptor)
+    }
+}
+
+/// Tags of the `index.json` of an image layout, which are stored in the
+/// [ANNOTATION_REF_NAME](super::ANNOTATION_REF_NAME) annotation of the descriptors.
+impl ImageIndex {
+    /// Returns the tags of the descriptors, in the order of the descriptors.
+    pub fn tags(&self) -> Vec<&str> {
+        self.manifests
+            .iter()
+            .filter_map(|m| m.annotations().ref_name())
+            .collect()
+    }
+
+    /// Returns the descriptor tagged with `tag`, if any.
+    ///
+    /// ```
+    /// use oci_spec::image::{Descriptor, ImageIndex, MediaType};
+    ///
+    /// let mut index = ImageIndex::default();
+    /// index.set_tag("v1.2", Descriptor::empty_config()).unwrap();
+    ///
+    /// assert_eq!(index.tags(), ["v1.2"]);
+    /// assert_eq!(
+    ///     index.resolve_tag("v1.2").unwrap().digest(),
+    ///     Descriptor::empty_config().digest()
+    /// );
+    /// assert!(index.resolve_tag("latest").is_none());
+    /// ```
+    pub fn resolve_tag(&self, tag: &str) -> Option<&Descriptor> {
+        self.manifests
+            .iter()
+            .find(|m| m.annotations().ref_name() == Some(tag))
+    }
+
+    /// Tags `descriptor` with `tag` and adds it to the index, replacing the
+    /// descriptor previously tagged with `tag`.
+    ///
+    /// # Errors
+    /// Fails if `tag` does not match the grammar of the image layout spec,
+    /// which is a `/` separated list of alphanumeric components, which may
+    /// contain the separators `-`, `.`, `_`, `:`, `@`, `+` and `--`.
+    pub fn set_tag(&mut self, tag: &str, mut descriptor: Descriptor) -> Result<()> {
+        if !is_valid_ref_name(tag) {
+            return Err(oci_error(format!("invalid ref name {tag}")));
+        }
+        self.remove_tag(tag);
+        descriptor.annotations_mut().set_ref_name(tag);
+        self.manifests.push(descriptor);
+        Ok(())
+    }
+
+    /// Removes the descriptor tagged with `tag` from the index and returns
+    /// it, if any.
+    pub fn remove_tag(&mut self, tag: &str) -> Option<Descriptor> {
+        let position = self
+            .manifests
+            .iter()
+            .position(|m| m.annotations().ref_name() == Some(tag))?;
+        Some(self.manifests.remove(position))
+    }
+}
+
+/// ref ::= component ("/" component)*
+fn is_valid_ref_name(tag: &str) -> bool {
+    tag.split('/').all(is_valid_ref_component)
+}
+
+/// component ::= alphanum (separator alphanum)*
+/// separator ::= [-._:@+] | "--"
+fn is_valid_ref_component(component: &str) -> bool {
+    let mut chars = component.chars().peekable();
+    loop {
+        let mut alphanum = false;
+        while chars.next_if(char::is_ascii_alphanumeric).is_some() {
+            alphanum = true;
+        }
+        if !alphanum {
+            return false;
+        }
+        match chars.next() {
+            None => return true,
+            Some('-') => {
+                chars.next_if_eq(&'-');
+            }
+            Some('.' | '_' | ':' | '@' | '+') => {}
+            Some(_) => return false,
+        }
+    }
+}
+
+impl Annotated for ImageIndex {
+    fn annotation_map(&self) -> Option<&HashMap<String, String>> {
+        self.annotations.as_ref()
+    }
+
+    fn annotation_map_mut(&mut self) -> &mut HashMap<String, String> {
+        self.annotations.get_or_insert_with(HashMap::new)
+    }
 }
 
 impl Default for ImageIndex {
@@ -286,6 +555,7 @@ mod tests {
         PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/data/index.json")
     }
 
+    #[cfg(feature = "filesystem")]
     #[test]
     fn load_index_from_file() {
         // arrange
@@ -312,6 +582,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "filesystem")]
     #[test]
     fn save_index_to_file() {
         // arrange
@@ -357,4 +628,94 @@ mod tests {
         let expected = fs::read_to_string(get_index_path()).expect("read expected");
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn index_from_manifests() {
+        let manifest =
+            ImageManifest::artifact(MediaType::from("application/example"), vec![], None);
+        let platform = |arch| {
+            PlatformBuilder::default()
+                .os(Os::Linux)
+                .architecture(arch)
+                .build()
+                .unwrap()
+        };
+        let index = ImageIndexBuilder::from_manifests([
+            (platform(Arch::Amd64), manifest.clone()),
+            (platform(Arch::ARM64), manifest.clone()),
+        ])
+        .unwrap()
+        .build()
+        .unwrap();
+
+        assert_eq!(index.media_type(), &Some(MediaType::ImageIndex));
+        assert_eq!(index.manifests().len(), 2);
+        let descriptor = &index.manifests()[1];
+        let content = manifest.to_canonical_vec().unwrap();
+        assert_eq!(descriptor.size(), content.len() as u64);
+        assert_eq!(
+            descriptor.platform().as_ref().unwrap().architecture(),
+            &Arch::ARM64
+        );
+        assert_eq!(descriptor.artifact_type(), manifest.artifact_type());
+        assert_eq!(descriptor.digest(), index.manifests()[0].digest());
+        assert_eq!(
+            descriptor.as_digest_sha256().unwrap(),
+            format!("{:x}", <sha2::Sha256 as sha2::Digest>::digest(&content))
+        );
+    }
+
+    #[test]
+    fn tags() {
+        let mut index = create_index();
+        let amd64 = index.manifests()[1].clone();
+        let ppc64le = index.manifests()[0].clone();
+        assert!(index.tags().is_empty());
+
+        index.set_tag("v1.2", amd64.clone()).unwrap();
+        index.set_tag("latest", amd64.clone()).unwrap();
+        index.set_tag("v1.2", ppc64le.clone()).unwrap();
+        assert_eq!(index.tags(), ["latest", "v1.2"]);
+        assert_eq!(
+            index.resolve_tag("v1.2").unwrap().digest(),
+            ppc64le.digest()
+        );
+        assert_eq!(index.manifests().len(), 4);
+
+        assert_eq!(index.remove_tag("latest").unwrap().digest(), amd64.digest());
+        assert!(index.resolve_tag("latest").is_none());
+        assert!(index.remove_tag("latest").is_none());
+    }
+
+    #[test]
+    fn ref_names() {
+        for valid in ["v1.2", "latest", "a--b", "registry:5000/repo@x", "1+2_3"] {
+            assert!(is_valid_ref_name(valid), "{valid}");
+        }
+        for invalid in ["", "-a", "a-", "a---b", "a..b", "a//b", "/a", "a b", "ä"] {
+            assert!(!is_valid_ref_name(invalid), "{invalid}");
+        }
+        assert!(create_index()
+            .set_tag("a b", Descriptor::empty_config())
+            .is_err());
+    }
+
+    #[test]
+    fn referrer_index_round_trip() {
+        let subject = create_index().manifests()[0].clone();
+        let index = ImageIndexBuilder::default()
+            .schema_version(2u32)
+            .media_type(MediaType::ImageIndex)
+            .artifact_type(MediaType::from("application/vnd.example.bundle"))
+            .manifests(vec![])
+            .subject(subject.clone())
+            .build()
+            .expect("build referrer index");
+
+        let json = index.to_string().expect("to string");
+        assert!(json.contains(r#""artifactType":"application/vnd.example.bundle""#));
+        let parsed = ImageIndex::from_reader_strict(json.as_bytes()).expect("from reader");
+        assert_eq!(parsed, index);
+        assert_eq!(parsed.subject(), &Some(subject));
+    }
 }
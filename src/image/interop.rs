@@ -0,0 +1,224 @@
+//! Normalization of known quirks of manifest producers.
+//!
+//! Registries and build tools in the wild produce manifests which deviate
+//! slightly from the specification, for example by mixing Docker media types
+//! into OCI manifests. The functions of this module fix these deviations in
+//! place and report every change they made.
+
+use std::fmt::{self, Display, Formatter};
+
+use super::{compat, ImageManifest, MediaType};
+
+/// A change made while normalizing a document.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Change {
+    /// The path of the changed field, e.g. `layers[0].mediaType`.
+    pub field: String,
+    /// The previous value of the field, or `None` if it was missing.
+    pub from: Option<String>,
+    /// The new value of the field.
+    pub to: String,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self.from {
+            Some(from) => write!(f, "{}: {} -> {}", self.field, from, self.to),
+            None => write!(f, "{}: set to {}", self.field, self.to),
+        }
+    }
+}
+
+/// Fixes known producer quirks of an OCI [ImageManifest] and returns the
+/// changes which have been made:
+///
+/// - a missing `mediaType` is set to the OCI image manifest media type,
+/// - Docker media types of the manifest, its config and its layers are
+///   replaced by their OCI equivalents,
+/// - misspelled layer media types, like `tar.gzip` or `tar+gz`, are
+///   corrected.
+///
+/// ```
+/// use std::str::FromStr;
+/// use oci_spec::image::{interop, Descriptor, Digest, ImageManifestBuilder, MediaType};
+///
+/// let digest = Digest::from_str(
+///     "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356",
+/// ).unwrap();
+/// let config = Descriptor::new(
+///     MediaType::Other("application/vnd.docker.container.image.v1+json".to_string()),
+///     7,
+///     digest,
+/// );
+/// let mut manifest = ImageManifestBuilder::default()
+///     .schema_version(2u32)
+///     .config(config)
+///     .layers(vec![])
+///     .build()
+///     .unwrap();
+///
+/// let changes = interop::normalize_manifest(&mut manifest);
+/// assert_eq!(changes.len(), 2);
+/// assert_eq!(manifest.media_type(), &Some(MediaType::ImageManifest));
+/// assert_eq!(manifest.config().media_type(), &MediaType::ImageConfig);
+/// ```
+pub fn normalize_manifest(manifest: &mut ImageManifest) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    match manifest.media_type() {
+        None => changes.push(Change {
+            field: "mediaType".to_owned(),
+            from: None,
+            to: MediaType::ImageManifest.to_string(),
+        }),
+        // The document is an image manifest, so any non-standard media type
+        // is replaced by the OCI image manifest one, even if it stands for a
+        // different document like a Docker manifest list.
+        Some(media_type) => {
+            if normalize_media_type(media_type).is_some() {
+                changes.push(Change {
+                    field: "mediaType".to_owned(),
+                    from: Some(media_type.to_string()),
+                    to: MediaType::ImageManifest.to_string(),
+                });
+            }
+        }
+    }
+    if !changes.is_empty() {
+        manifest.set_media_type(Some(MediaType::ImageManifest));
+    }
+
+    let mut config = manifest.config().clone();
+    if let Some(to) = normalize_media_type(config.media_type()) {
+        changes.push(Change {
+            field: "config.mediaType".to_owned(),
+            from: Some(config.media_type().to_string()),
+            to: to.to_string(),
+        });
+        config.set_media_type(to);
+        manifest.set_config(config);
+    }
+
+    for (i, layer) in manifest.layers_mut().iter_mut().enumerate() {
+        if let Some(to) = normalize_media_type(layer.media_type()) {
+            changes.push(Change {
+                field: format!("layers[{i}].mediaType"),
+                from: Some(layer.media_type().to_string()),
+                to: to.to_string(),
+            });
+            layer.set_media_type(to);
+        }
+    }
+
+    changes
+}
+
+/// Returns the OCI media type a non-standard media type is known to stand
+/// for, or `None` if it needs no normalization. Docker media types are mapped
+/// with [compat::to_oci_media_type].
+fn normalize_media_type(media_type: &MediaType) -> Option<MediaType> {
+    let MediaType::Other(other) = media_type else {
+        return None;
+    };
+    let oci = compat::to_oci_media_type(media_type);
+    if &oci != media_type {
+        return Some(oci);
+    }
+    Some(match other.as_str() {
+        "application/vnd.oci.image.layer.v1.tar.gzip"
+        | "application/vnd.oci.image.layer.v1.tar+gz"
+        | "application/vnd.oci.image.layer.v1.tar.gz" => MediaType::ImageLayerGzip,
+        "application/vnd.oci.image.layer.v1.tar.zstd"
+        | "application/vnd.oci.image.layer.v1.tar+zst" => MediaType::ImageLayerZstd,
+        "application/vnd.oci.image.layer.nondistributable.v1.tar.gzip"
+        | "application/vnd.oci.image.layer.nondistributable.v1.tar+gz" => {
+            MediaType::ImageLayerNonDistributableGzip
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::image::{Descriptor, Digest, ImageManifestBuilder};
+
+    fn descriptor(media_type: &str) -> Descriptor {
+        let digest = Digest::from_str(
+            "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356",
+        )
+        .unwrap();
+        Descriptor::new(MediaType::from(media_type), 7, digest)
+    }
+
+    #[test]
+    fn normalize_docker_manifest() {
+        let mut manifest = ImageManifestBuilder::default()
+            .schema_version(2u32)
+            .media_type(MediaType::from(
+                "application/vnd.docker.distribution.manifest.v2+json",
+            ))
+            .config(descriptor("application/vnd.docker.container.image.v1+json"))
+            .layers(vec![
+                descriptor("application/vnd.docker.image.rootfs.diff.tar.gzip"),
+                descriptor("application/vnd.oci.image.layer.v1.tar+gzip"),
+                descriptor("application/vnd.oci.image.layer.v1.tar.gzip"),
+                descriptor("application/vnd.docker.image.rootfs.diff.tar.zstd"),
+            ])
+            .build()
+            .unwrap();
+
+        let changes = normalize_manifest(&mut manifest);
+        let fields: Vec<_> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            [
+                "mediaType",
+                "config.mediaType",
+                "layers[0].mediaType",
+                "layers[2].mediaType",
+                "layers[3].mediaType"
+            ]
+        );
+        assert_eq!(
+            changes[3].to_string(),
+            "layers[2].mediaType: application/vnd.oci.image.layer.v1.tar.gzip -> application/vnd.oci.image.layer.v1.tar+gzip"
+        );
+        assert_eq!(manifest.media_type(), &Some(MediaType::ImageManifest));
+        assert!(manifest.layers()[..3]
+            .iter()
+            .all(|l| l.media_type() == &MediaType::ImageLayerGzip));
+        assert_eq!(
+            manifest.layers()[3].media_type(),
+            &MediaType::ImageLayerZstd
+        );
+
+        assert!(normalize_manifest(&mut manifest).is_empty());
+    }
+
+    #[test]
+    fn normalize_manifest_list_media_type() {
+        let mut manifest = ImageManifestBuilder::default()
+            .schema_version(2u32)
+            .media_type(MediaType::from(
+                "application/vnd.docker.distribution.manifest.list.v2+json",
+            ))
+            .config(descriptor("application/vnd.oci.image.config.v1+json"))
+            .layers(vec![])
+            .build()
+            .unwrap();
+
+        let changes = normalize_manifest(&mut manifest);
+        assert_eq!(
+            changes,
+            [Change {
+                field: "mediaType".to_owned(),
+                from: Some("application/vnd.docker.distribution.manifest.list.v2+json".to_owned()),
+                to: MediaType::ImageManifest.to_string(),
+            }]
+        );
+        assert_eq!(manifest.media_type(), &Some(MediaType::ImageManifest));
+    }
+}
@@ -1,16 +1,23 @@
-use super::{Descriptor, MediaType};
+use super::{Annotated, ArtifactManifest, Descriptor, MediaType, SCHEMA_VERSION};
+#[cfg(feature = "proptests")]
+use crate::some_none_generator_util;
 use crate::{
     error::{OciSpecError, Result},
-    from_file, from_reader, to_file, to_string, to_writer,
+    from_reader, from_reader_strict, to_string, to_writer,
 };
+#[cfg(feature = "filesystem")]
+use crate::{from_file, from_file_strict, to_file};
 use derive_builder::Builder;
 use getset::{CopyGetters, Getters, MutGetters, Setters};
+#[cfg(feature = "proptests")]
+use quickcheck::{Arbitrary, Gen};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "filesystem")]
+use std::path::Path;
 use std::{
     collections::HashMap,
     fmt::Display,
     io::{Read, Write},
-    path::Path,
 };
 
 #[derive(
@@ -26,9 +33,11 @@ use std::{
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     pattern = "owned",
+    derive(Clone),
     setter(into, strip_option),
     build_fn(error = "OciSpecError")
 )]
@@ -42,13 +51,13 @@ pub struct ImageManifest {
     /// backward compatibility with older versions of Docker. The
     /// value of this field will not change. This field MAY be
     /// removed in a future version of the specification.
-    #[getset(get_copy = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get_copy = "pub", set = "pub")]
     schema_version: u32,
     /// This property is reserved for use, to maintain compatibility. When
     /// used, this field contains the media type of this document,
     /// which differs from the descriptor use of mediaType.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     #[builder(default)]
     media_type: Option<MediaType>,
     /// This OPTIONAL property contains the type of an artifact when the manifest is used for an
@@ -57,7 +66,7 @@ pub struct ImageManifest {
     /// MAY be registered with IANA. Implementations storing or copying image manifests MUST NOT
     /// error on encountering an artifactType that is unknown to the implementation.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     #[builder(default)]
     artifact_type: Option<MediaType>,
     /// This REQUIRED property references a configuration object for a
@@ -70,7 +79,7 @@ pub struct ImageManifest {
     ///
     /// Manifests concerned with portability SHOULD use one of the above
     /// media types.
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     config: Descriptor,
     /// Each item in the array MUST be a descriptor. The array MUST have the
     /// base layer at index 0. Subsequent layers MUST then follow in
@@ -83,7 +92,7 @@ pub struct ImageManifest {
     /// This OPTIONAL property specifies a descriptor of another manifest. This value, used by the
     /// referrers API, indicates a relationship to the specified manifest.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     #[builder(default)]
     subject: Option<Descriptor>,
     /// This OPTIONAL property contains arbitrary metadata for the image
@@ -95,7 +104,80 @@ pub struct ImageManifest {
     annotations: Option<HashMap<String, String>>,
 }
 
+option_getters! {
+    ImageManifest {
+        media_type_ref => media_type: MediaType,
+        artifact_type_ref => artifact_type: MediaType,
+        subject_ref => subject: Descriptor,
+        annotations_ref => annotations: HashMap<String, String>,
+    }
+}
+
+mutable_builder! {
+    /// Non-consuming builder for [`ImageManifest`], which allows setting
+    /// fields conditionally without reassigning the builder.
+    ImageManifestBuilderMut(ImageManifestBuilder) -> ImageManifest {
+        schema_version: u32,
+        media_type: MediaType,
+        artifact_type: MediaType,
+        config: Descriptor,
+        layers: Vec<Descriptor>,
+        subject: Descriptor,
+        annotations: HashMap<String, String>,
+    }
+}
+
 impl ImageManifest {
+    /// Creates the manifest of an artifact of type `artifact_type`, as
+    /// recommended by image-spec 1.1. The config is the
+    /// [empty descriptor](Descriptor::empty_config), as is the only layer if
+    /// there are no `blobs`.
+    ///
+    /// ```
+    /// use oci_spec::image::{Descriptor, ImageManifest, MediaType};
+    ///
+    /// let signature = MediaType::from("application/vnd.dev.cosign.artifact.sig.v1+json");
+    /// let manifest = ImageManifest::artifact(signature.clone(), vec![], None);
+    ///
+    /// assert_eq!(manifest.artifact_type(), &Some(signature));
+    /// assert_eq!(manifest.config(), &Descriptor::empty_config());
+    /// assert_eq!(manifest.layers(), &[Descriptor::empty_config()]);
+    /// ```
+    pub fn artifact(
+        artifact_type: MediaType,
+        blobs: Vec<Descriptor>,
+        subject: Option<Descriptor>,
+    ) -> Self {
+        let layers = match blobs.is_empty() {
+            true => vec![Descriptor::empty_config()],
+            false => blobs,
+        };
+        Self {
+            schema_version: SCHEMA_VERSION,
+            media_type: Some(MediaType::ImageManifest),
+            artifact_type: Some(artifact_type),
+            config: Descriptor::empty_config(),
+            layers,
+            subject,
+            annotations: None,
+        }
+    }
+
+    /// Returns the sum of the sizes of all layers as transferred, i.e. in their
+    /// compressed form.
+    pub fn layers_size(&self) -> u64 {
+        self.layers.iter().map(Descriptor::size).sum()
+    }
+
+    /// Returns the sum of the uncompressed sizes of all layers, if the eStargz
+    /// uncompressed size annotation is set on every layer.
+    pub fn layers_estargz_uncompressed_size(&self) -> Option<u64> {
+        self.layers
+            .iter()
+            .map(Descriptor::estargz_uncompressed_size)
+            .sum()
+    }
+
     /// Attempts to load an image manifest from a file.
     /// # Errors
     /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
@@ -108,6 +190,7 @@ impl ImageManifest {
     ///
     /// let image_manifest = ImageManifest::from_file("manifest.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<ImageManifest> {
         from_file(path)
     }
@@ -128,6 +211,42 @@ impl ImageManifest {
         from_reader(reader)
     }
 
+    /// Attempts to load an image manifest from a file, rejecting any field which is
+    /// not defined by the specification.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
+    /// if the file does not exist or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the image manifest
+    /// cannot be deserialized, contains unknown fields or duplicate keys.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::image::ImageManifest;
+    ///
+    /// let image_manifest = ImageManifest::from_file_strict("manifest.json").unwrap();
+    /// ```
+    #[cfg(feature = "filesystem")]
+    pub fn from_file_strict<P: AsRef<Path>>(path: P) -> Result<ImageManifest> {
+        from_file_strict(path)
+    }
+
+    /// Attempts to load an image manifest from a stream, rejecting any field which is
+    /// not defined by the specification.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe)
+    /// if the image manifest cannot be deserialized, contains unknown fields or duplicate
+    /// keys.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::image::ImageManifest;
+    /// use std::fs::File;
+    ///
+    /// let reader = File::open("manifest.json").unwrap();
+    /// let image_manifest = ImageManifest::from_reader_strict(reader).unwrap();
+    /// ```
+    pub fn from_reader_strict<R: Read>(reader: R) -> Result<ImageManifest> {
+        from_reader_strict(reader)
+    }
+
     /// Attempts to write an image manifest to a file as JSON. If the file already exists, it
     /// will be overwritten.
     /// # Errors
@@ -140,6 +259,7 @@ impl ImageManifest {
     /// let image_manifest = ImageManifest::from_file("manifest.json").unwrap();
     /// image_manifest.to_file("my-manifest.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         to_file(&self, path, false)
     }
@@ -156,6 +276,7 @@ impl ImageManifest {
     /// let image_manifest = ImageManifest::from_file("manifest.json").unwrap();
     /// image_manifest.to_file_pretty("my-manifest.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn to_file_pretty<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         to_file(&self, path, true)
     }
@@ -192,6 +313,60 @@ impl ImageManifest {
         to_writer(&self, writer, true)
     }
 
+    /// Attempts to load an image manifest from a file asynchronously.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
+    /// if the file does not exist or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the image manifest
+    /// cannot be deserialized.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::image::ImageManifest;
+    ///
+    /// # async fn example() {
+    /// let image_manifest = ImageManifest::from_file_async("manifest.json").await.unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn from_file_async<P: AsRef<Path>>(path: P) -> Result<ImageManifest> {
+        crate::from_file_async(path).await
+    }
+
+    /// Attempts to load an image manifest from an asynchronous stream.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
+    /// if the stream cannot be read or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the image manifest
+    /// cannot be deserialized.
+    #[cfg(feature = "async")]
+    pub async fn from_reader_async<R: tokio::io::AsyncRead + Unpin>(
+        reader: R,
+    ) -> Result<ImageManifest> {
+        crate::from_reader_async(reader).await
+    }
+
+    /// Attempts to write an image manifest to a file as JSON asynchronously. If the file
+    /// already exists, it will be overwritten.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
+    /// the image manifest cannot be serialized.
+    #[cfg(feature = "async")]
+    pub async fn to_file_async<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        crate::to_file_async(self, path, false).await
+    }
+
+    /// Attempts to write an image manifest to an asynchronous stream as JSON.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
+    /// the image manifest cannot be serialized.
+    #[cfg(feature = "async")]
+    pub async fn to_writer_async<W: tokio::io::AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+    ) -> Result<()> {
+        crate::to_writer_async(self, writer, false).await
+    }
+
     /// Attempts to write an image manifest to a string as JSON.
     /// # Errors
     /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
@@ -221,6 +396,46 @@ impl ImageManifest {
     pub fn to_string_pretty(&self) -> Result<String> {
         to_string(&self, true)
     }
+
+    /// Serializes the image manifest into its canonical form, compact JSON with
+    /// sorted object keys. These are the bytes [Self::descriptor] refers to.
+    pub fn to_canonical_vec(&self) -> Result<Vec<u8>> {
+        crate::to_sorted_vec(self)
+    }
+
+    /// Returns the descriptor of the image manifest in its
+    /// [canonical form](Self::to_canonical_vec), ready to be embedded into
+    /// another document.
+    pub fn descriptor(&self) -> Result<Descriptor> {
+        let media_type = self.media_type.clone().unwrap_or(MediaType::ImageManifest);
+        let mut descriptor = Descriptor::from_content(media_type, &self.to_canonical_vec()?);
+        descriptor.set_artifact_type(self.artifact_type.clone());
+        Ok(descriptor)
+    }
+}
+
+impl Annotated for ImageManifest {
+    fn annotation_map(&self) -> Option<&HashMap<String, String>> {
+        self.annotations.as_ref()
+    }
+
+    fn annotation_map_mut(&mut self) -> &mut HashMap<String, String> {
+        self.annotations.get_or_insert_with(HashMap::new)
+    }
+}
+
+/// Converts a manifest of the deprecated artifact manifest format into an
+/// image manifest, using [ImageManifest::artifact].
+impl From<ArtifactManifest> for ImageManifest {
+    fn from(artifact: ArtifactManifest) -> Self {
+        let mut manifest = ImageManifest::artifact(
+            artifact.artifact_type().clone(),
+            artifact.blobs().clone(),
+            artifact.subject().clone(),
+        );
+        manifest.annotations = artifact.annotations().clone();
+        manifest
+    }
 }
 
 /// This ToString trait is automatically implemented for any type which implements the Display trait.
@@ -239,6 +454,26 @@ impl Display for ImageManifest {
     }
 }
 
+#[cfg(feature = "proptests")]
+impl Arbitrary for ImageManifest {
+    fn arbitrary(g: &mut Gen) -> ImageManifest {
+        ImageManifest {
+            schema_version: SCHEMA_VERSION,
+            media_type: g
+                .choose(&[None, Some(MediaType::ImageManifest)])
+                .unwrap()
+                .clone(),
+            artifact_type: some_none_generator_util(g),
+            config: Descriptor::arbitrary(g),
+            layers: (0..usize::arbitrary(g) % 4)
+                .map(|_| Descriptor::arbitrary(g))
+                .collect(),
+            subject: some_none_generator_util(g),
+            annotations: some_none_generator_util(g),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{fs, path::PathBuf, str::FromStr};
@@ -297,6 +532,7 @@ mod tests {
         PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/data/manifest.json")
     }
 
+    #[cfg(feature = "filesystem")]
     #[test]
     fn load_manifest_from_file() {
         // arrange
@@ -319,6 +555,108 @@ mod tests {
         assert_eq!(manifest.layers().len(), 4);
     }
 
+    #[test]
+    fn builder_mut() {
+        let expected = create_manifest();
+        let mut builder = ImageManifestBuilderMut::default();
+        builder
+            .schema_version(expected.schema_version())
+            .config(expected.config().clone());
+        assert!(builder.build().is_err());
+
+        builder.layers(expected.layers().clone());
+        assert_eq!(builder.build().expect("build image manifest"), expected);
+    }
+
+    #[test]
+    fn mutate_in_place() {
+        let mut manifest = create_manifest();
+        *manifest.media_type_mut() = Some(MediaType::ImageManifest);
+        manifest
+            .config_mut()
+            .annotations_mut()
+            .get_or_insert_with(HashMap::new)
+            .insert("stamp".to_owned(), "1".to_owned());
+        *manifest.layers_mut()[0].size_mut() = 1;
+
+        assert_eq!(manifest.media_type(), &Some(MediaType::ImageManifest));
+        assert_eq!(
+            manifest.config().annotations().as_ref().unwrap()["stamp"],
+            "1"
+        );
+        assert_eq!(manifest.layers()[0].size(), 1);
+    }
+
+    #[test]
+    fn from_artifact_manifest() {
+        use crate::image::ArtifactManifestBuilder;
+
+        let blob = Descriptor::new(
+            MediaType::from("application/spdx+json"),
+            100,
+            "c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356"
+                .parse::<Sha256Digest>()
+                .unwrap(),
+        );
+        let subject = create_manifest().config().clone();
+        let artifact = ArtifactManifestBuilder::default()
+            .artifact_type(MediaType::from("application/vnd.example.sbom"))
+            .blobs(vec![blob.clone()])
+            .subject(subject.clone())
+            .annotations(HashMap::from([("a".to_owned(), "b".to_owned())]))
+            .build()
+            .unwrap();
+
+        let manifest = ImageManifest::from(artifact);
+        assert_eq!(manifest.schema_version(), SCHEMA_VERSION);
+        assert_eq!(
+            manifest.artifact_type(),
+            &Some(MediaType::from("application/vnd.example.sbom"))
+        );
+        assert_eq!(manifest.config().media_type(), &MediaType::EmptyJSON);
+        assert_eq!(manifest.layers(), &[blob]);
+        assert_eq!(manifest.subject(), &Some(subject));
+        assert_eq!(manifest.annotations().as_ref().unwrap()["a"], "b");
+    }
+
+    #[test]
+    fn descriptor() {
+        let mut manifest = create_manifest();
+        for i in 0..10 {
+            manifest
+                .annotations_mut()
+                .get_or_insert_with(HashMap::new)
+                .insert(format!("key{i}"), "value".to_owned());
+        }
+        let content = manifest.to_canonical_vec().unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&content).unwrap();
+        assert!(value["annotations"].as_object().unwrap().keys().is_sorted());
+
+        let descriptor = manifest.descriptor().unwrap();
+        assert_eq!(descriptor.media_type(), &MediaType::ImageManifest);
+        assert_eq!(descriptor.size(), content.len() as u64);
+        assert_eq!(
+            descriptor.digest(),
+            Descriptor::from_content(MediaType::ImageManifest, &content).digest()
+        );
+        assert_eq!(manifest.clone().descriptor().unwrap(), descriptor);
+    }
+
+    #[test]
+    fn layers_size() {
+        let mut manifest = create_manifest();
+        assert_eq!(manifest.layers_size(), 32654 + 16724 + 73109);
+        assert_eq!(manifest.layers_estargz_uncompressed_size(), None);
+
+        for layer in manifest.layers_mut() {
+            layer.set_estargz_uncompressed_size(layer.size() * 2);
+        }
+        assert_eq!(
+            manifest.layers_estargz_uncompressed_size(),
+            Some((32654 + 16724 + 73109) * 2)
+        );
+    }
+
     #[test]
     fn load_manifest_from_reader() {
         // arrange
@@ -332,6 +670,54 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn load_manifest_strict() {
+        let reader = fs::read(get_manifest_path()).expect("read manifest");
+        let actual = ImageManifest::from_reader_strict(&*reader).expect("from reader strict");
+        assert_eq!(actual, create_manifest());
+
+        let mut value: serde_json::Value = serde_json::from_slice(&reader).expect("parse manifest");
+        value["layers"][1]["foo"] = "bar".into();
+        let reader = serde_json::to_vec(&value).expect("serialize manifest");
+        assert!(ImageManifest::from_reader(&*reader).is_ok());
+        let err = ImageManifest::from_reader_strict(&*reader).expect_err("unknown field");
+        assert!(
+            err.to_string()
+                .starts_with("serde failed: unknown field `layers[1].foo` at line 1 column"),
+            "unexpected error: {err}"
+        );
+
+        let reader = br#"{"schemaVersion": 2, "config": {"mediaType": "application/vnd.oci.image.config.v1+json", "digest": "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356", "size": 7}, "layers": [], "annotations": {"a": "1", "b": "2", "a": "3"}}"#;
+        assert!(ImageManifest::from_reader(&reader[..]).is_ok());
+        let err = ImageManifest::from_reader_strict(&reader[..]).expect_err("duplicate key");
+        assert!(
+            err.to_string()
+                .starts_with("serde failed: duplicate key `annotations.a` at line 1 column"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn load_save_manifest_async() {
+        let expected = create_manifest();
+        let actual = ImageManifest::from_file_async(get_manifest_path())
+            .await
+            .expect("from file async");
+        assert_eq!(actual, expected);
+
+        let mut writer = Vec::new();
+        expected
+            .to_writer_async(&mut writer)
+            .await
+            .expect("to writer async");
+        let actual = ImageManifest::from_reader_async(writer.as_slice())
+            .await
+            .expect("from reader async");
+        assert_eq!(actual, expected);
+    }
+
+    #[cfg(feature = "filesystem")]
     #[test]
     fn save_manifest_to_file() {
         // arrange
@@ -377,4 +763,14 @@ mod tests {
         let expected = fs::read_to_string(get_manifest_path()).expect("read expected");
         assert_eq!(actual, expected);
     }
+
+    #[cfg(feature = "proptests")]
+    #[test]
+    fn manifest_round_trip() {
+        fn prop(manifest: ImageManifest) -> bool {
+            let json = manifest.to_string().unwrap();
+            ImageManifest::from_reader(json.as_bytes()).unwrap() == manifest
+        }
+        quickcheck::quickcheck(prop as fn(ImageManifest) -> bool);
+    }
 }
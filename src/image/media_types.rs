@@ -0,0 +1,42 @@
+//! Media types defined by the OCI image format spec, as string constants.
+//!
+//! These are the values of the corresponding [`MediaType`](super::MediaType)
+//! variants and can be used where no [`MediaType`](super::MediaType) is at
+//! hand, for example when matching `Content-Type` or `Accept` headers.
+//!
+//! ```
+//! use oci_spec::image::{media_types, MediaType};
+//!
+//! assert_eq!(MediaType::ImageManifest.as_str(), media_types::IMAGE_MANIFEST);
+//! assert_eq!(MediaType::from(media_types::IMAGE_INDEX), MediaType::ImageIndex);
+//! ```
+
+/// The media type of a content descriptor.
+pub const DESCRIPTOR: &str = "application/vnd.oci.descriptor";
+/// The media type of the oci-layout file.
+pub const LAYOUT_HEADER: &str = "application/vnd.oci.layout.header.v1+json";
+/// The media type of an image manifest.
+pub const IMAGE_MANIFEST: &str = "application/vnd.oci.image.manifest.v1+json";
+/// The media type of an image index.
+pub const IMAGE_INDEX: &str = "application/vnd.oci.image.index.v1+json";
+/// The media type of an uncompressed layer.
+pub const IMAGE_LAYER: &str = "application/vnd.oci.image.layer.v1.tar";
+/// The media type of a gzip compressed layer.
+pub const IMAGE_LAYER_GZIP: &str = "application/vnd.oci.image.layer.v1.tar+gzip";
+/// The media type of a zstd compressed layer.
+pub const IMAGE_LAYER_ZSTD: &str = "application/vnd.oci.image.layer.v1.tar+zstd";
+/// The media type of an uncompressed layer with distribution restrictions.
+pub const IMAGE_LAYER_NON_DISTRIBUTABLE: &str =
+    "application/vnd.oci.image.layer.nondistributable.v1.tar";
+/// The media type of a gzip compressed layer with distribution restrictions.
+pub const IMAGE_LAYER_NON_DISTRIBUTABLE_GZIP: &str =
+    "application/vnd.oci.image.layer.nondistributable.v1.tar+gzip";
+/// The media type of a zstd compressed layer with distribution restrictions.
+pub const IMAGE_LAYER_NON_DISTRIBUTABLE_ZSTD: &str =
+    "application/vnd.oci.image.layer.nondistributable.v1.tar+zstd";
+/// The media type of an image configuration.
+pub const IMAGE_CONFIG: &str = "application/vnd.oci.image.config.v1+json";
+/// The media type of an artifact manifest.
+pub const ARTIFACT_MANIFEST: &str = "application/vnd.oci.artifact.manifest.v1+json";
+/// The media type of the empty JSON object `{}`.
+pub const EMPTY_JSON: &str = "application/vnd.oci.empty.v1+json";
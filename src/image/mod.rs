@@ -2,31 +2,42 @@
 
 mod annotations;
 mod artifact;
+pub mod compat;
 mod config;
 mod descriptor;
-mod digest;
+mod encryption;
 mod index;
+pub mod interop;
 mod manifest;
+pub mod media_types;
 mod oci_layout;
+mod seekable;
+mod signal;
+mod validation;
 mod version;
 
 use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
+pub use crate::digest::*;
 pub use annotations::*;
 pub use artifact::*;
 pub use config::*;
 pub use descriptor::*;
-pub use digest::*;
+pub use encryption::*;
 pub use index::*;
 pub use manifest::*;
 pub use oci_layout::*;
+pub use seekable::*;
+pub use signal::*;
+pub use validation::*;
 pub use version::*;
 
 /// Media types used by OCI image format spec. Values MUST comply with RFC 6838,
 /// including the naming requirements in its section 4.2.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum MediaType {
     /// MediaType Descriptor specifies the media type for a content descriptor.
     Descriptor,
@@ -71,34 +82,63 @@ pub enum MediaType {
     Other(String),
 }
 
+impl MediaType {
+    /// Returns the media type as a string slice, without allocating.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Other(media_type) => media_type.as_str(),
+            known => known.as_static_str().unwrap_or_default(),
+        }
+    }
+
+    /// Returns the media type as a `&'static str`, or `None` for
+    /// [`MediaType::Other`].
+    pub const fn as_static_str(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::Descriptor => media_types::DESCRIPTOR,
+            Self::LayoutHeader => media_types::LAYOUT_HEADER,
+            Self::ImageManifest => media_types::IMAGE_MANIFEST,
+            Self::ImageIndex => media_types::IMAGE_INDEX,
+            Self::ImageLayer => media_types::IMAGE_LAYER,
+            Self::ImageLayerGzip => media_types::IMAGE_LAYER_GZIP,
+            Self::ImageLayerZstd => media_types::IMAGE_LAYER_ZSTD,
+            Self::ImageLayerNonDistributable => media_types::IMAGE_LAYER_NON_DISTRIBUTABLE,
+            Self::ImageLayerNonDistributableGzip => media_types::IMAGE_LAYER_NON_DISTRIBUTABLE_GZIP,
+            Self::ImageLayerNonDistributableZstd => media_types::IMAGE_LAYER_NON_DISTRIBUTABLE_ZSTD,
+            Self::ImageConfig => media_types::IMAGE_CONFIG,
+            Self::ArtifactManifest => media_types::ARTIFACT_MANIFEST,
+            Self::EmptyJSON => media_types::EMPTY_JSON,
+            Self::Other(_) => return None,
+        })
+    }
+}
+
 impl Display for MediaType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.as_ref())
+        f.write_str(self.as_str())
     }
 }
 
 impl From<&str> for MediaType {
     fn from(media_type: &str) -> Self {
         match media_type {
-            "application/vnd.oci.descriptor" => MediaType::Descriptor,
-            "application/vnd.oci.layout.header.v1+json" => MediaType::LayoutHeader,
-            "application/vnd.oci.image.manifest.v1+json" => MediaType::ImageManifest,
-            "application/vnd.oci.image.index.v1+json" => MediaType::ImageIndex,
-            "application/vnd.oci.image.layer.v1.tar" => MediaType::ImageLayer,
-            "application/vnd.oci.image.layer.v1.tar+gzip" => MediaType::ImageLayerGzip,
-            "application/vnd.oci.image.layer.v1.tar+zstd" => MediaType::ImageLayerZstd,
-            "application/vnd.oci.image.layer.nondistributable.v1.tar" => {
-                MediaType::ImageLayerNonDistributable
-            }
-            "application/vnd.oci.image.layer.nondistributable.v1.tar+gzip" => {
+            media_types::DESCRIPTOR => MediaType::Descriptor,
+            media_types::LAYOUT_HEADER => MediaType::LayoutHeader,
+            media_types::IMAGE_MANIFEST => MediaType::ImageManifest,
+            media_types::IMAGE_INDEX => MediaType::ImageIndex,
+            media_types::IMAGE_LAYER => MediaType::ImageLayer,
+            media_types::IMAGE_LAYER_GZIP => MediaType::ImageLayerGzip,
+            media_types::IMAGE_LAYER_ZSTD => MediaType::ImageLayerZstd,
+            media_types::IMAGE_LAYER_NON_DISTRIBUTABLE => MediaType::ImageLayerNonDistributable,
+            media_types::IMAGE_LAYER_NON_DISTRIBUTABLE_GZIP => {
                 MediaType::ImageLayerNonDistributableGzip
             }
-            "application/vnd.oci.image.layer.nondistributable.v1.tar+zstd" => {
+            media_types::IMAGE_LAYER_NON_DISTRIBUTABLE_ZSTD => {
                 MediaType::ImageLayerNonDistributableZstd
             }
-            "application/vnd.oci.image.config.v1+json" => MediaType::ImageConfig,
-            "application/vnd.oci.artifact.manifest.v1+json" => MediaType::ArtifactManifest,
-            "application/vnd.oci.empty.v1+json" => MediaType::EmptyJSON,
+            media_types::IMAGE_CONFIG => MediaType::ImageConfig,
+            media_types::ARTIFACT_MANIFEST => MediaType::ArtifactManifest,
+            media_types::EMPTY_JSON => MediaType::EmptyJSON,
             media => MediaType::Other(media.to_owned()),
         }
     }
@@ -106,34 +146,28 @@ impl From<&str> for MediaType {
 
 impl From<MediaType> for String {
     fn from(media_type: MediaType) -> Self {
-        media_type.as_ref().to_owned()
+        match media_type {
+            MediaType::Other(media_type) => media_type,
+            known => known.as_str().to_owned(),
+        }
     }
 }
 
 impl AsRef<str> for MediaType {
     fn as_ref(&self) -> &str {
-        match self {
-            Self::Descriptor => "application/vnd.oci.descriptor",
-            Self::LayoutHeader => "application/vnd.oci.layout.header.v1+json",
-            Self::ImageManifest => "application/vnd.oci.image.manifest.v1+json",
-            Self::ImageIndex => "application/vnd.oci.image.index.v1+json",
-            Self::ImageLayer => "application/vnd.oci.image.layer.v1.tar",
-            Self::ImageLayerGzip => "application/vnd.oci.image.layer.v1.tar+gzip",
-            Self::ImageLayerZstd => "application/vnd.oci.image.layer.v1.tar+zstd",
-            Self::ImageLayerNonDistributable => {
-                "application/vnd.oci.image.layer.nondistributable.v1.tar"
-            }
-            Self::ImageLayerNonDistributableGzip => {
-                "application/vnd.oci.image.layer.nondistributable.v1.tar+gzip"
-            }
-            Self::ImageLayerNonDistributableZstd => {
-                "application/vnd.oci.image.layer.nondistributable.v1.tar+zstd"
-            }
-            Self::ImageConfig => "application/vnd.oci.image.config.v1+json",
-            Self::ArtifactManifest => "application/vnd.oci.artifact.manifest.v1+json",
-            Self::EmptyJSON => "application/vnd.oci.empty.v1+json",
-            Self::Other(media_type) => media_type.as_str(),
-        }
+        self.as_str()
+    }
+}
+
+impl PartialEq<str> for MediaType {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for MediaType {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
     }
 }
 
@@ -167,8 +201,7 @@ impl Serialize for MediaType {
     where
         S: serde::Serializer,
     {
-        let media_type = format!("{self}");
-        media_type.serialize(serializer)
+        serializer.serialize_str(self.as_str())
     }
 }
 
@@ -182,9 +215,24 @@ impl<'de> Deserialize<'de> for MediaType {
     }
 }
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for MediaType {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "MediaType".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "pattern": "^[A-Za-z0-9][A-Za-z0-9!#$&^_.+-]{0,126}/[A-Za-z0-9][A-Za-z0-9!#$&^_.+-]{0,126}$",
+        })
+    }
+}
+
 /// Name of the target operating system.
 #[allow(missing_docs)]
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum Os {
     AIX,
     Android,
@@ -202,15 +250,19 @@ pub enum Os {
     OpenBSD,
     Plan9,
     Solaris,
+    /// The WebAssembly System Interface, named `wasip1` after the Go port.
+    Wasi,
     Windows,
     #[allow(non_camel_case_types)]
     zOS,
     Other(String),
 }
 
+/// Known operating system names are matched case-insensitively, because
+/// content in the wild contains e.g. `Linux`. Unknown names are kept as is.
 impl From<&str> for Os {
     fn from(os: &str) -> Self {
-        match os {
+        match os.to_ascii_lowercase().as_str() {
             "aix" => Os::AIX,
             "android" => Os::Android,
             "darwin" => Os::Darwin,
@@ -226,9 +278,10 @@ impl From<&str> for Os {
             "openbsd" => Os::OpenBSD,
             "plan9" => Os::Plan9,
             "solaris" => Os::Solaris,
+            "wasip1" => Os::Wasi,
             "windows" => Os::Windows,
             "zos" => Os::zOS,
-            name => Os::Other(name.to_owned()),
+            _ => Os::Other(os.to_owned()),
         }
     }
 }
@@ -251,6 +304,7 @@ impl Display for Os {
             Os::OpenBSD => "openbsd",
             Os::Plan9 => "plan9",
             Os::Solaris => "solaris",
+            Os::Wasi => "wasip1",
             Os::Windows => "windows",
             Os::zOS => "zos",
             Os::Other(name) => name,
@@ -280,14 +334,39 @@ impl<'de> Deserialize<'de> for Os {
     }
 }
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Os {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Os".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+        })
+    }
+}
+
+impl Os {
+    /// Returns the operating system of the host this program was compiled
+    /// for, using the Go naming, e.g. `darwin` for macOS.
+    pub fn host() -> Self {
+        match std::env::consts::OS {
+            "macos" => Os::Darwin,
+            os => Os::from(os),
+        }
+    }
+}
+
 impl Default for Os {
     fn default() -> Self {
-        Os::from(std::env::consts::OS)
+        Os::host()
     }
 }
 
 /// Name of the CPU target architecture.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum Arch {
     /// 32 bit x86, little-endian
     #[allow(non_camel_case_types)]
@@ -378,14 +457,16 @@ impl Display for Arch {
     }
 }
 
+/// Known architecture names are matched case-insensitively, because content
+/// in the wild contains e.g. `AMD64`. Unknown names are kept as is.
 impl From<&str> for Arch {
     fn from(arch: &str) -> Self {
-        match arch {
+        match arch.to_ascii_lowercase().as_str() {
             "386" => Arch::i386,
             "amd64" => Arch::Amd64,
             "amd64p32" => Arch::Amd64p32,
             "arm" => Arch::ARM,
-            "armbe" => Arch::ARM64be,
+            "armbe" => Arch::ARMbe,
             "arm64" => Arch::ARM64,
             "arm64be" => Arch::ARM64be,
             "loong64" => Arch::LoongArch64,
@@ -405,7 +486,7 @@ impl From<&str> for Arch {
             "sparc" => Arch::SPARC,
             "sparc64" => Arch::SPARC64,
             "wasm" => Arch::Wasm,
-            arch => Arch::Other(arch.to_owned()),
+            _ => Arch::Other(arch.to_owned()),
         }
     }
 }
@@ -430,23 +511,99 @@ impl<'de> Deserialize<'de> for Arch {
     }
 }
 
-impl Default for Arch {
-    fn default() -> Self {
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Arch {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Arch".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+        })
+    }
+}
+
+impl Arch {
+    /// Returns the CPU architecture of the host this program was compiled
+    /// for, using the Go naming, e.g. `amd64` for `x86_64`.
+    pub fn host() -> Self {
         // Translate from the Rust architecture names to the Go versions.
-        // It seems like the Rust ones are the same GNU/Linux...except for `powerpc64` and not `ppc64le`?
         // This list just contains exceptions, everything else is passed through literally.
         // See also https://github.com/containerd/containerd/blob/140ecc9247386d3be21616fe285021c081f4ea08/platforms/database.go
+        let little_endian = cfg!(target_endian = "little");
         let goarch = match std::env::consts::ARCH {
+            "x86" => "386",
+            "x86_64" if cfg!(target_pointer_width = "32") => "amd64p32",
             "x86_64" => "amd64",
-            "aarch64" => "arm64",
-            "powerpc64" if cfg!(target_endian = "big") => "ppc64",
-            "powerpc64" if cfg!(target_endian = "little") => "ppc64le",
+            "aarch64" if little_endian => "arm64",
+            "aarch64" => "arm64be",
+            "arm" if !little_endian => "armbe",
+            "loongarch64" => "loong64",
+            "mips" if little_endian => "mipsle",
+            "mips64" if little_endian => "mips64le",
+            "powerpc" => "ppc",
+            "powerpc64" if little_endian => "ppc64le",
+            "powerpc64" => "ppc64",
+            "riscv32" => "riscv",
+            "wasm32" => "wasm",
             o => o,
         };
         Arch::from(goarch)
     }
 }
 
+impl Default for Arch {
+    fn default() -> Self {
+        Arch::host()
+    }
+}
+
+#[cfg(feature = "proptests")]
+use quickcheck::{Arbitrary, Gen};
+
+#[cfg(feature = "proptests")]
+impl Arbitrary for MediaType {
+    fn arbitrary(g: &mut Gen) -> MediaType {
+        g.choose(&[
+            MediaType::ImageManifest,
+            MediaType::ImageIndex,
+            MediaType::ImageConfig,
+            MediaType::ImageLayer,
+            MediaType::ImageLayerGzip,
+            MediaType::ImageLayerZstd,
+            MediaType::EmptyJSON,
+            MediaType::Other("application/vnd.example.artifact.v1+json".to_owned()),
+        ])
+        .unwrap()
+        .clone()
+    }
+}
+
+#[cfg(feature = "proptests")]
+impl Arbitrary for Os {
+    fn arbitrary(g: &mut Gen) -> Os {
+        g.choose(&[Os::Linux, Os::Windows, Os::Darwin, Os::FreeBSD])
+            .unwrap()
+            .clone()
+    }
+}
+
+#[cfg(feature = "proptests")]
+impl Arbitrary for Arch {
+    fn arbitrary(g: &mut Gen) -> Arch {
+        g.choose(&[
+            Arch::Amd64,
+            Arch::ARM64,
+            Arch::ARM,
+            Arch::PowerPC64le,
+            Arch::s390x,
+        ])
+        .unwrap()
+        .clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -460,6 +617,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_host() {
+        assert_eq!(Os::host(), Os::default());
+        assert!(!matches!(Os::host(), Os::Other(_)));
+        assert_eq!(Arch::host(), Arch::default());
+        if cfg!(all(target_arch = "x86_64", target_os = "linux")) {
+            assert_eq!(Platform::host().to_string(), "linux/amd64");
+        }
+    }
+
     #[test]
     fn test_asref() {
         // This just spot checks a few conversions
@@ -472,4 +639,75 @@ mod tests {
             "application/vnd.oci.image.config.v1+json"
         );
     }
+
+    #[test]
+    fn test_media_type_str() {
+        assert_eq!(
+            MediaType::ImageManifest.as_static_str(),
+            Some(media_types::IMAGE_MANIFEST)
+        );
+        assert_eq!(MediaType::Other("text/plain".into()).as_static_str(), None);
+        assert_eq!(MediaType::Other("text/plain".into()).as_str(), "text/plain");
+        assert_eq!(MediaType::EmptyJSON, media_types::EMPTY_JSON);
+        assert_ne!(MediaType::ImageIndex, "application/vnd.oci.image.index.v1");
+        for media_type in [
+            MediaType::Descriptor,
+            MediaType::LayoutHeader,
+            MediaType::ImageLayerNonDistributableZstd,
+            MediaType::ArtifactManifest,
+        ] {
+            assert_eq!(MediaType::from(media_type.as_str()), media_type);
+        }
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_json_schema() {
+        let schema = schemars::schema_for!(ImageManifest);
+        let digest = schema
+            .pointer("/$defs/Digest/pattern")
+            .and_then(|p| p.as_str())
+            .expect("digest pattern");
+        assert!(digest.ends_with(":[a-zA-Z0-9=_-]+$"));
+    }
+
+    #[test]
+    fn test_os_arch_case_insensitive() {
+        let platform: (Os, Arch) = serde_json::from_str(r#"["Linux", "AMD64"]"#).unwrap();
+        assert_eq!(platform, (Os::Linux, Arch::Amd64));
+        assert_eq!(
+            serde_json::to_string(&platform).unwrap(),
+            r#"["linux","amd64"]"#
+        );
+
+        assert_eq!(Os::from("MyOS"), Os::Other("MyOS".to_string()));
+        assert_eq!(Os::from("wasip1"), Os::Wasi);
+        assert_eq!(Os::Wasi.to_string(), "wasip1");
+        // Only `wasip1` is a GOOS value, `wasi` is kept as is so that
+        // documents using it serialize unchanged.
+        assert_eq!(Os::from("wasi"), Os::Other("wasi".to_string()));
+        assert_eq!(
+            serde_json::to_string(&serde_json::from_str::<Os>(r#""wasi""#).unwrap()).unwrap(),
+            r#""wasi""#
+        );
+        assert_eq!(Arch::from("MyArch"), Arch::Other("MyArch".to_string()));
+        assert_eq!(
+            serde_json::to_string(&Arch::from("MyArch")).unwrap(),
+            r#""MyArch""#
+        );
+    }
+
+    #[test]
+    fn test_arch_round_trip() {
+        for arch in [
+            Arch::i386,
+            Arch::ARM,
+            Arch::ARMbe,
+            Arch::ARM64be,
+            Arch::PowerPC64le,
+            Arch::s390x,
+        ] {
+            assert_eq!(Arch::from(arch.to_string().as_str()), arch);
+        }
+    }
 }
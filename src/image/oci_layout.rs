@@ -1,16 +1,20 @@
 use crate::{
     error::{OciSpecError, Result},
-    from_file, from_reader, to_file, to_string, to_writer,
+    from_reader, to_string, to_writer,
 };
+#[cfg(feature = "filesystem")]
+use crate::{from_file, to_file};
 use derive_builder::Builder;
-use getset::{Getters, Setters};
+use getset::{Getters, MutGetters, Setters};
 use serde::{Deserialize, Serialize};
-use std::{
-    io::{Read, Write},
-    path::Path,
-};
+use std::io::{Read, Write};
+#[cfg(feature = "filesystem")]
+use std::path::Path;
 
-#[derive(Builder, Clone, Debug, Deserialize, Eq, Getters, Setters, PartialEq, Serialize)]
+#[derive(
+    Builder, Clone, Debug, Deserialize, Eq, Getters, MutGetters, Setters, PartialEq, Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     pattern = "owned",
@@ -23,7 +27,7 @@ use std::{
 /// pin a given version until changes to the image layout are required.
 pub struct OciLayout {
     /// This REQUIRED property specifies the image layout version.
-    #[getset(get = "pub", set = "pub")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
     image_layout_version: String,
 }
 
@@ -40,6 +44,7 @@ impl OciLayout {
     ///
     /// let oci_layout = OciLayout::from_file("oci-layout").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<OciLayout> {
         from_file(path)
     }
@@ -72,6 +77,7 @@ impl OciLayout {
     /// let oci_layout = OciLayout::from_file("oci-layout").unwrap();
     /// oci_layout.to_file("oci-layout").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         to_file(&self, path, false)
     }
@@ -88,6 +94,7 @@ impl OciLayout {
     /// let oci_layout = OciLayout::from_file("oci-layout").unwrap();
     /// oci_layout.to_file_pretty("my-oci-layout").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn to_file_pretty<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         to_file(&self, path, true)
     }
@@ -124,6 +131,60 @@ impl OciLayout {
         to_writer(&self, writer, true)
     }
 
+    /// Attempts to load an oci layout from a file asynchronously.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
+    /// if the file does not exist or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the oci layout
+    /// cannot be deserialized.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::image::OciLayout;
+    ///
+    /// # async fn example() {
+    /// let oci_layout = OciLayout::from_file_async("oci-layout").await.unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn from_file_async<P: AsRef<Path>>(path: P) -> Result<OciLayout> {
+        crate::from_file_async(path).await
+    }
+
+    /// Attempts to load an oci layout from an asynchronous stream.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io)
+    /// if the stream cannot be read or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the oci layout
+    /// cannot be deserialized.
+    #[cfg(feature = "async")]
+    pub async fn from_reader_async<R: tokio::io::AsyncRead + Unpin>(
+        reader: R,
+    ) -> Result<OciLayout> {
+        crate::from_reader_async(reader).await
+    }
+
+    /// Attempts to write an oci layout to a file as JSON asynchronously. If the file
+    /// already exists, it will be overwritten.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
+    /// the oci layout cannot be serialized.
+    #[cfg(feature = "async")]
+    pub async fn to_file_async<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        crate::to_file_async(self, path, false).await
+    }
+
+    /// Attempts to write an oci layout to an asynchronous stream as JSON.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
+    /// the oci layout cannot be serialized.
+    #[cfg(feature = "async")]
+    pub async fn to_writer_async<W: tokio::io::AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+    ) -> Result<()> {
+        crate::to_writer_async(self, writer, false).await
+    }
+
     /// Attempts to write an oci layout to a string as JSON.
     /// # Errors
     /// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if
@@ -172,6 +233,7 @@ mod tests {
         PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/data/oci-layout")
     }
 
+    #[cfg(feature = "filesystem")]
     #[test]
     fn load_oci_layout_from_file() {
         // arrange
@@ -198,6 +260,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "filesystem")]
     #[test]
     fn save_oci_layout_to_file() {
         // arrange
@@ -231,6 +294,7 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[cfg(feature = "filesystem")]
     #[test]
     fn save_oci_layout_to_string() {
         // arrange
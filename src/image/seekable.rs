@@ -0,0 +1,243 @@
+//! Annotations of seekable layer formats, which allow lazy pulling of
+//! individual files instead of whole layers.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use super::{
+    Annotated, Descriptor, Digest, MediaType, ANNOTATION_ESTARGZ_TOC_DIGEST,
+    ANNOTATION_ZSTD_CHUNKED_MANIFEST_CHECKSUM, ANNOTATION_ZSTD_CHUNKED_MANIFEST_POSITION,
+    ANNOTATION_ZSTD_CHUNKED_TARSPLIT_POSITION,
+};
+use crate::error::{oci_error, OciSpecError, Result};
+
+/// A seekable layer format.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SeekableFormat {
+    /// A zstd compressed layer with a table of contents, see
+    /// [zstd:chunked](https://github.com/containers/storage/blob/main/docs/containers-storage-zstd-chunked.md).
+    ZstdChunked,
+    /// A gzip compressed layer with a table of contents, see
+    /// [eStargz](https://github.com/containerd/stargz-snapshotter/blob/main/docs/estargz.md).
+    Estargz,
+}
+
+/// The position of a section within a zstd:chunked layer, like its table of
+/// contents or the tar-split data.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ChunkedPosition {
+    /// The offset of the compressed section within the layer.
+    pub offset: u64,
+    /// The length of the compressed section.
+    pub length: u64,
+    /// The length of the uncompressed section.
+    pub uncompressed_length: u64,
+    /// The format of the table of contents. It is only set for the position
+    /// of the table of contents.
+    pub manifest_type: Option<u64>,
+}
+
+impl FromStr for ChunkedPosition {
+    type Err = OciSpecError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let fields = s
+            .split(':')
+            .map(str::parse)
+            .collect::<std::result::Result<Vec<u64>, _>>()
+            .map_err(|err| oci_error(format!("invalid chunked position {s}: {err}")))?;
+        match fields[..] {
+            [offset, length, uncompressed_length] => Ok(Self {
+                offset,
+                length,
+                uncompressed_length,
+                manifest_type: None,
+            }),
+            [offset, length, uncompressed_length, manifest_type] => Ok(Self {
+                offset,
+                length,
+                uncompressed_length,
+                manifest_type: Some(manifest_type),
+            }),
+            _ => Err(oci_error(format!("invalid chunked position {s}"))),
+        }
+    }
+}
+
+impl Display for ChunkedPosition {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.offset, self.length, self.uncompressed_length
+        )?;
+        match self.manifest_type {
+            Some(manifest_type) => write!(f, ":{manifest_type}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Descriptor {
+    /// Returns the seekable format of the layer, detected from its media type
+    /// and annotations, or `None` if the layer can only be pulled as a whole.
+    ///
+    /// ```
+    /// use oci_spec::image::{Descriptor, Digest, MediaType, SeekableFormat};
+    /// use std::str::FromStr;
+    ///
+    /// let digest = Digest::from_str(
+    ///     "sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356",
+    /// ).unwrap();
+    /// let mut layer = Descriptor::new(MediaType::ImageLayerGzip, 100, digest.clone());
+    /// assert_eq!(layer.seekable_format(), None);
+    ///
+    /// layer.set_estargz_toc_digest(digest);
+    /// assert_eq!(layer.seekable_format(), Some(SeekableFormat::Estargz));
+    /// ```
+    pub fn seekable_format(&self) -> Option<SeekableFormat> {
+        match self.media_type() {
+            MediaType::ImageLayerZstd | MediaType::ImageLayerNonDistributableZstd
+                if self.zstd_chunked_manifest_checksum().is_some() =>
+            {
+                Some(SeekableFormat::ZstdChunked)
+            }
+            MediaType::ImageLayerGzip | MediaType::ImageLayerNonDistributableGzip
+                if self.estargz_toc_digest().is_some() =>
+            {
+                Some(SeekableFormat::Estargz)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if the layer has a seekable format.
+    pub fn is_seekable(&self) -> bool {
+        self.seekable_format().is_some()
+    }
+
+    /// Returns the digest of the table of contents of a zstd:chunked layer as
+    /// stored in the [ANNOTATION_ZSTD_CHUNKED_MANIFEST_CHECKSUM] annotation,
+    /// if it is set and valid.
+    pub fn zstd_chunked_manifest_checksum(&self) -> Option<Digest> {
+        self.digest_annotation(ANNOTATION_ZSTD_CHUNKED_MANIFEST_CHECKSUM)
+    }
+
+    /// Sets the [ANNOTATION_ZSTD_CHUNKED_MANIFEST_CHECKSUM] annotation.
+    pub fn set_zstd_chunked_manifest_checksum(&mut self, digest: impl Into<Digest>) -> &mut Self {
+        self.annotation_map_mut().insert(
+            ANNOTATION_ZSTD_CHUNKED_MANIFEST_CHECKSUM.to_owned(),
+            digest.into().to_string(),
+        );
+        self
+    }
+
+    /// Returns the position of the table of contents of a zstd:chunked layer
+    /// as stored in the [ANNOTATION_ZSTD_CHUNKED_MANIFEST_POSITION]
+    /// annotation, if it is set and valid.
+    pub fn zstd_chunked_manifest_position(&self) -> Option<ChunkedPosition> {
+        self.annotation_map()?
+            .get(ANNOTATION_ZSTD_CHUNKED_MANIFEST_POSITION)
+            .and_then(|p| p.parse().ok())
+    }
+
+    /// Sets the [ANNOTATION_ZSTD_CHUNKED_MANIFEST_POSITION] annotation.
+    pub fn set_zstd_chunked_manifest_position(&mut self, position: ChunkedPosition) -> &mut Self {
+        self.annotation_map_mut().insert(
+            ANNOTATION_ZSTD_CHUNKED_MANIFEST_POSITION.to_owned(),
+            position.to_string(),
+        );
+        self
+    }
+
+    /// Returns the position of the tar-split data of a zstd:chunked layer as
+    /// stored in the [ANNOTATION_ZSTD_CHUNKED_TARSPLIT_POSITION] annotation,
+    /// if it is set and valid.
+    pub fn zstd_chunked_tarsplit_position(&self) -> Option<ChunkedPosition> {
+        self.annotation_map()?
+            .get(ANNOTATION_ZSTD_CHUNKED_TARSPLIT_POSITION)
+            .and_then(|p| p.parse().ok())
+    }
+
+    /// Sets the [ANNOTATION_ZSTD_CHUNKED_TARSPLIT_POSITION] annotation.
+    pub fn set_zstd_chunked_tarsplit_position(&mut self, position: ChunkedPosition) -> &mut Self {
+        self.annotation_map_mut().insert(
+            ANNOTATION_ZSTD_CHUNKED_TARSPLIT_POSITION.to_owned(),
+            position.to_string(),
+        );
+        self
+    }
+
+    /// Returns the digest of the table of contents of an eStargz layer as
+    /// stored in the [ANNOTATION_ESTARGZ_TOC_DIGEST] annotation, if it is set
+    /// and valid.
+    pub fn estargz_toc_digest(&self) -> Option<Digest> {
+        self.digest_annotation(ANNOTATION_ESTARGZ_TOC_DIGEST)
+    }
+
+    /// Sets the [ANNOTATION_ESTARGZ_TOC_DIGEST] annotation.
+    pub fn set_estargz_toc_digest(&mut self, digest: impl Into<Digest>) -> &mut Self {
+        self.annotation_map_mut().insert(
+            ANNOTATION_ESTARGZ_TOC_DIGEST.to_owned(),
+            digest.into().to_string(),
+        );
+        self
+    }
+
+    fn digest_annotation(&self, key: &str) -> Option<Digest> {
+        self.annotation_map()?
+            .get(key)
+            .and_then(|d| Digest::from_str(d).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest() -> Digest {
+        Digest::from_str("sha256:c2b8beca588702777e5f35dafdbeae9ec16c2bab802331f81cacd2a92f1d5356")
+            .unwrap()
+    }
+
+    #[test]
+    fn chunked_position() {
+        let position: ChunkedPosition = "100:20:50:1".parse().unwrap();
+        assert_eq!(position.offset, 100);
+        assert_eq!(position.manifest_type, Some(1));
+        assert_eq!(position.to_string(), "100:20:50:1");
+
+        let position: ChunkedPosition = "100:20:50".parse().unwrap();
+        assert_eq!(position.manifest_type, None);
+        assert_eq!(position.to_string(), "100:20:50");
+
+        assert!("100:20".parse::<ChunkedPosition>().is_err());
+        assert!("100:20:x".parse::<ChunkedPosition>().is_err());
+    }
+
+    #[test]
+    fn zstd_chunked() {
+        let mut layer = Descriptor::new(MediaType::ImageLayerZstd, 100, digest());
+        assert!(!layer.is_seekable());
+
+        let position = ChunkedPosition {
+            offset: 80,
+            length: 20,
+            uncompressed_length: 40,
+            manifest_type: Some(1),
+        };
+        layer
+            .set_zstd_chunked_manifest_checksum(digest())
+            .set_zstd_chunked_manifest_position(position);
+        assert_eq!(layer.seekable_format(), Some(SeekableFormat::ZstdChunked));
+        assert_eq!(layer.zstd_chunked_manifest_checksum(), Some(digest()));
+        assert_eq!(layer.zstd_chunked_manifest_position(), Some(position));
+        assert_eq!(layer.zstd_chunked_tarsplit_position(), None);
+
+        // The annotations are meaningless on other media types.
+        layer.set_media_type(MediaType::ImageLayerGzip);
+        assert!(!layer.is_seekable());
+    }
+}
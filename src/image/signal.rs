@@ -0,0 +1,274 @@
+//! Signals as used by the `StopSignal` of image configurations.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::{oci_error, OciSpecError, Result};
+
+macro_rules! signals {
+    ($($(#[$doc:meta])* $variant:ident = $name:literal, $number:literal;)*) => {
+        /// A signal, which can be given by name, like `SIGTERM`, as a real-time
+        /// signal relative to `SIGRTMIN` or `SIGRTMAX`, like `SIGRTMIN+3`, or by
+        /// number.
+        ///
+        /// Names are parsed case-insensitively and with an optional `SIG`
+        /// prefix, as done by Docker, and are formatted in their canonical
+        /// form. The aliases `SIGIOT`, `SIGCLD` and `SIGPOLL` are parsed as
+        /// `SIGABRT`, `SIGCHLD` and `SIGIO`. Signals are equal if they have
+        /// the same [number](Signal::number), e.g. `9` equals `SIGKILL`.
+        ///
+        /// ```
+        /// use oci_spec::image::Signal;
+        ///
+        /// let signal: Signal = "term".parse().unwrap();
+        /// assert_eq!(signal, Signal::Term);
+        /// assert_eq!(signal.to_string(), "SIGTERM");
+        /// assert_eq!(signal.number(), 15);
+        ///
+        /// let signal: Signal = "SIGRTMIN+3".parse().unwrap();
+        /// assert_eq!(signal, Signal::RtMin(3));
+        /// assert_eq!(signal.number(), 37);
+        ///
+        /// assert_eq!("9".parse::<Signal>().unwrap(), Signal::Kill);
+        /// assert!("SIGFOO".parse::<Signal>().is_err());
+        /// ```
+        #[derive(Clone, Copy, Debug)]
+        pub enum Signal {
+            $($(#[$doc])* $variant,)*
+            /// The real-time signal `SIGRTMIN+n`.
+            RtMin(u8),
+            /// The real-time signal `SIGRTMAX-n`.
+            RtMax(u8),
+            /// A signal given by number.
+            Numeric(i32),
+        }
+
+        impl Signal {
+            const NAMED: &'static [(Signal, &'static str, i32)] = &[
+                $((Signal::$variant, $name, $number),)*
+            ];
+        }
+    };
+}
+
+signals! {
+    /// `SIGHUP`
+    Hup = "SIGHUP", 1;
+    /// `SIGINT`
+    Int = "SIGINT", 2;
+    /// `SIGQUIT`
+    Quit = "SIGQUIT", 3;
+    /// `SIGILL`
+    Ill = "SIGILL", 4;
+    /// `SIGTRAP`
+    Trap = "SIGTRAP", 5;
+    /// `SIGABRT`
+    Abrt = "SIGABRT", 6;
+    /// `SIGBUS`
+    Bus = "SIGBUS", 7;
+    /// `SIGFPE`
+    Fpe = "SIGFPE", 8;
+    /// `SIGKILL`
+    Kill = "SIGKILL", 9;
+    /// `SIGUSR1`
+    Usr1 = "SIGUSR1", 10;
+    /// `SIGSEGV`
+    Segv = "SIGSEGV", 11;
+    /// `SIGUSR2`
+    Usr2 = "SIGUSR2", 12;
+    /// `SIGPIPE`
+    Pipe = "SIGPIPE", 13;
+    /// `SIGALRM`
+    Alrm = "SIGALRM", 14;
+    /// `SIGTERM`
+    Term = "SIGTERM", 15;
+    /// `SIGSTKFLT`
+    Stkflt = "SIGSTKFLT", 16;
+    /// `SIGCHLD`
+    Chld = "SIGCHLD", 17;
+    /// `SIGCONT`
+    Cont = "SIGCONT", 18;
+    /// `SIGSTOP`
+    Stop = "SIGSTOP", 19;
+    /// `SIGTSTP`
+    Tstp = "SIGTSTP", 20;
+    /// `SIGTTIN`
+    Ttin = "SIGTTIN", 21;
+    /// `SIGTTOU`
+    Ttou = "SIGTTOU", 22;
+    /// `SIGURG`
+    Urg = "SIGURG", 23;
+    /// `SIGXCPU`
+    Xcpu = "SIGXCPU", 24;
+    /// `SIGXFSZ`
+    Xfsz = "SIGXFSZ", 25;
+    /// `SIGVTALRM`
+    Vtalrm = "SIGVTALRM", 26;
+    /// `SIGPROF`
+    Prof = "SIGPROF", 27;
+    /// `SIGWINCH`
+    Winch = "SIGWINCH", 28;
+    /// `SIGIO`
+    Io = "SIGIO", 29;
+    /// `SIGPWR`
+    Pwr = "SIGPWR", 30;
+    /// `SIGSYS`
+    Sys = "SIGSYS", 31;
+}
+
+impl Signal {
+    /// The number of `SIGRTMIN`.
+    pub const SIGRTMIN: i32 = 34;
+    /// The number of `SIGRTMAX`.
+    pub const SIGRTMAX: i32 = 64;
+
+    /// Returns the number of the signal, using the numbering of Linux on
+    /// x86 and ARM.
+    pub fn number(&self) -> i32 {
+        match self {
+            Self::RtMin(n) => Self::SIGRTMIN + i32::from(*n),
+            Self::RtMax(n) => Self::SIGRTMAX - i32::from(*n),
+            Self::Numeric(n) => *n,
+            named => Self::NAMED
+                .iter()
+                .find(|(signal, _, _)| {
+                    std::mem::discriminant(signal) == std::mem::discriminant(named)
+                })
+                .map(|(_, _, number)| *number)
+                .expect("named signal"),
+        }
+    }
+}
+
+impl PartialEq for Signal {
+    fn eq(&self, other: &Self) -> bool {
+        self.number() == other.number()
+    }
+}
+
+impl Eq for Signal {}
+
+impl Hash for Signal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.number().hash(state)
+    }
+}
+
+impl Display for Signal {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::RtMin(0) => write!(f, "SIGRTMIN"),
+            Self::RtMin(n) => write!(f, "SIGRTMIN+{n}"),
+            Self::RtMax(0) => write!(f, "SIGRTMAX"),
+            Self::RtMax(n) => write!(f, "SIGRTMAX-{n}"),
+            Self::Numeric(n) => write!(f, "{n}"),
+            named => {
+                let (_, name, _) = Self::NAMED
+                    .iter()
+                    .find(|(signal, _, _)| {
+                        std::mem::discriminant(signal) == std::mem::discriminant(named)
+                    })
+                    .expect("named signal");
+                write!(f, "{name}")
+            }
+        }
+    }
+}
+
+impl FromStr for Signal {
+    type Err = OciSpecError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Ok(number) = s.parse() {
+            return Ok(Self::Numeric(number));
+        }
+
+        let upper = s.to_ascii_uppercase();
+        let name = upper.strip_prefix("SIG").unwrap_or(&upper);
+        let offset = |offset: &str| {
+            offset
+                .parse::<u8>()
+                .ok()
+                .filter(|o| i32::from(*o) <= Self::SIGRTMAX - Self::SIGRTMIN)
+        };
+        let signal = match name {
+            "RTMIN" => Some(Self::RtMin(0)),
+            "RTMAX" => Some(Self::RtMax(0)),
+            "IOT" => Some(Self::Abrt),
+            "CLD" => Some(Self::Chld),
+            "POLL" => Some(Self::Io),
+            _ => match (name.strip_prefix("RTMIN+"), name.strip_prefix("RTMAX-")) {
+                (Some(n), _) => offset(n).map(Self::RtMin),
+                (_, Some(n)) => offset(n).map(Self::RtMax),
+                _ => Self::NAMED
+                    .iter()
+                    .find(|(_, known, _)| known[3..] == *name)
+                    .map(|(signal, _, _)| *signal),
+            },
+        };
+        signal.ok_or_else(|| oci_error(format!("invalid signal {s}")))
+    }
+}
+
+impl Serialize for Signal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Signal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let signal = String::deserialize(deserializer)?;
+        signal.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Signal {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Signal".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_signals() {
+        for (input, signal, formatted) in [
+            ("SIGKILL", Signal::Kill, "SIGKILL"),
+            ("kill", Signal::Kill, "SIGKILL"),
+            ("SigWinch", Signal::Winch, "SIGWINCH"),
+            ("9", Signal::Numeric(9), "9"),
+            ("SIGRTMIN", Signal::RtMin(0), "SIGRTMIN"),
+            ("RTMAX-2", Signal::RtMax(2), "SIGRTMAX-2"),
+            ("SIGIOT", Signal::Abrt, "SIGABRT"),
+            ("cld", Signal::Chld, "SIGCHLD"),
+            ("SIGPOLL", Signal::Io, "SIGIO"),
+        ] {
+            let parsed: Signal = input.parse().unwrap();
+            assert_eq!(parsed, signal, "{input}");
+            assert_eq!(parsed.to_string(), formatted);
+        }
+        for invalid in ["", "SIG", "SIGFOO", "SIGRTMIN+31", "SIGRTMIN-1", "1.5"] {
+            assert!(invalid.parse::<Signal>().is_err(), "{invalid}");
+        }
+        assert_eq!(Signal::RtMax(2).number(), 62);
+        assert_eq!(Signal::Sys.number(), 31);
+        assert_eq!(Signal::Numeric(9), Signal::Kill);
+        assert_eq!(Signal::Numeric(37), Signal::RtMin(3));
+        assert_ne!(Signal::Numeric(9), Signal::Term);
+    }
+}
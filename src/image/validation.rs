@@ -0,0 +1,293 @@
+//! Validation of an image manifest against the image configuration it
+//! references.
+
+use std::fmt::{self, Display, Formatter};
+
+use super::{compat, Digest, DigestAlgorithm, ImageConfiguration, ImageManifest, MediaType};
+
+/// The severity of a [Finding].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Severity {
+    /// A violation of a MUST requirement of the image spec.
+    Error,
+    /// A violation of a SHOULD requirement of the image spec.
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found while validating documents against each other.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Finding {
+    /// Whether the problem makes the image invalid.
+    pub severity: Severity,
+    /// The path of the offending field, prefixed with the document it
+    /// belongs to, e.g. `manifest.layers[1].digest` or
+    /// `config.rootfs.diff_ids[1]`.
+    pub field: String,
+    /// Human readable description of the problem.
+    pub message: String,
+}
+
+impl Finding {
+    fn error(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.field, self.message)
+    }
+}
+
+impl ImageManifest {
+    /// Validates the manifest against the image configuration it references
+    /// and returns every problem found:
+    ///
+    /// - the config descriptor must have the image configuration media type,
+    /// - the number of layers must match the number of diff IDs and of the
+    ///   history entries which are not marked as `empty_layer`,
+    /// - `rootfs.type` must be `layers`,
+    /// - layers must have a layer media type,
+    /// - uncompressed layers must have the diff ID at the same position as
+    ///   digest, which detects reordered layers,
+    /// - digests should use a registered algorithm.
+    ///
+    /// ```
+    /// use oci_spec::image::{ImageConfiguration, ImageManifest, Severity};
+    ///
+    /// let manifest = ImageManifest::from_file("test/data/manifest.json").unwrap();
+    /// let config = ImageConfiguration::from_file("test/data/config.json").unwrap();
+    ///
+    /// // The test data has three layers, but only two diff IDs.
+    /// let findings = manifest.validate_with_config(&config);
+    /// assert!(findings.iter().any(|f| f.severity == Severity::Error));
+    /// ```
+    pub fn validate_with_config(&self, config: &ImageConfiguration) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        let media_type = self.config().media_type();
+        match media_type {
+            MediaType::ImageConfig => {}
+            MediaType::Other(other) if other == compat::DOCKER_CONFIG => {
+                findings.push(Finding::warning(
+                    "manifest.config.mediaType",
+                    format!("Docker media type {other} used for the image configuration"),
+                ));
+            }
+            _ => findings.push(Finding::error(
+                "manifest.config.mediaType",
+                format!("{media_type} is not the media type of an image configuration"),
+            )),
+        }
+        check_algorithm(
+            &mut findings,
+            "manifest.config.digest",
+            self.config().digest(),
+        );
+
+        let rootfs = config.rootfs();
+        if rootfs.typ() != "layers" {
+            findings.push(Finding::error(
+                "config.rootfs.type",
+                format!("must be layers, found {}", rootfs.typ()),
+            ));
+        }
+
+        let diff_ids = rootfs.diff_ids();
+        if self.layers().len() != diff_ids.len() {
+            findings.push(Finding::error(
+                "config.rootfs.diff_ids",
+                format!(
+                    "{} diff IDs for {} layers",
+                    diff_ids.len(),
+                    self.layers().len()
+                ),
+            ));
+        }
+
+        let history = config
+            .history()
+            .iter()
+            .filter(|h| h.empty_layer() != Some(true))
+            .count();
+        if !config.history().is_empty() && history != self.layers().len() {
+            findings.push(Finding::error(
+                "config.history",
+                format!(
+                    "{history} history entries creating a layer for {} layers",
+                    self.layers().len()
+                ),
+            ));
+        }
+
+        for (i, layer) in self.layers().iter().enumerate() {
+            if !is_layer(layer.media_type()) {
+                findings.push(Finding::error(
+                    format!("manifest.layers[{i}].mediaType"),
+                    format!("{} is not a layer media type", layer.media_type()),
+                ));
+            }
+            check_algorithm(
+                &mut findings,
+                format!("manifest.layers[{i}].digest"),
+                layer.digest(),
+            );
+
+            let uncompressed = matches!(
+                layer.media_type(),
+                MediaType::ImageLayer | MediaType::ImageLayerNonDistributable
+            );
+            match diff_ids.get(i) {
+                Some(diff_id) if uncompressed && diff_id != layer.digest() => {
+                    findings.push(Finding::error(
+                        format!("config.rootfs.diff_ids[{i}]"),
+                        format!(
+                            "{diff_id} does not match the digest {} of the uncompressed layer",
+                            layer.digest()
+                        ),
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        for (i, diff_id) in diff_ids.iter().enumerate() {
+            check_algorithm(
+                &mut findings,
+                format!("config.rootfs.diff_ids[{i}]"),
+                diff_id,
+            );
+        }
+
+        findings
+    }
+}
+
+fn check_algorithm(findings: &mut Vec<Finding>, field: impl Into<String>, digest: &Digest) {
+    if let DigestAlgorithm::Other(algorithm) = digest.algorithm() {
+        findings.push(Finding::warning(
+            field,
+            format!("unregistered digest algorithm {algorithm}"),
+        ));
+    }
+}
+
+fn is_layer(media_type: &MediaType) -> bool {
+    match media_type {
+        MediaType::ImageLayer
+        | MediaType::ImageLayerGzip
+        | MediaType::ImageLayerZstd
+        | MediaType::ImageLayerNonDistributable
+        | MediaType::ImageLayerNonDistributableGzip
+        | MediaType::ImageLayerNonDistributableZstd => true,
+        MediaType::Other(_) => match compat::to_oci_media_type(media_type) {
+            MediaType::Other(_) => true,
+            oci => is_layer(&oci),
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::image::{Descriptor, HistoryBuilder, ImageManifestBuilder};
+
+    fn digest(hex: char) -> Digest {
+        Digest::from_str(&format!("sha256:{}", hex.to_string().repeat(64))).unwrap()
+    }
+
+    fn manifest(layers: Vec<Descriptor>) -> ImageManifest {
+        ImageManifestBuilder::default()
+            .schema_version(2u32)
+            .config(Descriptor::new(MediaType::ImageConfig, 2, digest('c')))
+            .layers(layers)
+            .build()
+            .unwrap()
+    }
+
+    fn config(diff_ids: Vec<Digest>) -> ImageConfiguration {
+        let mut config = ImageConfiguration::default();
+        for diff_id in diff_ids {
+            let history = HistoryBuilder::default().build().unwrap();
+            config.add_layer(diff_id, history).unwrap();
+        }
+        config
+    }
+
+    #[test]
+    fn consistent() {
+        let manifest = manifest(vec![
+            Descriptor::new(MediaType::ImageLayer, 10, digest('a')),
+            Descriptor::new(MediaType::ImageLayerGzip, 10, digest('b')),
+        ]);
+        let config = config(vec![digest('a'), digest('e')]);
+        assert_eq!(manifest.validate_with_config(&config), vec![]);
+    }
+
+    #[test]
+    fn inconsistent() {
+        let mut manifest = manifest(vec![
+            Descriptor::new(MediaType::ImageLayer, 10, digest('a')),
+            Descriptor::new(MediaType::ImageLayer, 10, digest('b')),
+        ]);
+        manifest
+            .config_mut()
+            .set_media_type(MediaType::ImageManifest);
+        let mut config = config(vec![digest('b'), digest('a')]);
+        config.history_mut().pop();
+
+        let fields: Vec<_> = manifest
+            .validate_with_config(&config)
+            .into_iter()
+            .map(|f| f.field)
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "manifest.config.mediaType",
+                "config.history",
+                "config.rootfs.diff_ids[0]",
+                "config.rootfs.diff_ids[1]",
+            ]
+        );
+
+        let mut manifest = manifest.clone();
+        manifest.layers_mut().pop();
+        manifest.layers_mut()[0].set_media_type(MediaType::ImageConfig);
+        manifest
+            .layers_mut()
+            .push(Descriptor::new(MediaType::ImageLayerZstd, 10, digest('e')));
+        manifest.layers_mut()[1].set_digest(Digest::from_str("b3:abc").unwrap());
+        let findings = manifest.validate_with_config(&config);
+        assert!(findings
+            .iter()
+            .any(|f| f.field == "manifest.layers[0].mediaType"));
+        assert!(findings
+            .iter()
+            .any(|f| f.field == "manifest.layers[1].digest" && f.severity == Severity::Warning));
+    }
+}
@@ -2,39 +2,352 @@
 #![doc = include_str!("../README.md")]
 #![allow(clippy::too_long_first_doc_paragraph)]
 
+#[macro_use]
+mod builder;
+#[cfg(any(feature = "image", feature = "runtime"))]
+mod digest;
 #[cfg(feature = "distribution")]
 pub mod distribution;
+mod env;
 mod error;
+#[macro_use]
+mod getters;
+#[cfg(feature = "test-fixtures")]
+pub mod fixtures;
 #[cfg(feature = "image")]
 pub mod image;
 #[cfg(feature = "runtime")]
 pub mod runtime;
+#[cfg(feature = "schema")]
+pub mod schema;
+#[cfg(any(feature = "distribution", feature = "runtime"))]
+mod semver;
 
 use std::{
-    fs::{self, OpenOptions},
+    cell::Cell,
+    collections::HashSet,
     io::{Read, Write},
-    path::Path,
 };
+#[cfg(feature = "filesystem")]
+use std::{fs, path::Path};
 
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{
+    de::{DeserializeOwned, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor},
+    Serialize,
+};
+use serde_json::Value;
 
+#[cfg(any(feature = "image", feature = "runtime"))]
+pub use digest::*;
+pub use env::*;
 pub use error::*;
 
+#[cfg(feature = "proptests")]
+fn some_none_generator_util<T: quickcheck::Arbitrary>(g: &mut quickcheck::Gen) -> Option<T> {
+    let choice = g.choose(&[true, false]).unwrap();
+    match choice {
+        false => None,
+        true => Some(T::arbitrary(g)),
+    }
+}
+
+#[cfg(feature = "filesystem")]
 fn from_file<P: AsRef<Path>, T: DeserializeOwned>(path: P) -> Result<T> {
     let path = path.as_ref();
-    let manifest_file = std::io::BufReader::new(fs::File::open(path)?);
-    let manifest = serde_json::from_reader(manifest_file)?;
-    Ok(manifest)
+    fs::File::open(path)
+        .map_err(OciSpecError::from)
+        .and_then(|file| from_reader(std::io::BufReader::new(file)))
+        .map_err(|err| err.with_path(path))
 }
 
 fn from_reader<R: Read, T: DeserializeOwned>(reader: R) -> Result<T> {
-    let manifest = serde_json::from_reader(reader)?;
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let manifest = deserialize(&mut deserializer)?;
+    deserializer.end()?;
     Ok(manifest)
 }
 
+#[cfg(feature = "async")]
+fn from_slice<T: DeserializeOwned>(data: &[u8]) -> Result<T> {
+    let mut deserializer = serde_json::Deserializer::from_slice(data);
+    let item = deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(item)
+}
+
+/// Deserializes `T` while tracking the path of the current field. Errors
+/// caused by invalid data are prefixed with the path of the failing field,
+/// e.g. `linux.resources.memory.limit`. The line and column of the error are
+/// kept, as serde_json recovers them from the message suffix.
+fn deserialize<'de, D, T>(deserializer: D) -> Result<T>
+where
+    D: Deserializer<'de, Error = serde_json::Error>,
+    T: DeserializeOwned,
+{
+    serde_path_to_error::deserialize(deserializer).map_err(|err| {
+        let path = err.path().to_string();
+        let inner = err.into_inner();
+        if path == "." || !inner.is_data() {
+            return inner.into();
+        }
+        OciSpecError::SerDe(serde::de::Error::custom(format!("{path}: {inner}")))
+    })
+}
+
+#[cfg(feature = "filesystem")]
+fn from_file_strict<P: AsRef<Path>, T: DeserializeOwned>(path: P) -> Result<T> {
+    let path = path.as_ref();
+    fs::File::open(path)
+        .map_err(OciSpecError::from)
+        .and_then(|file| from_reader_strict(std::io::BufReader::new(file)))
+        .map_err(|err| err.with_path(path))
+}
+
+thread_local! {
+    static STRICT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Returns true while a document is deserialized by [from_reader_strict].
+/// Types which collect unknown fields themselves, e.g. via
+/// `#[serde(flatten)]`, are not covered by `serde_ignored` and have to reject
+/// them on their own in this case.
+#[cfg(feature = "runtime")]
+pub(crate) fn is_strict() -> bool {
+    STRICT.with(Cell::get)
+}
+
+/// Deserializes `T` from `reader` and rejects any field which is not known
+/// to `T` or one of its nested types, as well as duplicate keys in any map.
+///
+/// Unknown fields are the ones ignored while deserializing `T`. The error
+/// points at the offending key, i.e. it carries its path as well as its line
+/// and column.
+fn from_reader_strict<R: Read, T: DeserializeOwned>(mut reader: R) -> Result<T> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let strict = STRICT.replace(true);
+    let mut unknown = None;
+    let mut ignored = |path: serde_ignored::Path| {
+        unknown.get_or_insert_with(|| ignored_path(&path));
+    };
+    let mut deserializer = serde_json::Deserializer::from_slice(&data);
+    let item = deserialize(serde_ignored::Deserializer::new(
+        &mut deserializer,
+        &mut ignored,
+    ))
+    .and_then(|item| Ok(deserializer.end().map(|()| item)?));
+    STRICT.set(strict);
+    let item = item?;
+
+    let mut deserializer = serde_json::Deserializer::from_slice(&data);
+    StrictKeys {
+        path: String::new(),
+        unknown: unknown.as_deref(),
+    }
+    .deserialize(&mut deserializer)?;
+    match unknown {
+        Some(path) => Err(oci_error(format!("unknown field `{path}`"))),
+        None => Ok(item),
+    }
+}
+
+/// Formats `path` the same way as [StrictKeys], e.g. `mounts[0].type`.
+fn ignored_path(path: &serde_ignored::Path) -> String {
+    use serde_ignored::Path;
+
+    match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, index } => format!("{}[{index}]", ignored_path(parent)),
+        Path::Map { parent, key } => match ignored_path(parent) {
+            parent if parent.is_empty() => key.clone(),
+            parent => format!("{parent}.{key}"),
+        },
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => ignored_path(parent),
+    }
+}
+
+/// Walks a JSON document and fails at the first key which occurs more than
+/// once in the same object or whose path is `unknown`, so that the error
+/// carries the position of the key. `serde_json` silently keeps the last
+/// value of a duplicate key, which could be used to hide values of maps like
+/// annotations or sysctls from reviewers.
+struct StrictKeys<'a> {
+    path: String,
+    unknown: Option<&'a str>,
+}
+
+impl StrictKeys<'_> {
+    fn nested(&self, path: String) -> Self {
+        StrictKeys {
+            path,
+            unknown: self.unknown,
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for StrictKeys<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for StrictKeys<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<(), A::Error> {
+        let mut keys = HashSet::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let path = match self.path.as_str() {
+                "" => key.clone(),
+                _ => format!("{}.{key}", self.path),
+            };
+            if self.unknown == Some(path.as_str()) {
+                return Err(serde::de::Error::custom(format!("unknown field `{path}`")));
+            }
+            if !keys.insert(key) {
+                return Err(serde::de::Error::custom(format!("duplicate key `{path}`")));
+            }
+            map.next_value_seed(self.nested(path))?;
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+        let mut i = 0;
+        while seq
+            .next_element_seed(self.nested(format!("{}[{i}]", self.path)))?
+            .is_some()
+        {
+            i += 1;
+        }
+        Ok(())
+    }
+
+    fn visit_bool<E>(self, _: bool) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _: &str) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> std::result::Result<(), E> {
+        Ok(())
+    }
+}
+
+#[cfg(any(feature = "image", feature = "runtime"))]
+fn is_empty_value(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.is_empty(),
+        _ => false,
+    }
+}
+
+/// Serializes `item` into its canonical JSON form: object keys are sorted,
+/// keys holding empty values are dropped, the values at the JSON pointers
+/// (see [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901)) in `strip`
+/// are removed and the arrays at the JSON pointers in `sets` are sorted.
+#[cfg(feature = "runtime")]
+fn to_canonical_vec<T: Serialize>(item: &T, strip: &[&str], sets: &[&str]) -> Result<Vec<u8>> {
+    let mut value = serde_json::to_value(item)?;
+    for pointer in strip {
+        remove_pointer(&mut value, pointer);
+    }
+    for pointer in sets {
+        if let Some(Value::Array(array)) = value.pointer_mut(pointer) {
+            array.sort_by_key(|v| v.to_string());
+        }
+    }
+    Ok(serde_json::to_vec(&sort_keys(value, true))?)
+}
+
+#[cfg(feature = "runtime")]
+fn remove_pointer(value: &mut Value, pointer: &str) {
+    let Some((parent, key)) = pointer.rsplit_once('/') else {
+        return;
+    };
+    let key = key.replace("~1", "/").replace("~0", "~");
+    match value.pointer_mut(parent) {
+        Some(Value::Object(object)) => {
+            object.remove(&key);
+        }
+        Some(Value::Array(array)) => {
+            if let Ok(index) = key.parse::<usize>() {
+                if index < array.len() {
+                    array.remove(index);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Serializes `item` into compact JSON with sorted object keys, so that equal
+/// documents always serialize to the same bytes, regardless of the iteration
+/// order of maps like annotations.
+#[cfg(feature = "image")]
+fn to_sorted_vec<T: Serialize>(item: &T) -> Result<Vec<u8>> {
+    Ok(serde_json::to_vec(&sort_keys(
+        serde_json::to_value(item)?,
+        false,
+    ))?)
+}
+
+/// Sorts the keys of all objects within `value` and, if `drop_empty` is set,
+/// removes the keys holding empty values.
+#[cfg(any(feature = "image", feature = "runtime"))]
+fn sort_keys(value: Value, drop_empty: bool) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object
+                .into_iter()
+                .filter(|(_, v)| !drop_empty || !is_empty_value(v))
+                .map(|(k, v)| (k, sort_keys(v, drop_empty)))
+                .collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(entries.into_iter().collect())
+        }
+        Value::Array(array) => Value::Array(
+            array
+                .into_iter()
+                .map(|v| sort_keys(v, drop_empty))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+#[cfg(all(feature = "filesystem", feature = "image"))]
 fn to_file<P: AsRef<Path>, T: Serialize>(item: &T, path: P, pretty: bool) -> Result<()> {
     let path = path.as_ref();
-    let file = OpenOptions::new()
+    let file = fs::OpenOptions::new()
         .write(true)
         .create(true)
         .truncate(true)
@@ -49,6 +362,127 @@ fn to_file<P: AsRef<Path>, T: Serialize>(item: &T, path: P, pretty: bool) -> Res
     Ok(())
 }
 
+/// Serializes `item` and writes it atomically to `path`, see [write_atomic].
+#[cfg(all(feature = "filesystem", feature = "runtime"))]
+fn to_file_atomic<P: AsRef<Path>, T: Serialize>(
+    item: &T,
+    path: P,
+    pretty: bool,
+    lock: bool,
+) -> Result<()> {
+    write_atomic(path.as_ref(), &to_vec(item, pretty)?, lock)
+}
+
+/// Like [to_file_atomic], but the file is written on the blocking thread pool
+/// of tokio.
+#[cfg(all(feature = "async", feature = "runtime"))]
+async fn to_file_atomic_async<P: AsRef<Path>, T: Serialize>(
+    item: &T,
+    path: P,
+    pretty: bool,
+    lock: bool,
+) -> Result<()> {
+    let data = to_vec(item, pretty)?;
+    let path = path.as_ref().to_path_buf();
+    tokio::task::spawn_blocking(move || write_atomic(&path, &data, lock))
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+/// Writes `data` into a temporary file next to `path` and renames it into
+/// place, so that readers never observe a partially written file.
+///
+/// If `path` is a symlink, its target is replaced and the link is kept. The
+/// permissions of an existing file are preserved. The file and, on Unix, its
+/// directory are synced, so that the new content survives a crash once this
+/// returns. If `lock` is set, an exclusive advisory lock on `<path>.lock` is
+/// held while writing.
+#[cfg(all(feature = "filesystem", feature = "runtime"))]
+fn write_atomic(path: &Path, data: &[u8], lock: bool) -> Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let path = resolve_symlinks(path)?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| oci_error(format!("{} is not a file path", path.display())))?
+        .to_string_lossy();
+
+    let _lock = if lock {
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path.with_file_name(format!("{file_name}.lock")))?;
+        file.lock()?;
+        Some(file)
+    } else {
+        None
+    };
+
+    let permissions = match fs::metadata(&path) {
+        Ok(metadata) => Some(metadata.permissions()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err.into()),
+    };
+    let tmp = path.with_file_name(format!(
+        ".{file_name}.{}.{}.tmp",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let write = || -> Result<()> {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(data)?;
+        if let Some(permissions) = permissions {
+            file.set_permissions(permissions)?;
+        }
+        file.sync_all()?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    };
+    write().inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })?;
+
+    #[cfg(unix)]
+    {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        fs::File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Follows `path` while it is a symlink and returns the final target, which
+/// does not need to exist.
+#[cfg(all(feature = "filesystem", feature = "runtime"))]
+fn resolve_symlinks(path: &Path) -> Result<std::path::PathBuf> {
+    const MAX_SYMLINKS: usize = 40;
+
+    let mut path = path.to_path_buf();
+    for _ in 0..MAX_SYMLINKS {
+        match fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                let target = fs::read_link(&path)?;
+                path = match path.parent() {
+                    Some(dir) => dir.join(target),
+                    None => target,
+                };
+            }
+            Ok(_) => return Ok(path),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(path),
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Err(oci_error(format!(
+        "too many levels of symbolic links at {}",
+        path.display()
+    )))
+}
+
 fn to_writer<W: Write, T: Serialize>(item: &T, writer: &mut W, pretty: bool) -> Result<()> {
     match pretty {
         true => serde_json::to_writer_pretty(writer, item)?,
@@ -58,9 +492,65 @@ fn to_writer<W: Write, T: Serialize>(item: &T, writer: &mut W, pretty: bool) ->
     Ok(())
 }
 
+#[cfg(feature = "image")]
 fn to_string<T: Serialize>(item: &T, pretty: bool) -> Result<String> {
     Ok(match pretty {
         true => serde_json::to_string_pretty(item)?,
         false => serde_json::to_string(item)?,
     })
 }
+
+#[cfg(feature = "async")]
+async fn from_file_async<P: AsRef<Path>, T: DeserializeOwned>(path: P) -> Result<T> {
+    let path = path.as_ref();
+    let data = tokio::fs::read(path)
+        .await
+        .map_err(|err| OciSpecError::from(err).with_path(path))?;
+    from_slice(&data).map_err(|err| err.with_path(path))
+}
+
+#[cfg(all(feature = "async", feature = "image"))]
+async fn from_reader_async<R, T>(mut reader: R) -> Result<T>
+where
+    R: tokio::io::AsyncRead + Unpin,
+    T: DeserializeOwned,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data).await?;
+    from_slice(&data)
+}
+
+#[cfg(all(feature = "async", feature = "image"))]
+async fn to_file_async<P: AsRef<Path>, T: Serialize>(
+    item: &T,
+    path: P,
+    pretty: bool,
+) -> Result<()> {
+    let data = to_vec(item, pretty)?;
+    tokio::fs::write(path, data).await?;
+    Ok(())
+}
+
+#[cfg(all(feature = "async", feature = "image"))]
+async fn to_writer_async<W, T>(item: &T, writer: &mut W, pretty: bool) -> Result<()>
+where
+    W: tokio::io::AsyncWrite + Unpin,
+    T: Serialize,
+{
+    use tokio::io::AsyncWriteExt;
+
+    let data = to_vec(item, pretty)?;
+    writer.write_all(&data).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+#[cfg(any(feature = "async", all(feature = "filesystem", feature = "runtime")))]
+fn to_vec<T: Serialize>(item: &T, pretty: bool) -> Result<Vec<u8>> {
+    Ok(match pretty {
+        true => serde_json::to_vec_pretty(item)?,
+        false => serde_json::to_vec(item)?,
+    })
+}
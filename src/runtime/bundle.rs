@@ -0,0 +1,199 @@
+#[cfg(feature = "tar")]
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use super::Spec;
+use crate::error::{oci_error, Result};
+
+/// The name of the configuration file within a bundle.
+pub const CONFIG_FILE: &str = "config.json";
+
+/// A [filesystem bundle](https://github.com/opencontainers/runtime-spec/blob/main/bundle.md):
+/// a directory holding the `config.json` of a container and, usually, its root filesystem.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bundle {
+    root: PathBuf,
+    spec: Spec,
+}
+
+impl Bundle {
+    /// Create a bundle at the directory `root` for `spec`. Nothing is written
+    /// until [Bundle::save] is called.
+    pub fn new<P: Into<PathBuf>>(root: P, spec: Spec) -> Self {
+        Self {
+            root: root.into(),
+            spec,
+        }
+    }
+
+    /// Load the bundle at the directory `root` by reading its `config.json`.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io) if the
+    /// directory or its `config.json` does not exist or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the spec is invalid.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::runtime::Bundle;
+    ///
+    /// let bundle = Bundle::load("/run/containers/my-container").unwrap();
+    /// bundle.validate_rootfs().unwrap();
+    /// ```
+    pub fn load<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let root = root.as_ref().canonicalize()?;
+        let spec = Spec::load(root.join(CONFIG_FILE))?;
+        Ok(Self { root, spec })
+    }
+
+    /// Unpack the tar archive read from `tar` into the directory `dest` and
+    /// load the bundle from there. The `config.json` is expected at the root
+    /// of the archive.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io) if the
+    /// archive cannot be unpacked or contains no `config.json` or an
+    /// [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the spec is invalid.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::runtime::Bundle;
+    ///
+    /// let archive = std::fs::File::open("bundle.tar").unwrap();
+    /// let bundle = Bundle::unpack(archive, "/tmp/bundle").unwrap();
+    /// ```
+    #[cfg(feature = "tar")]
+    pub fn unpack<R: Read, P: AsRef<Path>>(tar: R, dest: P) -> Result<Self> {
+        tar::Archive::new(tar).unpack(dest.as_ref())?;
+        Self::load(dest)
+    }
+
+    /// Write the spec to the `config.json` of the bundle.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io) if the file
+    /// cannot be created or an [OciSpecError::SerDe](crate::OciSpecError::SerDe) if the
+    /// spec cannot be serialized.
+    pub fn save(&self) -> Result<()> {
+        self.spec.save(self.config_path())
+    }
+
+    /// The directory of the bundle.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The path of the `config.json` of the bundle.
+    pub fn config_path(&self) -> PathBuf {
+        self.root.join(CONFIG_FILE)
+    }
+
+    /// The spec of the bundle.
+    pub fn spec(&self) -> &Spec {
+        &self.spec
+    }
+
+    /// The spec of the bundle for modification.
+    pub fn spec_mut(&mut self) -> &mut Spec {
+        &mut self.spec
+    }
+
+    /// Consume the bundle and return its spec.
+    pub fn into_spec(self) -> Spec {
+        self.spec
+    }
+
+    /// The root filesystem of the container, i.e. `root.path` of the spec
+    /// resolved against the bundle directory. The path is not required to
+    /// exist.
+    /// # Errors
+    /// This function will return an [OciSpecError::Other](crate::OciSpecError::Other) if
+    /// the spec has no `root`.
+    pub fn rootfs(&self) -> Result<PathBuf> {
+        let root = self
+            .spec
+            .root()
+            .as_ref()
+            .ok_or_else(|| oci_error("bundle spec has no root"))?;
+        Ok(self.root.join(root.path()))
+    }
+
+    /// Check that the root filesystem of the container is an existing
+    /// directory.
+    /// # Errors
+    /// This function will return an [OciSpecError::Other](crate::OciSpecError::Other) if
+    /// the spec has no `root` or if it is not a directory.
+    pub fn validate_rootfs(&self) -> Result<()> {
+        let rootfs = self.rootfs()?;
+        if !rootfs.is_dir() {
+            return Err(oci_error(format!(
+                "rootfs {} is not a directory",
+                rootfs.display()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Canonicalize `path` against the bundle directory. Relative paths are
+    /// interpreted relative to the bundle, absolute paths are kept as is.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io](crate::OciSpecError::Io) if the path
+    /// does not exist.
+    pub fn canonicalize<P: AsRef<Path>>(&self, path: P) -> Result<PathBuf> {
+        Spec::canonicalize_path(&self.root, path)
+    }
+
+    /// Replace `root.path` of the spec by the canonical path of the root
+    /// filesystem, see [Spec::canonicalize_rootfs].
+    pub fn canonicalize_rootfs(&mut self) -> Result<()> {
+        self.spec.canonicalize_rootfs(&self.root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn bundle_load_save() {
+        let dir = tempfile::tempdir().expect("failed to create tmp dir");
+        let bundle = Bundle::new(dir.path(), Spec::default());
+        assert!(bundle.validate_rootfs().is_err());
+        bundle.save().expect("failed to save bundle");
+
+        fs::create_dir(dir.path().join("rootfs")).expect("failed to create rootfs");
+        let mut loaded = Bundle::load(dir.path()).expect("failed to load bundle");
+        assert_eq!(loaded.spec(), bundle.spec());
+        assert_eq!(loaded.config_path(), loaded.root().join(CONFIG_FILE));
+        assert_eq!(loaded.rootfs().unwrap(), loaded.root().join("rootfs"));
+        loaded.validate_rootfs().expect("rootfs should be valid");
+
+        loaded.canonicalize_rootfs().unwrap();
+        assert_eq!(
+            loaded.spec().root().as_ref().unwrap().path(),
+            &loaded.canonicalize("rootfs").unwrap()
+        );
+
+        assert!(Bundle::load(dir.path().join("missing")).is_err());
+    }
+
+    #[cfg(feature = "tar")]
+    #[test]
+    fn bundle_tar() {
+        let dir = tempfile::tempdir().expect("failed to create tmp dir");
+        let source = dir.path().join("source");
+        fs::create_dir_all(source.join("rootfs")).expect("failed to create rootfs");
+        let mut spec = Spec::default();
+        spec.set_hostname(Some("packed".to_string()));
+        Bundle::new(&source, spec.clone()).save().unwrap();
+
+        let mut builder = tar::Builder::new(Vec::new());
+        builder.append_dir_all(".", &source).unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        assert_eq!(Spec::from_tar(archive.as_slice()).unwrap(), spec);
+
+        let bundle = Bundle::unpack(archive.as_slice(), dir.path().join("dest")).unwrap();
+        assert_eq!(bundle.spec(), &spec);
+        bundle.validate_rootfs().expect("rootfs should be unpacked");
+
+        let empty = tar::Builder::new(Vec::new()).into_inner().unwrap();
+        assert!(Spec::from_tar(empty.as_slice()).is_err());
+    }
+}
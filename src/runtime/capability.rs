@@ -10,6 +10,7 @@ use strum_macros::{Display, EnumString};
 pub type Capabilities = HashSet<Capability>;
 
 #[derive(Clone, Copy, Debug, EnumString, Eq, Display, Hash, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 /// All available capabilities.
 ///
 /// For the purpose of performing permission checks, traditional UNIX
@@ -24,6 +25,7 @@ pub type Capabilities = HashSet<Capability>;
 /// associated with superuser into distinct units, known as capabilities, which
 /// can be independently enabled and disabled. Capabilities are a per-thread attribute.
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+#[non_exhaustive]
 pub enum Capability {
     #[serde(rename = "CAP_AUDIT_CONTROL")]
     /// Enable and disable kernel auditing; change auditing filter rules;
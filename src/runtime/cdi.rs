@@ -0,0 +1,169 @@
+//! Helpers for requesting [Container Device Interface](https://github.com/cncf-tags/container-device-interface)
+//! devices via annotations of the runtime spec.
+
+use super::Spec;
+use crate::error::{oci_error, Result};
+
+/// The prefix of the annotation keys used to request CDI devices. The value
+/// of such an annotation is a comma separated list of fully-qualified device
+/// names.
+pub const CDI_ANNOTATION_PREFIX: &str = "cdi.k8s.io/";
+
+/// Validates a fully-qualified CDI device name of the form
+/// `vendor/class=name`, e.g. `nvidia.com/gpu=0`.
+///
+/// The vendor must start with a letter and may contain letters, digits, `-`,
+/// `_` and `.`. The class must start with a letter and may contain letters,
+/// digits, `-` and `_`. The name must start with a letter or digit and may
+/// contain letters, digits, `-`, `_`, `.` and `:`. All parts must end with a
+/// letter or digit.
+pub fn validate_cdi_device_name(device: &str) -> Result<()> {
+    let invalid = |reason: &str| oci_error(format!("invalid CDI device {device:?}: {reason}"));
+    let (vendor, rest) = device
+        .split_once('/')
+        .ok_or_else(|| invalid("missing vendor"))?;
+    let (class, name) = rest
+        .split_once('=')
+        .ok_or_else(|| invalid("missing device name"))?;
+
+    if !is_cdi_component(vendor, |c| c.is_ascii_alphabetic(), "-_.") {
+        return Err(invalid("invalid vendor"));
+    }
+    if !is_cdi_component(class, |c| c.is_ascii_alphabetic(), "-_") {
+        return Err(invalid("invalid class"));
+    }
+    if !is_cdi_component(name, |c| c.is_ascii_alphanumeric(), "-_.:") {
+        return Err(invalid("invalid device name"));
+    }
+    Ok(())
+}
+
+fn is_cdi_component(s: &str, valid_first: impl Fn(char) -> bool, extra: &str) -> bool {
+    s.starts_with(valid_first)
+        && s.ends_with(|c: char| c.is_ascii_alphanumeric())
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || extra.contains(c))
+}
+
+impl Spec {
+    /// Requests the CDI device with the fully-qualified name `device`, e.g.
+    /// `nvidia.com/gpu=0`, by adding it to the annotation
+    /// `cdi.k8s.io/<vendor>_<class>`. Requesting a device twice has no
+    /// effect.
+    ///
+    /// ```
+    /// use oci_spec::runtime::Spec;
+    ///
+    /// let mut spec = Spec::default();
+    /// spec.add_cdi_device("nvidia.com/gpu=0").unwrap();
+    /// spec.add_cdi_device("nvidia.com/gpu=1").unwrap();
+    /// assert!(spec.add_cdi_device("gpu0").is_err());
+    ///
+    /// assert_eq!(spec.cdi_devices(), vec!["nvidia.com/gpu=0", "nvidia.com/gpu=1"]);
+    /// assert_eq!(
+    ///     spec.annotations().as_ref().unwrap()["cdi.k8s.io/nvidia.com_gpu"],
+    ///     "nvidia.com/gpu=0,nvidia.com/gpu=1"
+    /// );
+    /// ```
+    pub fn add_cdi_device(&mut self, device: &str) -> Result<()> {
+        validate_cdi_device_name(device)?;
+        let (kind, _) = device.split_once('=').unwrap_or_default();
+        let key = format!("{CDI_ANNOTATION_PREFIX}{}", kind.replace('/', "_"));
+
+        let value = self
+            .annotations
+            .get_or_insert_with(Default::default)
+            .entry(key)
+            .or_default();
+        if !value.split(',').any(|d| d.trim() == device) {
+            if !value.is_empty() {
+                value.push(',');
+            }
+            value.push_str(device);
+        }
+        Ok(())
+    }
+
+    /// Returns the CDI devices requested by the `cdi.k8s.io/` annotations,
+    /// ordered by annotation key and without duplicates. The device names
+    /// are not validated.
+    pub fn cdi_devices(&self) -> Vec<&str> {
+        let mut annotations: Vec<_> = self
+            .annotations
+            .iter()
+            .flatten()
+            .filter(|(key, _)| key.starts_with(CDI_ANNOTATION_PREFIX))
+            .collect();
+        annotations.sort();
+
+        let mut devices = Vec::new();
+        for device in annotations
+            .into_iter()
+            .flat_map(|(_, value)| value.split(','))
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+        {
+            if !devices.contains(&device) {
+                devices.push(device);
+            }
+        }
+        devices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn cdi_device_names() {
+        for valid in [
+            "nvidia.com/gpu=0",
+            "vendor.com/class=all",
+            "example.com/net-device=eth0:1",
+            "a/b=c",
+        ] {
+            assert!(validate_cdi_device_name(valid).is_ok(), "{valid}");
+        }
+        for invalid in [
+            "",
+            "nvidia.com/gpu",
+            "nvidia.com=gpu",
+            "1vendor.com/gpu=0",
+            "vendor.com./gpu=0",
+            "vendor.com/gpu.x=0",
+            "vendor.com/gpu=",
+            "vendor.com/gpu=-0",
+            "vendor.com/gpu=0/1",
+        ] {
+            assert!(validate_cdi_device_name(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn cdi_devices() -> Result<()> {
+        let mut spec = Spec::default();
+        spec.set_annotations(Some(HashMap::from([
+            (
+                "cdi.k8s.io/vfio".to_owned(),
+                "example.com/vfio=a, ".to_owned(),
+            ),
+            ("other".to_owned(), "example.com/other=b".to_owned()),
+        ])));
+        spec.add_cdi_device("nvidia.com/gpu=0")?;
+        spec.add_cdi_device("nvidia.com/gpu=0")?;
+        spec.add_cdi_device("example.com/vfio=b")?;
+        assert_eq!(
+            spec.cdi_devices(),
+            vec![
+                "example.com/vfio=b",
+                "nvidia.com/gpu=0",
+                "example.com/vfio=a"
+            ]
+        );
+        assert!(spec.add_cdi_device("nvidia.com/gpu").is_err());
+        Ok(())
+    }
+}
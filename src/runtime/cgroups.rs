@@ -0,0 +1,167 @@
+use std::{
+    fmt::{self, Display},
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+};
+
+use super::Linux;
+use crate::error::{oci_error, OciSpecError, Result};
+
+/// A parsed `linux.cgroupsPath`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum CgroupsPath {
+    /// A path in the cgroup filesystem, relative to the cgroup mount point if
+    /// absolute and to the cgroup of the runtime otherwise.
+    Cgroupfs(PathBuf),
+    /// The `slice:prefix:name` form used with the systemd cgroup driver.
+    Systemd {
+        /// The parent slice, e.g. `system.slice`. Empty for the default
+        /// slice of the runtime.
+        slice: String,
+        /// The prefix of the scope unit, e.g. `cri-containerd`.
+        prefix: String,
+        /// The name of the scope unit, usually the container id.
+        name: String,
+    },
+}
+
+impl CgroupsPath {
+    fn parse_systemd(s: &str) -> Option<Result<Self>> {
+        let mut parts = s.split(':');
+        let (Some(slice), Some(prefix), Some(name), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        if s.contains('/') {
+            return None;
+        }
+
+        if !slice.is_empty() && !slice.ends_with(".slice") {
+            return Some(Err(oci_error(format!(
+                "cgroupsPath {s}: systemd slice {slice} must end with .slice"
+            ))));
+        }
+        if name.is_empty() {
+            return Some(Err(oci_error(format!(
+                "cgroupsPath {s}: systemd unit name must not be empty"
+            ))));
+        }
+        Some(Ok(Self::Systemd {
+            slice: slice.to_owned(),
+            prefix: prefix.to_owned(),
+            name: name.to_owned(),
+        }))
+    }
+
+    fn parse_cgroupfs(s: &str) -> Result<Self> {
+        let path = Path::new(s);
+        let clean = !s.is_empty()
+            && !s.contains("//")
+            && (s == "/" || !s.ends_with('/'))
+            && path
+                .components()
+                .all(|c| matches!(c, Component::RootDir | Component::Normal(_)));
+        if !clean {
+            return Err(oci_error(format!(
+                "cgroupsPath {s} must be a clean cgroupfs path or of the form slice:prefix:name"
+            )));
+        }
+        Ok(Self::Cgroupfs(path.to_path_buf()))
+    }
+}
+
+/// A `cgroupsPath` is parsed in the systemd form if it consists of three
+/// colon separated parts without any `/`, otherwise as a cgroupfs path which
+/// must not contain empty, `.` or `..` components.
+impl FromStr for CgroupsPath {
+    type Err = OciSpecError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse_systemd(s).unwrap_or_else(|| Self::parse_cgroupfs(s))
+    }
+}
+
+impl Display for CgroupsPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cgroupfs(path) => write!(f, "{}", path.display()),
+            Self::Systemd {
+                slice,
+                prefix,
+                name,
+            } => write!(f, "{slice}:{prefix}:{name}"),
+        }
+    }
+}
+
+impl Linux {
+    /// Validate and parse the `cgroupsPath`, returning `None` if it is unset.
+    /// # Errors
+    /// This function will return an [OciSpecError::Other] if the path is neither a clean
+    /// cgroupfs path nor of the systemd form `slice:prefix:name`.
+    /// # Example
+    /// ```
+    /// use oci_spec::runtime::{CgroupsPath, LinuxBuilder};
+    ///
+    /// let linux = LinuxBuilder::default()
+    ///     .cgroups_path("system.slice:cri-containerd:abc")
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(matches!(
+    ///     linux.parsed_cgroups_path().unwrap(),
+    ///     Some(CgroupsPath::Systemd { .. })
+    /// ));
+    /// ```
+    pub fn parsed_cgroups_path(&self) -> Result<Option<CgroupsPath>> {
+        self.cgroups_path_ref()
+            .map(|path| {
+                path.to_str()
+                    .ok_or_else(|| {
+                        oci_error(format!("cgroupsPath {} is not UTF-8", path.display()))
+                    })?
+                    .parse()
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cgroups_path() {
+        assert_eq!(
+            "/kubepods/burstable/pod1".parse::<CgroupsPath>().unwrap(),
+            CgroupsPath::Cgroupfs("/kubepods/burstable/pod1".into())
+        );
+        assert_eq!(
+            "mycontainer".parse::<CgroupsPath>().unwrap(),
+            CgroupsPath::Cgroupfs("mycontainer".into())
+        );
+        let systemd: CgroupsPath = "system.slice:docker:abc".parse().unwrap();
+        assert_eq!(
+            systemd,
+            CgroupsPath::Systemd {
+                slice: "system.slice".to_owned(),
+                prefix: "docker".to_owned(),
+                name: "abc".to_owned(),
+            }
+        );
+        assert_eq!(systemd.to_string(), "system.slice:docker:abc");
+        assert!(":docker:abc".parse::<CgroupsPath>().is_ok());
+
+        for invalid in [
+            "",
+            "/a//b",
+            "/a/",
+            "/a/../b",
+            "./a",
+            "system:docker:abc",
+            "system.slice:docker:",
+        ] {
+            assert!(invalid.parse::<CgroupsPath>().is_err(), "{invalid}");
+        }
+    }
+}
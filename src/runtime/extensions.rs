@@ -0,0 +1,141 @@
+//! Sections of platforms which are not part of the runtime spec yet.
+
+use std::collections::HashMap;
+
+use serde::{
+    de::{DeserializeOwned, Error},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::Value;
+
+use super::Spec;
+use crate::error::{oci_error, Result};
+
+/// Names of experimental platforms which are in use by runtimes, but not
+/// part of the runtime spec yet. `freebsd` is used by
+/// [runj](https://github.com/samuelkarp/runj) for FreeBSD jails.
+pub const REGISTERED_EXTENSION_PLATFORMS: &[&str] = &["freebsd"];
+
+/// Returns true if `name` may be used for an extension platform, i.e. if it
+/// is registered in [REGISTERED_EXTENSION_PLATFORMS] or uses the `x-` prefix
+/// reserved for private platforms.
+pub fn is_extension_platform(name: &str) -> bool {
+    REGISTERED_EXTENSION_PLATFORMS.contains(&name)
+        || name.strip_prefix("x-").is_some_and(|n| !n.is_empty())
+}
+
+/// Collects the top-level objects of a spec which belong to an extension
+/// platform. Other unknown fields, e.g. misspelled ones, are dropped, as
+/// before, or rejected when loading strictly.
+pub(super) fn deserialize_extension_platforms<'de, D>(
+    deserializer: D,
+) -> std::result::Result<HashMap<String, Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut platforms = HashMap::<String, Value>::deserialize(deserializer)?;
+    let keep = |key: &str, value: &Value| is_extension_platform(key) && value.is_object();
+    if crate::is_strict() {
+        if let Some((key, _)) = platforms.iter().find(|(key, value)| !keep(key, value)) {
+            return Err(D::Error::custom(format!("unknown field `{key}`")));
+        }
+    }
+    platforms.retain(|key, value| keep(key, value));
+    Ok(platforms)
+}
+
+impl Spec {
+    /// Returns the section of the experimental platform `name` parsed as
+    /// `T`, or `None` if the spec has no such section. Only sections of
+    /// platforms accepted by [is_extension_platform] are kept.
+    ///
+    /// ```
+    /// use oci_spec::runtime::Spec;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Jail {
+    ///     parent: String,
+    /// }
+    ///
+    /// let spec: Spec = serde_json::from_str(
+    ///     r#"{"ociVersion": "1.2.0", "x-jail": {"parent": "base"}}"#,
+    /// ).unwrap();
+    /// let jail: Jail = spec.extension_platform("x-jail").unwrap().unwrap();
+    /// assert_eq!(jail.parent, "base");
+    /// ```
+    pub fn extension_platform<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>> {
+        self.extension_platforms
+            .get(name)
+            .map(|value| crate::deserialize(value.clone()))
+            .transpose()
+    }
+
+    /// Sets the section of the experimental platform `name` to `platform`.
+    /// # Errors
+    /// This function will return an [OciSpecError::Other](crate::OciSpecError::Other) if
+    /// `name` is not accepted by [is_extension_platform] or `platform` does not
+    /// serialize to a JSON object.
+    pub fn set_extension_platform<T: Serialize>(
+        &mut self,
+        name: impl Into<String>,
+        platform: &T,
+    ) -> Result<()> {
+        let name = name.into();
+        if !is_extension_platform(&name) {
+            return Err(oci_error(format!(
+                "{name} is neither a registered extension platform nor prefixed with x-"
+            )));
+        }
+        let platform = serde_json::to_value(platform)?;
+        if !platform.is_object() {
+            return Err(oci_error(format!(
+                "extension platform {name} must be a JSON object"
+            )));
+        }
+        self.extension_platforms.insert(name, platform);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_platforms_round_trip() -> Result<()> {
+        let json = r#"{"ociVersion":"1.2.0","x-haiku":{"teams":{"max":8}},"linx":{},"foo":1}"#;
+        let spec: Spec = serde_json::from_str(json)?;
+        assert_eq!(spec.extension_platforms().len(), 1);
+        let value = serde_json::to_value(&spec)?;
+        assert_eq!(value["x-haiku"]["teams"]["max"], 8);
+        assert!(value.get("linx").is_none());
+        assert!(value.get("foo").is_none());
+
+        let mut spec = Spec::default();
+        assert_eq!(spec.extension_platform::<Value>("x-haiku")?, None);
+        assert!(spec.set_extension_platform("haiku", &Value::Null).is_err());
+        assert!(spec.set_extension_platform("x-haiku", &1).is_err());
+        spec.set_extension_platform("x-haiku", &serde_json::json!({"teams": {}}))?;
+        let reloaded: Spec = serde_json::from_str(&serde_json::to_string(&spec)?)?;
+        assert_eq!(reloaded, spec);
+        Ok(())
+    }
+
+    #[test]
+    fn extension_platforms_strict() {
+        let json = r#"{"ociVersion":"1.2.0","x-haiku":{"teams":{}}}"#;
+        assert!(crate::from_reader_strict::<_, Spec>(json.as_bytes()).is_ok());
+        for unknown in [
+            r#""hostnme":"typo""#,
+            r#""linx":{}"#,
+            r#""proces":{"cwd":"/"}"#,
+        ] {
+            let json = format!(r#"{{"ociVersion":"1.2.0",{unknown}}}"#);
+            assert!(
+                crate::from_reader_strict::<_, Spec>(json.as_bytes()).is_err(),
+                "{unknown}"
+            );
+        }
+    }
+}
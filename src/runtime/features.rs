@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::{
     error::OciSpecError,
-    runtime::{Arch, LinuxNamespaceType, LinuxSeccompAction},
+    runtime::{Arch, LinuxNamespaceType, LinuxSeccompAction, OciVersion},
 };
 use derive_builder::Builder;
 use getset::{Getters, MutGetters, Setters};
@@ -25,6 +25,7 @@ use serde::{Deserialize, Serialize};
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -35,9 +36,9 @@ use serde::{Deserialize, Serialize};
 #[getset(get_mut = "pub", get = "pub", set = "pub")]
 pub struct Features {
     /// The minimum OCI Runtime Spec version recognized by the runtime, e.g., "1.0.0".
-    oci_version_min: String,
+    oci_version_min: OciVersion,
     /// The maximum OCI Runtime Spec version recognized by the runtime, e.g., "1.0.2-dev".
-    oci_version_max: String,
+    oci_version_max: OciVersion,
     /// The list of the recognized hook names, e.g., "createRuntime".
     /// "None" means "unknown", not "no support for any hook".
     hooks: Option<Vec<String>>,
@@ -56,6 +57,24 @@ pub struct Features {
     potentially_unsafe_config_annotations: Option<Vec<String>>,
 }
 
+option_getters! {
+    Features {
+        hooks_ref => hooks: [String],
+        mount_options_ref => mount_options: [String],
+        linux_ref => linux: LinuxFeature,
+        annotations_ref => annotations: HashMap<String, String>,
+        potentially_unsafe_config_annotations_ref => potentially_unsafe_config_annotations: [String],
+    }
+}
+
+impl Features {
+    /// Returns true if `version` lies within the inclusive range of
+    /// `ociVersionMin` and `ociVersionMax`.
+    pub fn supports_version(&self, version: &OciVersion) -> bool {
+        (&self.oci_version_min..=&self.oci_version_max).contains(&version)
+    }
+}
+
 /// Linux specific features.
 #[derive(
     Builder,
@@ -70,6 +89,7 @@ pub struct Features {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -99,6 +119,19 @@ pub struct LinuxFeature {
     mount_extensions: Option<MountExtensions>,
 }
 
+option_getters! {
+    LinuxFeature {
+        namespaces_ref => namespaces: [LinuxNamespaceType],
+        capabilities_ref => capabilities: [String],
+        cgroup_ref => cgroup: Cgroup,
+        seccomp_ref => seccomp: Seccomp,
+        apparmor_ref => apparmor: Apparmor,
+        selinux_ref => selinux: Selinux,
+        intel_rdt_ref => intel_rdt: IntelRdt,
+        mount_extensions_ref => mount_extensions: MountExtensions,
+    }
+}
+
 /// Cgroup represents the "cgroup" field.
 #[derive(
     Builder,
@@ -113,6 +146,7 @@ pub struct LinuxFeature {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -158,6 +192,7 @@ pub struct Cgroup {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -189,6 +224,16 @@ pub struct Seccomp {
     supported_flags: Option<Vec<String>>,
 }
 
+option_getters! {
+    Seccomp {
+        actions_ref => actions: [LinuxSeccompAction],
+        operators_ref => operators: [String],
+        archs_ref => archs: [Arch],
+        known_flags_ref => known_flags: [String],
+        supported_flags_ref => supported_flags: [String],
+    }
+}
+
 /// Apparmor represents the "apparmor" field.
 #[derive(
     Builder,
@@ -203,6 +248,7 @@ pub struct Seccomp {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -232,6 +278,7 @@ pub struct Apparmor {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -261,6 +308,7 @@ pub struct Selinux {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -289,6 +337,7 @@ pub struct IntelRdt {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -302,6 +351,12 @@ pub struct MountExtensions {
     idmap: Option<IDMap>,
 }
 
+option_getters! {
+    MountExtensions {
+        idmap_ref => idmap: IDMap,
+    }
+}
+
 /// IDMap represents the "idmap" field.
 #[derive(
     Builder,
@@ -316,6 +371,7 @@ pub struct MountExtensions {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -333,7 +389,6 @@ pub struct IDMap {
 
 #[cfg(test)]
 mod tests {
-    use std::ops::Deref;
 
     use super::*;
 
@@ -545,8 +600,10 @@ mod tests {
 
         // Parse and check each field
         let features: Features = serde_json::from_str(example_json).unwrap();
-        assert_eq!(features.oci_version_min().deref(), "1.0.0".to_string());
-        assert_eq!(features.oci_version_max().deref(), "1.1.0-rc.2".to_string());
+        assert_eq!(features.oci_version_min(), &OciVersion::new(1, 0, 0));
+        assert_eq!(features.oci_version_max().to_string(), "1.1.0-rc.2");
+        assert!(features.supports_version(&"1.1.0-rc.1".parse().unwrap()));
+        assert!(!features.supports_version(&OciVersion::new(1, 1, 0)));
 
         assert_eq!(
             features.hooks.as_ref().unwrap(),
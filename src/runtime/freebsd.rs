@@ -0,0 +1,292 @@
+use std::path::PathBuf;
+
+use crate::error::OciSpecError;
+use derive_builder::Builder;
+use getset::{CopyGetters, Getters, Setters};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display as StrumDisplay, EnumString};
+
+#[derive(
+    Builder, Clone, Debug, Default, Deserialize, Getters, Setters, Eq, PartialEq, Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+#[getset(get = "pub", set = "pub")]
+/// FreeBSD contains platform-specific configuration for FreeBSD jail based
+/// containers, as used by runj. The section is not part of the runtime spec
+/// yet.
+///
+/// ```
+/// use oci_spec::runtime::{FreeBSDSharing, Spec};
+///
+/// let spec: Spec = serde_json::from_str(
+///     r#"{"ociVersion": "1.2.0", "freebsd": {"jail": {"vnet": "new", "allow": {"rawSockets": true}}}}"#,
+/// ).unwrap();
+/// let jail = spec.freebsd().as_ref().unwrap().jail().as_ref().unwrap();
+/// assert_eq!(jail.vnet(), &Some(FreeBSDSharing::New));
+/// assert_eq!(jail.allow().as_ref().unwrap().raw_sockets(), Some(true));
+/// ```
+pub struct FreeBSD {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Devices which must be made available in the container.
+    devices: Option<Vec<FreeBSDDevice>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Parameters of the jail of the container.
+    jail: Option<FreeBSDJail>,
+}
+
+option_getters! {
+    FreeBSD {
+        devices_ref => devices: [FreeBSDDevice],
+        jail_ref => jail: FreeBSDJail,
+    }
+}
+
+#[derive(
+    Builder,
+    Clone,
+    CopyGetters,
+    Debug,
+    Default,
+    Deserialize,
+    Getters,
+    Setters,
+    Eq,
+    PartialEq,
+    Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+/// A device node made available in a FreeBSD container.
+pub struct FreeBSDDevice {
+    #[getset(get = "pub", set = "pub")]
+    /// Path to the device inside the container.
+    path: PathBuf,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    /// File mode of the device.
+    mode: Option<u32>,
+}
+
+#[derive(
+    Builder, Clone, Debug, Default, Deserialize, Getters, Setters, Eq, PartialEq, Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+#[getset(get = "pub", set = "pub")]
+/// Parameters of a FreeBSD jail, see jail(8).
+pub struct FreeBSDJail {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Name of the parent jail to create the jail in.
+    parent: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Whether the jail has its own host name or inherits the one of its
+    /// parent.
+    host: Option<FreeBSDSharing>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Access of the jail to the IPv4 addresses of its parent.
+    ip4: Option<FreeBSDSharing>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// IPv4 addresses of the jail if `ip4` is `new`.
+    ip4_addr: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Access of the jail to the IPv6 addresses of its parent.
+    ip6: Option<FreeBSDSharing>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// IPv6 addresses of the jail if `ip6` is `new`.
+    ip6_addr: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Whether the jail has its own virtual network stack.
+    vnet: Option<FreeBSDSharing>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Interfaces moved into the virtual network stack of the jail.
+    vnet_interfaces: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Network interface to add the IPv4 and IPv6 addresses of the jail to.
+    interface: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Number of the devfs ruleset applied to the devfs mounted in the jail.
+    devfs_ruleset: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Which mount points are visible in the jail: `0` shows all, `1` only
+    /// those below the jail root and `2` only the jail root itself.
+    enforce_statfs: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Access of the jail to System V message queues.
+    sysvmsg: Option<FreeBSDSharing>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Access of the jail to System V semaphores.
+    sysvsem: Option<FreeBSDSharing>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Access of the jail to System V shared memory.
+    sysvshm: Option<FreeBSDSharing>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Privileges granted to the jail.
+    allow: Option<FreeBSDJailAllow>,
+}
+
+option_getters! {
+    FreeBSDJail {
+        parent_ref => parent: str,
+        host_ref => host: FreeBSDSharing,
+        ip4_ref => ip4: FreeBSDSharing,
+        ip4_addr_ref => ip4_addr: [String],
+        ip6_ref => ip6: FreeBSDSharing,
+        ip6_addr_ref => ip6_addr: [String],
+        vnet_ref => vnet: FreeBSDSharing,
+        vnet_interfaces_ref => vnet_interfaces: [String],
+        interface_ref => interface: str,
+        sysvmsg_ref => sysvmsg: FreeBSDSharing,
+        sysvsem_ref => sysvsem: FreeBSDSharing,
+        sysvshm_ref => sysvshm: FreeBSDSharing,
+        allow_ref => allow: FreeBSDJailAllow,
+    }
+}
+
+#[derive(
+    Builder,
+    Clone,
+    CopyGetters,
+    Debug,
+    Default,
+    Deserialize,
+    Getters,
+    Setters,
+    Eq,
+    PartialEq,
+    Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+/// The `allow.*` parameters of a FreeBSD jail. Unset privileges are denied.
+pub struct FreeBSDJailAllow {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    /// Allow processes in the jail to change the host name of the jail.
+    set_hostname: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    /// Allow processes in the jail to create raw sockets.
+    raw_sockets: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    /// Allow privileged users in the jail to change system file flags.
+    chflags: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    /// File system types privileged users in the jail may mount, e.g.
+    /// `nullfs` or `tmpfs`.
+    mount: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    /// Allow privileged users in the jail to administer quotas.
+    quotas: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    /// Allow processes in the jail to use all socket address families.
+    socket_af: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    /// Allow processes in the jail to lock memory.
+    mlock: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    /// Allow privileged users in the jail to bind to reserved ports.
+    reserved_ports: Option<bool>,
+}
+
+option_getters! {
+    FreeBSDJailAllow {
+        mount_ref => mount: [String],
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, Deserialize, EnumString, Eq, Hash, PartialEq, Serialize, StrumDisplay,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+/// How a jail shares a resource with its parent.
+pub enum FreeBSDSharing {
+    /// The jail gets its own instance of the resource.
+    New,
+    /// The jail uses the resource of its parent.
+    Inherit,
+    /// The jail has no access to the resource.
+    Disable,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::Result,
+        runtime::{Spec, SpecBuilder},
+    };
+
+    #[test]
+    fn freebsd_extension() -> Result<()> {
+        let jail = FreeBSDJailBuilder::default()
+            .ip4(FreeBSDSharing::New)
+            .ip4_addr(vec!["10.0.0.2".to_owned()])
+            .vnet(FreeBSDSharing::Disable)
+            .build()?;
+        let freebsd = FreeBSDBuilder::default().jail(jail).build()?;
+
+        let spec = SpecBuilder::default().freebsd(freebsd.clone()).build()?;
+        let json = serde_json::to_value(&spec)?;
+        assert_eq!(json["freebsd"]["jail"]["ip4Addr"][0], "10.0.0.2");
+
+        let reloaded: Spec = serde_json::from_value(json)?;
+        assert_eq!(reloaded.freebsd(), &Some(freebsd));
+        assert!(reloaded.extension_platforms().is_empty());
+        Ok(())
+    }
+}
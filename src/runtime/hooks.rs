@@ -17,6 +17,7 @@ use std::path::PathBuf;
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -76,6 +77,21 @@ pub struct Hooks {
     poststop: Option<Vec<Hook>>,
 }
 
+option_getters! {
+    Hooks {
+        #[deprecated(
+            note = "Prestart hooks were deprecated in favor of `createRuntime`, `createContainer` and `startContainer` hooks"
+        )]
+        #[allow(deprecated)]
+        prestart_ref => prestart: [Hook],
+        create_runtime_ref => create_runtime: [Hook],
+        create_container_ref => create_container: [Hook],
+        start_container_ref => start_container: [Hook],
+        poststart_ref => poststart: [Hook],
+        poststop_ref => poststop: [Hook],
+    }
+}
+
 #[derive(
     Builder,
     Clone,
@@ -90,6 +106,7 @@ pub struct Hooks {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -125,3 +142,10 @@ pub struct Hook {
     /// timeout MUST be greater than zero.
     timeout: Option<i64>,
 }
+
+option_getters! {
+    Hook {
+        args_ref => args: [String],
+        env_ref => env: [String],
+    }
+}
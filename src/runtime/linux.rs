@@ -3,18 +3,25 @@ use crate::error::{oci_error, OciSpecError};
 use derive_builder::Builder;
 use getset::{CopyGetters, Getters, MutGetters, Setters};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fmt::Display, path::PathBuf, vec};
-use strum_macros::{Display as StrumDisplay, EnumString};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Component, Path, PathBuf},
+    vec,
+};
+use strum_macros::{Display as StrumDisplay, EnumString, FromRepr};
 
 #[derive(
     Builder, Clone, Debug, Deserialize, Eq, Getters, MutGetters, Setters, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
     pattern = "owned",
+    derive(Clone),
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 #[getset(get_mut = "pub", get = "pub", set = "pub")]
 /// Linux contains platform-specific configuration for Linux based
@@ -66,11 +73,11 @@ pub struct Linux {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// MaskedPaths masks over the provided paths inside the container.
-    masked_paths: Option<Vec<String>>,
+    masked_paths: Option<Vec<PathBuf>>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// ReadonlyPaths sets the provided paths as RO inside the container.
-    readonly_paths: Option<Vec<String>>,
+    readonly_paths: Option<Vec<PathBuf>>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// MountLabel specifies the selinux context for the mounts in the
@@ -93,6 +100,48 @@ pub struct Linux {
     time_offsets: Option<HashMap<String, String>>,
 }
 
+option_getters! {
+    Linux {
+        uid_mappings_ref => uid_mappings: [LinuxIdMapping],
+        gid_mappings_ref => gid_mappings: [LinuxIdMapping],
+        sysctl_ref => sysctl: HashMap<String, String>,
+        resources_ref => resources: LinuxResources,
+        cgroups_path_ref => cgroups_path: Path,
+        namespaces_ref => namespaces: [LinuxNamespace],
+        devices_ref => devices: [LinuxDevice],
+        seccomp_ref => seccomp: LinuxSeccomp,
+        rootfs_propagation_ref => rootfs_propagation: str,
+        masked_paths_ref => masked_paths: [PathBuf],
+        readonly_paths_ref => readonly_paths: [PathBuf],
+        mount_label_ref => mount_label: str,
+        intel_rdt_ref => intel_rdt: LinuxIntelRdt,
+        personality_ref => personality: LinuxPersonality,
+        time_offsets_ref => time_offsets: HashMap<String, String>,
+    }
+}
+
+mutable_builder! {
+    /// Non-consuming builder for [`Linux`], which allows setting fields
+    /// conditionally without reassigning the builder.
+    LinuxBuilderMut(LinuxBuilder) -> Linux {
+        uid_mappings: Vec<LinuxIdMapping>,
+        gid_mappings: Vec<LinuxIdMapping>,
+        sysctl: HashMap<String, String>,
+        resources: LinuxResources,
+        cgroups_path: PathBuf,
+        namespaces: Vec<LinuxNamespace>,
+        devices: Vec<LinuxDevice>,
+        seccomp: LinuxSeccomp,
+        rootfs_propagation: String,
+        masked_paths: Vec<PathBuf>,
+        readonly_paths: Vec<PathBuf>,
+        mount_label: String,
+        intel_rdt: LinuxIntelRdt,
+        personality: LinuxPersonality,
+        time_offsets: HashMap<String, String>,
+    }
+}
+
 // Default impl for Linux (see funtions for more info)
 impl Default for Linux {
     fn default() -> Self {
@@ -133,36 +182,113 @@ impl Default for Linux {
     }
 }
 
+impl LinuxBuilder {
+    fn validate(&self) -> Result<(), OciSpecError> {
+        for mappings in [&self.uid_mappings, &self.gid_mappings]
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            validate_id_mappings(mappings)?;
+        }
+
+        if let Some(Some(namespaces)) = &self.namespaces {
+            for (i, namespace) in namespaces.iter().enumerate() {
+                if namespaces[..i].iter().any(|ns| ns.typ == namespace.typ) {
+                    return Err(oci_error(format!(
+                        "namespace {} is specified more than once",
+                        namespace.typ
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl Linux {
     /// Return rootless Linux configuration.
     pub fn rootless(uid: u32, gid: u32) -> Self {
-        let mut namespaces = get_default_namespaces();
-        namespaces.retain(|ns| ns.typ != LinuxNamespaceType::Network);
-        namespaces.push(LinuxNamespace {
-            typ: LinuxNamespaceType::User,
-            ..Default::default()
-        });
-        Self {
+        let mut linux = Self {
             resources: None,
-            uid_mappings: Some(vec![LinuxIdMapping {
-                container_id: 0,
-                host_id: uid,
-                size: 1,
-            }]),
-            gid_mappings: Some(vec![LinuxIdMapping {
+            namespaces: Some(get_default_namespaces()),
+            ..Default::default()
+        };
+        linux.make_rootless(uid, gid);
+        linux
+    }
+
+    /// Maps the user and group `0` of the container to `uid` and `gid` unless
+    /// mappings are already set, removes the network namespace and adds a
+    /// user namespace if there is none.
+    pub(super) fn make_rootless(&mut self, uid: u32, gid: u32) {
+        let mapping = |host_id| {
+            Some(vec![LinuxIdMapping {
                 container_id: 0,
-                host_id: gid,
+                host_id,
                 size: 1,
-            }]),
-            namespaces: Some(namespaces),
-            ..Default::default()
+            }])
+        };
+        if self.uid_mappings.is_none() {
+            self.uid_mappings = mapping(uid);
+        }
+        if self.gid_mappings.is_none() {
+            self.gid_mappings = mapping(gid);
+        }
+
+        let namespaces = self.namespaces.get_or_insert_with(Vec::new);
+        namespaces.retain(|ns| ns.typ != LinuxNamespaceType::Network);
+        if !namespaces
+            .iter()
+            .any(|ns| ns.typ == LinuxNamespaceType::User)
+        {
+            namespaces.push(LinuxNamespace {
+                typ: LinuxNamespaceType::User,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Lexically normalize the masked and readonly paths, e.g. `/proc//sys/./`
+    /// becomes `/proc/sys`, and drop duplicates while keeping the first
+    /// occurrence of each path.
+    pub fn normalize_paths(&mut self) {
+        for paths in [&mut self.masked_paths, &mut self.readonly_paths]
+            .into_iter()
+            .flatten()
+        {
+            let mut normalized: Vec<PathBuf> = Vec::with_capacity(paths.len());
+            for path in paths.iter().map(|p| normalize_path(p)) {
+                if !normalized.contains(&path) {
+                    normalized.push(path);
+                }
+            }
+            *paths = normalized;
+        }
+    }
+}
+
+/// Resolves `.` and `..` components of `path` without accessing the
+/// filesystem.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
         }
     }
+    normalized
 }
 
 #[derive(
     Builder, Clone, Copy, CopyGetters, Debug, Default, Deserialize, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -187,7 +313,175 @@ pub struct LinuxIdMapping {
     size: u32,
 }
 
+impl LinuxIdMapping {
+    /// Returns the host ID the container ID `id` is mapped to, if it is
+    /// within the range of this mapping.
+    pub fn to_host(&self, id: u32) -> Option<u32> {
+        let offset = id.checked_sub(self.container_id)?;
+        if offset >= self.size {
+            return None;
+        }
+        self.host_id.checked_add(offset)
+    }
+
+    /// Parses an entry of a subordinate ID file like `/etc/subuid` or
+    /// `/etc/subgid`, in the `owner:start:count` form, into its owner and a
+    /// mapping of the `count` IDs starting at the host ID `start`. The
+    /// container ID of the mapping is zero.
+    ///
+    /// ```
+    /// use oci_spec::runtime::LinuxIdMapping;
+    ///
+    /// let (owner, mapping) = LinuxIdMapping::from_subid_entry("alice:100000:65536")?;
+    /// assert_eq!(owner, "alice");
+    /// assert_eq!(mapping.host_id(), 100000);
+    /// assert_eq!(mapping.size(), 65536);
+    /// # Ok::<(), oci_spec::OciSpecError>(())
+    /// ```
+    pub fn from_subid_entry(entry: &str) -> crate::Result<(&str, Self)> {
+        let invalid = || oci_error(format!("invalid subordinate ID entry {entry}"));
+        let mut fields = entry.trim().split(':');
+        let (Some(owner), Some(start), Some(count), None) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(invalid());
+        };
+        if owner.is_empty() {
+            return Err(invalid());
+        }
+        Ok((
+            owner,
+            Self {
+                container_id: 0,
+                host_id: start.parse().map_err(|_| invalid())?,
+                size: count.parse().map_err(|_| invalid())?,
+            },
+        ))
+    }
+}
+
+/// Returns the ID mappings of a rootless container for a user from the
+/// content of a subordinate ID file like `/etc/subuid` or `/etc/subgid`.
+///
+/// The ID `0` of the container is mapped to `id`, the ID of the user on the
+/// host, and the subordinate IDs of the user are mapped consecutively to the
+/// container IDs starting at `1`. Entries are owned by the user if their
+/// owner is either `user` or `id`. Empty lines and comments are skipped.
+///
+/// # Errors
+/// Fails if an entry is invalid or if the subordinate IDs exceed the range
+/// of container IDs.
+///
+/// ```
+/// use oci_spec::runtime::subid_mappings;
+///
+/// let subuid = "alice:100000:65536\nbob:165536:65536\n";
+/// let mappings = subid_mappings(subuid, "alice", 1000)?;
+/// assert_eq!(mappings.len(), 2);
+/// assert_eq!(mappings[1].container_id(), 1);
+/// assert_eq!(mappings[1].host_id(), 100000);
+/// # Ok::<(), oci_spec::OciSpecError>(())
+/// ```
+pub fn subid_mappings(content: &str, user: &str, id: u32) -> crate::Result<Vec<LinuxIdMapping>> {
+    let id_owner = id.to_string();
+    let mut mappings = vec![LinuxIdMapping {
+        container_id: 0,
+        host_id: id,
+        size: 1,
+    }];
+    let mut container_id = 1u32;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (owner, mut mapping) = LinuxIdMapping::from_subid_entry(line)?;
+        if owner != user && owner != id_owner {
+            continue;
+        }
+        mapping.container_id = container_id;
+        container_id = container_id.checked_add(mapping.size).ok_or_else(|| {
+            oci_error(format!("subordinate IDs of {user} exceed the range of IDs"))
+        })?;
+        mappings.push(mapping);
+    }
+    Ok(mappings)
+}
+
+/// The maximum number of lines of an ID map supported by the kernel.
+pub const MAX_ID_MAPPINGS: usize = 340;
+
+/// Validates an ID mapping table as the kernel does when it is written to
+/// `/proc/<pid>/uid_map` or `gid_map`.
+///
+/// # Errors
+/// Fails if a mapping has a size of zero, if the container or host range of
+/// a mapping exceeds the range of `u32`, if the container or host ranges of
+/// two mappings overlap, or if there are more than [MAX_ID_MAPPINGS]
+/// mappings.
+///
+/// ```
+/// use oci_spec::runtime::{map_container_id, validate_id_mappings, LinuxIdMappingBuilder};
+///
+/// let mappings = [
+///     LinuxIdMappingBuilder::default().container_id(0u32).host_id(1000u32).size(1u32).build()?,
+///     LinuxIdMappingBuilder::default().container_id(1u32).host_id(100000u32).size(65536u32).build()?,
+/// ];
+/// validate_id_mappings(&mappings)?;
+/// assert_eq!(map_container_id(&mappings, 0), Some(1000));
+/// assert_eq!(map_container_id(&mappings, 10), Some(100009));
+/// assert_eq!(map_container_id(&mappings, 65537), None);
+/// # Ok::<(), oci_spec::OciSpecError>(())
+/// ```
+pub fn validate_id_mappings(mappings: &[LinuxIdMapping]) -> crate::Result<()> {
+    if mappings.len() > MAX_ID_MAPPINGS {
+        return Err(oci_error(format!(
+            "{} ID mappings exceed the maximum of {MAX_ID_MAPPINGS}",
+            mappings.len()
+        )));
+    }
+
+    let range = |start: u32, size: u32| u64::from(start)..u64::from(start) + u64::from(size);
+    let max = u64::from(u32::MAX) + 1;
+    for (i, mapping) in mappings.iter().enumerate() {
+        if mapping.size == 0 {
+            return Err(oci_error(format!("ID mapping {i} has a size of zero")));
+        }
+        let container = range(mapping.container_id, mapping.size);
+        let host = range(mapping.host_id, mapping.size);
+        if container.end > max || host.end > max {
+            return Err(oci_error(format!(
+                "ID mapping {i} exceeds the range of IDs"
+            )));
+        }
+
+        for (j, other) in mappings[..i].iter().enumerate() {
+            let overlaps = |a: &std::ops::Range<u64>, b: std::ops::Range<u64>| {
+                a.start < b.end && b.start < a.end
+            };
+            if overlaps(&container, range(other.container_id, other.size)) {
+                return Err(oci_error(format!(
+                    "container IDs of ID mappings {j} and {i} overlap"
+                )));
+            }
+            if overlaps(&host, range(other.host_id, other.size)) {
+                return Err(oci_error(format!(
+                    "host IDs of ID mappings {j} and {i} overlap"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the host ID the container ID `id` is mapped to by `mappings`, or
+/// `None` if it is not mapped.
+pub fn map_container_id(mappings: &[LinuxIdMapping], id: u32) -> Option<u32> {
+    mappings.iter().find_map(|m| m.to_host(id))
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, EnumString)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[strum(serialize_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 /// Device types
@@ -242,6 +536,7 @@ impl LinuxDeviceType {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -277,6 +572,12 @@ pub struct LinuxDeviceCgroup {
     access: Option<String>,
 }
 
+option_getters! {
+    LinuxDeviceCgroup {
+        access_ref => access: str,
+    }
+}
+
 /// This ToString trait is automatically implemented for any type which implements the Display trait.
 /// As such, ToString shouldn’t be implemented directly: Display should be implemented instead,
 /// and you get the ToString implementation for free.
@@ -303,8 +604,19 @@ impl Display for LinuxDeviceCgroup {
 }
 
 #[derive(
-    Builder, Clone, Copy, CopyGetters, Debug, Default, Deserialize, Eq, PartialEq, Serialize,
+    Builder,
+    Clone,
+    Copy,
+    CopyGetters,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    PartialEq,
+    Serialize,
+    Setters,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -375,12 +687,13 @@ pub struct LinuxMemory {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 /// LinuxCPU for Linux cgroup 'cpu' resource management.
 pub struct LinuxCpu {
@@ -433,6 +746,102 @@ pub struct LinuxCpu {
     mems: Option<String>,
 }
 
+option_getters! {
+    LinuxCpu {
+        cpus_ref => cpus: str,
+        mems_ref => mems: str,
+    }
+}
+
+/// The bounds of `cpu.cfs_period_us` in microseconds, which also bound the
+/// quota from below.
+const CPU_PERIOD_MIN: u64 = 1_000;
+const CPU_PERIOD_MAX: u64 = 1_000_000;
+
+impl LinuxCpu {
+    /// Check the values against the limits of the kernel: the period lies
+    /// between 1ms and 1s, a limited quota is at least 1ms, the burst does
+    /// not exceed the quota, the realtime runtime does not exceed the
+    /// realtime period, idle is 0 or 1 and cpus and mems are lists like
+    /// `0-3,8`. Empty cpus and mems are treated as unset.
+    /// # Errors
+    /// This function will return an [OciSpecError::Other] for the first violated rule.
+    pub fn validate(&self) -> Result<(), OciSpecError> {
+        if let Some(period) = self.period {
+            if !(CPU_PERIOD_MIN..=CPU_PERIOD_MAX).contains(&period) {
+                return Err(oci_error(format!(
+                    "cpu period {period} must be between {CPU_PERIOD_MIN} and {CPU_PERIOD_MAX}"
+                )));
+            }
+        }
+
+        // A negative quota, usually -1, means unlimited.
+        if let Some(quota) = self.quota.filter(|quota| *quota >= 0) {
+            if quota < CPU_PERIOD_MIN as i64 {
+                return Err(oci_error(format!(
+                    "cpu quota {quota} must be at least {CPU_PERIOD_MIN}"
+                )));
+            }
+            if let Some(burst) = self.burst.filter(|burst| *burst > quota as u64) {
+                return Err(oci_error(format!(
+                    "cpu burst {burst} exceeds quota {quota}"
+                )));
+            }
+        }
+
+        if let (Some(runtime), Some(period)) = (self.realtime_runtime, self.realtime_period) {
+            if runtime > 0 && runtime as u64 > period {
+                return Err(oci_error(format!(
+                    "cpu realtimeRuntime {runtime} exceeds realtimePeriod {period}"
+                )));
+            }
+        }
+
+        if let Some(idle) = self.idle.filter(|idle| !matches!(idle, 0 | 1)) {
+            return Err(oci_error(format!("cpu idle {idle} must be 0 or 1")));
+        }
+
+        for (name, list) in [("cpus", &self.cpus), ("mems", &self.mems)] {
+            if let Some(list) = list.as_deref().filter(|l| !l.is_empty() && !is_cpu_list(l)) {
+                return Err(oci_error(format!("invalid cpu {name} list {list:?}")));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl LinuxCpuBuilder {
+    fn validate(&self) -> Result<(), OciSpecError> {
+        LinuxCpu {
+            shares: self.shares.flatten(),
+            quota: self.quota.flatten(),
+            idle: self.idle.flatten(),
+            burst: self.burst.flatten(),
+            period: self.period.flatten(),
+            realtime_runtime: self.realtime_runtime.flatten(),
+            realtime_period: self.realtime_period.flatten(),
+            cpus: self.cpus.clone().flatten(),
+            mems: self.mems.clone().flatten(),
+        }
+        .validate()
+    }
+}
+
+/// Returns true if `s` is a comma separated list of numbers and ascending
+/// ranges in the kernel list format, e.g. `0-3,7`.
+pub(super) fn is_cpu_list(s: &str) -> bool {
+    let number = |n: &str| {
+        (!n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| n.parse::<u32>().ok())
+            .flatten()
+    };
+    s.split(',').all(|item| match item.split_once('-') {
+        Some((start, end)) => matches!((number(start), number(end)), (Some(s), Some(e)) if s <= e),
+        None => number(item).is_some(),
+    })
+}
+
 #[derive(
     Builder,
     Clone,
@@ -446,6 +855,7 @@ pub struct LinuxCpu {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -463,6 +873,7 @@ pub struct LinuxPids {
 #[derive(
     Builder, Clone, Copy, CopyGetters, Debug, Default, Deserialize, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -495,6 +906,7 @@ pub struct LinuxWeightDevice {
 #[derive(
     Builder, Clone, Copy, CopyGetters, Debug, Default, Deserialize, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -530,6 +942,7 @@ pub struct LinuxThrottleDevice {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -565,17 +978,35 @@ pub struct LinuxBlockIo {
     /// IO write rate limit per cgroup per device, bytes per second.
     throttle_write_bps_device: Option<Vec<LinuxThrottleDevice>>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "throttleReadIOPSDevice",
+        alias = "throttleReadIopsDevice",
+        skip_serializing_if = "Option::is_none"
+    )]
     #[getset(get = "pub", set = "pub")]
     /// IO read rate limit per cgroup per device, IO per second.
     throttle_read_iops_device: Option<Vec<LinuxThrottleDevice>>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "throttleWriteIOPSDevice",
+        alias = "throttleWriteIopsDevice",
+        skip_serializing_if = "Option::is_none"
+    )]
     #[getset(get = "pub", set = "pub")]
     /// IO write rate limit per cgroup per device, IO per second.
     throttle_write_iops_device: Option<Vec<LinuxThrottleDevice>>,
 }
 
+option_getters! {
+    LinuxBlockIo {
+        weight_device_ref => weight_device: [LinuxWeightDevice],
+        throttle_read_bps_device_ref => throttle_read_bps_device: [LinuxThrottleDevice],
+        throttle_write_bps_device_ref => throttle_write_bps_device: [LinuxThrottleDevice],
+        throttle_read_iops_device_ref => throttle_read_iops_device: [LinuxThrottleDevice],
+        throttle_write_iops_device_ref => throttle_write_iops_device: [LinuxThrottleDevice],
+    }
+}
+
 #[derive(
     Builder,
     Clone,
@@ -589,6 +1020,7 @@ pub struct LinuxBlockIo {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -624,6 +1056,7 @@ pub struct LinuxHugepageLimit {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -667,6 +1100,7 @@ impl Display for LinuxInterfacePriority {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -686,6 +1120,12 @@ pub struct LinuxNetwork {
     priorities: Option<Vec<LinuxInterfacePriority>>,
 }
 
+option_getters! {
+    LinuxNetwork {
+        priorities_ref => priorities: [LinuxInterfacePriority],
+    }
+}
+
 #[derive(
     Builder,
     Clone,
@@ -700,6 +1140,7 @@ pub struct LinuxNetwork {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -757,6 +1198,20 @@ pub struct LinuxResources {
     unified: Option<HashMap<String, String>>,
 }
 
+option_getters! {
+    LinuxResources {
+        devices_ref => devices: [LinuxDeviceCgroup],
+        memory_ref => memory: LinuxMemory,
+        cpu_ref => cpu: LinuxCpu,
+        pids_ref => pids: LinuxPids,
+        block_io_ref => block_io: LinuxBlockIo,
+        hugepage_limits_ref => hugepage_limits: [LinuxHugepageLimit],
+        network_ref => network: LinuxNetwork,
+        rdma_ref => rdma: HashMap<String, LinuxRdma>,
+        unified_ref => unified: HashMap<String, String>,
+    }
+}
+
 #[derive(
     Builder,
     Clone,
@@ -770,6 +1225,7 @@ pub struct LinuxResources {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -791,10 +1247,14 @@ pub struct LinuxRdma {
     hca_objects: Option<u32>,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, Hash, StrumDisplay)]
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, Hash, StrumDisplay,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[strum(serialize_all = "lowercase")]
 #[serde(rename_all = "snake_case")]
 /// Available Linux namespaces.
+#[non_exhaustive]
 pub enum LinuxNamespaceType {
     #[strum(to_string = "mnt")]
     /// Mount Namespace for isolating mount points
@@ -813,6 +1273,7 @@ pub enum LinuxNamespaceType {
     User = 0x10000000,
 
     /// PID Namespace for isolating process ids
+    #[default]
     Pid = 0x20000000,
 
     #[strum(to_string = "net")]
@@ -843,12 +1304,6 @@ impl TryFrom<&str> for LinuxNamespaceType {
     }
 }
 
-impl Default for LinuxNamespaceType {
-    fn default() -> Self {
-        Self::Pid
-    }
-}
-
 #[derive(
     Builder,
     Clone,
@@ -862,6 +1317,7 @@ impl Default for LinuxNamespaceType {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -882,6 +1338,12 @@ pub struct LinuxNamespace {
     path: Option<PathBuf>,
 }
 
+option_getters! {
+    LinuxNamespace {
+        path_ref => path: Path,
+    }
+}
+
 /// Utility function to get default namespaces.
 pub fn get_default_namespaces() -> Vec<LinuxNamespace> {
     vec![
@@ -926,6 +1388,7 @@ pub fn get_default_namespaces() -> Vec<LinuxNamespace> {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -997,12 +1460,13 @@ impl From<&LinuxDevice> for LinuxDeviceCgroup {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 /// LinuxSeccomp represents syscall restrictions.
 pub struct LinuxSeccomp {
@@ -1012,7 +1476,8 @@ pub struct LinuxSeccomp {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[getset(get_copy = "pub", set = "pub")]
-    /// The default error return code to use when the default action is SCMP_ACT_ERRNO.
+    /// The default error return code to use when the default action is
+    /// SCMP_ACT_ERRNO or SCMP_ACT_TRACE.
     default_errno_ret: Option<u32>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -1028,11 +1493,12 @@ pub struct LinuxSeccomp {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[getset(get = "pub", set = "pub")]
     /// The unix domain socket path over which runtime will use for `SCMP_ACT_NOTIFY`.
+    /// It must be absolute and fit into `sun_path`, i.e. be at most 107 bytes long.
     listener_path: Option<PathBuf>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[getset(get = "pub", set = "pub")]
-    /// An opaque data to pass to the seccomp agent.
+    /// An opaque data to pass to the seccomp agent, at most 4 KiB long.
     listener_metadata: Option<String>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -1041,10 +1507,70 @@ pub struct LinuxSeccomp {
     syscalls: Option<Vec<LinuxSyscall>>,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString)]
+option_getters! {
+    LinuxSeccomp {
+        architectures_ref => architectures: [Arch],
+        flags_ref => flags: [LinuxSeccompFilterFlag],
+        listener_path_ref => listener_path: Path,
+        listener_metadata_ref => listener_metadata: str,
+        syscalls_ref => syscalls: [LinuxSyscall],
+    }
+}
+
+/// The size of `sun_path` in `struct sockaddr_un`, minus the terminating NUL.
+const SECCOMP_LISTENER_PATH_MAX: usize = 107;
+
+const SECCOMP_LISTENER_METADATA_MAX: usize = 4096;
+
+impl LinuxSeccompBuilder {
+    fn validate(&self) -> Result<(), OciSpecError> {
+        let default_action = self.default_action.unwrap_or_default();
+        if default_action == LinuxSeccompAction::ScmpActNotify {
+            return Err(oci_error(
+                "seccomp defaultAction must not be SCMP_ACT_NOTIFY",
+            ));
+        }
+        if matches!(self.default_errno_ret, Some(Some(_))) && !default_action.takes_errno() {
+            return Err(oci_error(format!(
+                "seccomp defaultErrnoRet is not supported by the default action {default_action}"
+            )));
+        }
+
+        if let Some(Some(path)) = &self.listener_path {
+            if !path.is_absolute() {
+                return Err(oci_error(format!(
+                    "seccomp listenerPath {} must be absolute",
+                    path.display()
+                )));
+            }
+            if path.as_os_str().len() > SECCOMP_LISTENER_PATH_MAX {
+                return Err(oci_error(format!(
+                    "seccomp listenerPath {} exceeds {SECCOMP_LISTENER_PATH_MAX} bytes",
+                    path.display()
+                )));
+            }
+        }
+
+        if let Some(Some(metadata)) = &self.listener_metadata {
+            if metadata.len() > SECCOMP_LISTENER_METADATA_MAX {
+                return Err(oci_error(format!(
+                    "seccomp listenerMetadata exceeds {SECCOMP_LISTENER_METADATA_MAX} bytes"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 /// Available seccomp actions.
+#[non_exhaustive]
 pub enum LinuxSeccompAction {
     /// Kill the thread, defined for backward compatibility.
     ScmpActKill,
@@ -1071,9 +1597,18 @@ pub enum LinuxSeccompAction {
     ScmpActLog,
 
     /// Allow the syscall to be executed.
+    #[default]
     ScmpActAllow,
 }
 
+impl LinuxSeccompAction {
+    /// Returns true if an errno return code can be specified for the action,
+    /// which is the case for `SCMP_ACT_ERRNO` and `SCMP_ACT_TRACE`.
+    fn takes_errno(self) -> bool {
+        matches!(self, Self::ScmpActErrno | Self::ScmpActTrace)
+    }
+}
+
 impl From<LinuxSeccompAction> for u32 {
     fn from(action: LinuxSeccompAction) -> Self {
         match action {
@@ -1090,17 +1625,159 @@ impl From<LinuxSeccompAction> for u32 {
     }
 }
 
-impl Default for LinuxSeccompAction {
-    fn default() -> Self {
-        Self::ScmpActAllow
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, StrumDisplay, EnumString, FromRepr)]
+#[strum(serialize_all = "UPPERCASE")]
+#[repr(u32)]
+/// Error numbers to be returned by `SCMP_ACT_ERRNO` and `SCMP_ACT_TRACE`,
+/// e.g. as [LinuxSeccomp::default_errno_ret] or [LinuxSyscall::errno_ret].
+/// The values are the generic Linux ones, shared by x86, arm and most other
+/// architectures.
+/// # Example
+/// ```
+/// use oci_spec::runtime::{Errno, LinuxSeccompAction, LinuxSeccompBuilder};
+///
+/// let seccomp = LinuxSeccompBuilder::default()
+///     .default_action(LinuxSeccompAction::ScmpActErrno)
+///     .default_errno_ret(Errno::Eperm)
+///     .build()
+///     .unwrap();
+/// assert_eq!(seccomp.default_errno_ret(), Some(1));
+/// ```
+#[non_exhaustive]
+pub enum Errno {
+    /// Operation not permitted.
+    Eperm = 1,
+
+    /// No such file or directory.
+    Enoent = 2,
+
+    /// No such process.
+    Esrch = 3,
+
+    /// Interrupted system call.
+    Eintr = 4,
+
+    /// I/O error.
+    Eio = 5,
+
+    /// No such device or address.
+    Enxio = 6,
+
+    /// Argument list too long.
+    E2big = 7,
+
+    /// Exec format error.
+    Enoexec = 8,
+
+    /// Bad file number.
+    Ebadf = 9,
+
+    /// No child processes.
+    Echild = 10,
+
+    /// Try again.
+    Eagain = 11,
+
+    /// Out of memory.
+    Enomem = 12,
+
+    /// Permission denied.
+    Eacces = 13,
+
+    /// Bad address.
+    Efault = 14,
+
+    /// Device or resource busy.
+    Ebusy = 16,
+
+    /// File exists.
+    Eexist = 17,
+
+    /// Cross-device link.
+    Exdev = 18,
+
+    /// No such device.
+    Enodev = 19,
+
+    /// Not a directory.
+    Enotdir = 20,
+
+    /// Is a directory.
+    Eisdir = 21,
+
+    /// Invalid argument.
+    Einval = 22,
+
+    /// File table overflow.
+    Enfile = 23,
+
+    /// Too many open files.
+    Emfile = 24,
+
+    /// Not a typewriter.
+    Enotty = 25,
+
+    /// Text file busy.
+    Etxtbsy = 26,
+
+    /// File too large.
+    Efbig = 27,
+
+    /// No space left on device.
+    Enospc = 28,
+
+    /// Illegal seek.
+    Espipe = 29,
+
+    /// Read-only file system.
+    Erofs = 30,
+
+    /// Too many links.
+    Emlink = 31,
+
+    /// Broken pipe.
+    Epipe = 32,
+
+    /// Math result not representable.
+    Erange = 34,
+
+    /// File name too long.
+    Enametoolong = 36,
+
+    /// Invalid system call number.
+    Enosys = 38,
+
+    /// Operation not supported on transport endpoint.
+    Eopnotsupp = 95,
+
+    /// Address family not supported by protocol.
+    Eafnosupport = 97,
+
+    /// Connection refused.
+    Econnrefused = 111,
+}
+
+impl From<Errno> for u32 {
+    fn from(errno: Errno) -> Self {
+        errno as u32
+    }
+}
+
+impl TryFrom<u32> for Errno {
+    type Error = OciSpecError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Errno::from_repr(value).ok_or_else(|| oci_error(format!("unknown errno {value}")))
     }
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[repr(u32)]
 /// Available seccomp architectures.
+#[non_exhaustive]
 pub enum Arch {
     /// The native architecture.
     ScmpArchNative = 0x00000000,
@@ -1161,9 +1838,11 @@ pub enum Arch {
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 /// Available seccomp filter flags.
+#[non_exhaustive]
 pub enum LinuxSeccompFilterFlag {
     /// All filter return actions except SECCOMP_RET_ALLOW should be logged. An administrator may
     /// override this filter flag by preventing specific actions from being logged via the
@@ -1185,7 +1864,10 @@ pub enum LinuxSeccompFilterFlag {
     SeccompFilterFlagSpecAllow,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString)]
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[repr(u32)]
@@ -1201,6 +1883,7 @@ pub enum LinuxSeccompOperator {
     ScmpCmpLe = 3,
 
     /// Refers to the SCMP_CMP_EQ operator (equal to).
+    #[default]
     ScmpCmpEq = 4,
 
     /// Refers to the SCMP_CMP_GE operator (greater equal).
@@ -1213,12 +1896,6 @@ pub enum LinuxSeccompOperator {
     ScmpCmpMaskedEq = 7,
 }
 
-impl Default for LinuxSeccompOperator {
-    fn default() -> Self {
-        Self::ScmpCmpEq
-    }
-}
-
 #[derive(
     Builder,
     Clone,
@@ -1232,12 +1909,13 @@ impl Default for LinuxSeccompOperator {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 /// LinuxSyscall is used to match a syscall in seccomp.
 pub struct LinuxSyscall {
@@ -1260,15 +1938,144 @@ pub struct LinuxSyscall {
     args: Option<Vec<LinuxSeccompArg>>,
 }
 
+option_getters! {
+    LinuxSyscall {
+        args_ref => args: [LinuxSeccompArg],
+    }
+}
+
+impl LinuxSyscall {
+    /// Create a rule applying `action` to the syscalls `names`, e.g. one of the
+    /// groups in [syscalls](super::syscalls).
+    pub fn new<I, S>(names: I, action: LinuxSeccompAction) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            names: names.into_iter().map(|n| n.as_ref().to_owned()).collect(),
+            action,
+            ..Default::default()
+        }
+    }
+
+    /// Create a rule allowing the syscalls `names`.
+    /// # Example
+    /// ```
+    /// use oci_spec::runtime::{LinuxSeccompOperator, LinuxSyscall};
+    ///
+    /// let rule = LinuxSyscall::allow(["personality"])
+    ///     .with_arg(0, LinuxSeccompOperator::ScmpCmpEq, 0x0)
+    ///     .unwrap();
+    /// assert_eq!(rule.args_ref().unwrap().len(), 1);
+    /// ```
+    pub fn allow<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::new(names, LinuxSeccompAction::ScmpActAllow)
+    }
+
+    /// Create a rule failing the syscalls `names` with `errno`.
+    /// # Example
+    /// ```
+    /// use oci_spec::runtime::{Errno, LinuxSyscall};
+    ///
+    /// let rule = LinuxSyscall::errno(["clone3"], Errno::Enosys);
+    /// assert_eq!(rule.errno_ret(), Some(38));
+    /// ```
+    pub fn errno<I, S, E>(names: I, errno: E) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        E: Into<u32>,
+    {
+        Self {
+            errno_ret: Some(errno.into()),
+            ..Self::new(names, LinuxSeccompAction::ScmpActErrno)
+        }
+    }
+
+    /// Restrict the rule to calls whose argument at `index` compares to
+    /// `value` using `op`. Use [LinuxSyscall::with_masked_arg] for
+    /// [LinuxSeccompOperator::ScmpCmpMaskedEq], which takes two values.
+    /// # Errors
+    /// This function will return an [OciSpecError::Other] if `index` is larger
+    /// than 5, as syscalls take at most six arguments, or if `op` is
+    /// [LinuxSeccompOperator::ScmpCmpMaskedEq].
+    pub fn with_arg(
+        mut self,
+        index: usize,
+        op: LinuxSeccompOperator,
+        value: u64,
+    ) -> Result<Self, OciSpecError> {
+        let arg = LinuxSeccompArgBuilder::default()
+            .index(index)
+            .op(op)
+            .value(value)
+            .build()?;
+        self.args.get_or_insert_with(Vec::new).push(arg);
+        Ok(self)
+    }
+
+    /// Restrict the rule to calls whose argument at `index`, masked by
+    /// `mask`, equals `value`, i.e. the [LinuxSeccompOperator::ScmpCmpMaskedEq]
+    /// comparison with `mask` as `value` and `value` as `valueTwo`.
+    /// # Errors
+    /// This function will return an [OciSpecError::Other] if `index` is larger
+    /// than 5, as syscalls take at most six arguments.
+    /// # Example
+    /// ```
+    /// use oci_spec::runtime::LinuxSyscall;
+    ///
+    /// // Allow clone only without CLONE_NEWUSER.
+    /// let rule = LinuxSyscall::allow(["clone"])
+    ///     .with_masked_arg(0, 0x10000000, 0)
+    ///     .unwrap();
+    /// let arg = &rule.args_ref().unwrap()[0];
+    /// assert_eq!((arg.value(), arg.value_two()), (0x10000000, Some(0)));
+    /// ```
+    pub fn with_masked_arg(
+        mut self,
+        index: usize,
+        mask: u64,
+        value: u64,
+    ) -> Result<Self, OciSpecError> {
+        let arg = LinuxSeccompArgBuilder::default()
+            .index(index)
+            .op(LinuxSeccompOperator::ScmpCmpMaskedEq)
+            .value(mask)
+            .value_two(value)
+            .build()?;
+        self.args.get_or_insert_with(Vec::new).push(arg);
+        Ok(self)
+    }
+}
+
+impl LinuxSyscallBuilder {
+    fn validate(&self) -> Result<(), OciSpecError> {
+        let action = self.action.unwrap_or_default();
+        if matches!(self.errno_ret, Some(Some(_))) && !action.takes_errno() {
+            return Err(oci_error(format!(
+                "seccomp errnoRet is not supported by the action {action}"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(
     Builder, Clone, Copy, CopyGetters, Debug, Default, Deserialize, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 #[getset(get_copy = "pub", set = "pub")]
 /// LinuxSeccompArg used for matching specific syscall arguments in seccomp.
@@ -1287,33 +2094,56 @@ pub struct LinuxSeccompArg {
     op: LinuxSeccompOperator,
 }
 
+/// The largest argument index of a syscall, which take at most six arguments.
+const SECCOMP_ARG_INDEX_MAX: usize = 5;
+
+impl LinuxSeccompArgBuilder {
+    fn validate(&self) -> Result<(), OciSpecError> {
+        let index = self.index.unwrap_or_default();
+        if index > SECCOMP_ARG_INDEX_MAX {
+            return Err(oci_error(format!(
+                "seccomp arg index {index} exceeds {SECCOMP_ARG_INDEX_MAX}"
+            )));
+        }
+        if self.op == Some(LinuxSeccompOperator::ScmpCmpMaskedEq)
+            && !matches!(self.value_two, Some(Some(_)))
+        {
+            return Err(oci_error(
+                "seccomp operator SCMP_CMP_MASKED_EQ requires valueTwo",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 /// Default masks paths, cannot read these host files.
-pub fn get_default_maskedpaths() -> Vec<String> {
+pub fn get_default_maskedpaths() -> Vec<PathBuf> {
     vec![
         // For example now host interfaces such as
         // bluetooth cannot be accessed due to /proc/acpi
-        "/proc/acpi".to_string(),
-        "/proc/asound".to_string(),
-        "/proc/kcore".to_string(),
-        "/proc/keys".to_string(),
-        "/proc/latency_stats".to_string(),
-        "/proc/timer_list".to_string(),
-        "/proc/timer_stats".to_string(),
-        "/proc/sched_debug".to_string(),
-        "/sys/firmware".to_string(),
-        "/proc/scsi".to_string(),
+        PathBuf::from("/proc/acpi"),
+        PathBuf::from("/proc/asound"),
+        PathBuf::from("/proc/kcore"),
+        PathBuf::from("/proc/keys"),
+        PathBuf::from("/proc/latency_stats"),
+        PathBuf::from("/proc/timer_list"),
+        PathBuf::from("/proc/timer_stats"),
+        PathBuf::from("/proc/sched_debug"),
+        PathBuf::from("/sys/firmware"),
+        PathBuf::from("/proc/scsi"),
     ]
 }
 
 /// Default readonly paths, for example most containers shouldn't have permission to write to
 /// `/proc/sys`.
-pub fn get_default_readonly_paths() -> Vec<String> {
+pub fn get_default_readonly_paths() -> Vec<PathBuf> {
     vec![
-        "/proc/bus".to_string(),
-        "/proc/fs".to_string(),
-        "/proc/irq".to_string(),
-        "/proc/sys".to_string(),
-        "/proc/sysrq-trigger".to_string(),
+        PathBuf::from("/proc/bus"),
+        PathBuf::from("/proc/fs"),
+        PathBuf::from("/proc/irq"),
+        PathBuf::from("/proc/sys"),
+        PathBuf::from("/proc/sysrq-trigger"),
     ]
 }
 
@@ -1330,6 +2160,7 @@ pub fn get_default_readonly_paths() -> Vec<String> {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -1370,6 +2201,14 @@ pub struct LinuxIntelRdt {
     enable_mbm: Option<bool>,
 }
 
+option_getters! {
+    LinuxIntelRdt {
+        clos_id_ref => clos_id: str,
+        l3_cache_schema_ref => l3_cache_schema: str,
+        mem_bw_schema_ref => mem_bw_schema: str,
+    }
+}
+
 #[derive(
     Builder,
     Clone,
@@ -1383,6 +2222,7 @@ pub struct LinuxIntelRdt {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -1398,15 +2238,158 @@ pub struct LinuxPersonality {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[getset(get = "pub", set = "pub")]
     /// Additional flags
-    flags: Option<Vec<String>>,
+    flags: Option<Vec<LinuxPersonalityFlag>>,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString)]
+option_getters! {
+    LinuxPersonality {
+        flags_ref => flags: [LinuxPersonalityFlag],
+    }
+}
+
+/// A flag of the personality(2) syscall, see `linux/personality.h`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LinuxPersonalityFlag {
+    /// Report a Linux 2.6.40+ kernel version as 2.6.40 - 2.6.59.
+    Uname26,
+    /// Disable address space layout randomization.
+    AddrNoRandomize,
+    /// Treat user space function pointers to signal handlers as pointers to function descriptors.
+    FdpicFuncptrs,
+    /// Map page 0 as read-only.
+    MmapPageZero,
+    /// Use the legacy virtual address space layout.
+    AddrCompatLayout,
+    /// Make `PROT_READ` imply `PROT_EXEC` for mmap(2).
+    ReadImpliesExec,
+    /// Limit the address space to 32 bits.
+    AddrLimit32bit,
+    /// No effect.
+    ShortInode,
+    /// No effect.
+    WholeSeconds,
+    /// Do not modify the timeout arguments of select(2), pselect(2) and ppoll(2).
+    StickyTimeouts,
+    /// Limit the address space to 3 GB for 32-bit processes.
+    AddrLimit3gb,
+    /// A flag not known to this crate.
+    Other(String),
+}
+
+impl LinuxPersonalityFlag {
+    /// The value of the flag as passed to personality(2), `None` for
+    /// [LinuxPersonalityFlag::Other].
+    pub fn bits(&self) -> Option<u32> {
+        match self {
+            Self::Uname26 => Some(0x0020000),
+            Self::AddrNoRandomize => Some(0x0040000),
+            Self::FdpicFuncptrs => Some(0x0080000),
+            Self::MmapPageZero => Some(0x0100000),
+            Self::AddrCompatLayout => Some(0x0200000),
+            Self::ReadImpliesExec => Some(0x0400000),
+            Self::AddrLimit32bit => Some(0x0800000),
+            Self::ShortInode => Some(0x1000000),
+            Self::WholeSeconds => Some(0x2000000),
+            Self::StickyTimeouts => Some(0x4000000),
+            Self::AddrLimit3gb => Some(0x8000000),
+            Self::Other(_) => None,
+        }
+    }
+}
+
+impl From<&str> for LinuxPersonalityFlag {
+    fn from(flag: &str) -> Self {
+        match flag {
+            "UNAME26" => Self::Uname26,
+            "ADDR_NO_RANDOMIZE" => Self::AddrNoRandomize,
+            "FDPIC_FUNCPTRS" => Self::FdpicFuncptrs,
+            "MMAP_PAGE_ZERO" => Self::MmapPageZero,
+            "ADDR_COMPAT_LAYOUT" => Self::AddrCompatLayout,
+            "READ_IMPLIES_EXEC" => Self::ReadImpliesExec,
+            "ADDR_LIMIT_32BIT" => Self::AddrLimit32bit,
+            "SHORT_INODE" => Self::ShortInode,
+            "WHOLE_SECONDS" => Self::WholeSeconds,
+            "STICKY_TIMEOUTS" => Self::StickyTimeouts,
+            "ADDR_LIMIT_3GB" => Self::AddrLimit3gb,
+            flag => Self::Other(flag.to_owned()),
+        }
+    }
+}
+
+impl From<String> for LinuxPersonalityFlag {
+    fn from(flag: String) -> Self {
+        flag.as_str().into()
+    }
+}
+
+impl AsRef<str> for LinuxPersonalityFlag {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Uname26 => "UNAME26",
+            Self::AddrNoRandomize => "ADDR_NO_RANDOMIZE",
+            Self::FdpicFuncptrs => "FDPIC_FUNCPTRS",
+            Self::MmapPageZero => "MMAP_PAGE_ZERO",
+            Self::AddrCompatLayout => "ADDR_COMPAT_LAYOUT",
+            Self::ReadImpliesExec => "READ_IMPLIES_EXEC",
+            Self::AddrLimit32bit => "ADDR_LIMIT_32BIT",
+            Self::ShortInode => "SHORT_INODE",
+            Self::WholeSeconds => "WHOLE_SECONDS",
+            Self::StickyTimeouts => "STICKY_TIMEOUTS",
+            Self::AddrLimit3gb => "ADDR_LIMIT_3GB",
+            Self::Other(flag) => flag,
+        }
+    }
+}
+
+impl Display for LinuxPersonalityFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl Serialize for LinuxPersonalityFlag {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_ref())
+    }
+}
+
+impl<'de> Deserialize<'de> for LinuxPersonalityFlag {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(String::deserialize(deserializer)?.into())
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for LinuxPersonalityFlag {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "LinuxPersonalityFlag".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+        })
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 /// Define domain and flags for LinuxPersonality.
+#[non_exhaustive]
 pub enum LinuxPersonalityDomain {
     #[serde(rename = "LINUX")]
     #[strum(serialize = "LINUX")]
     /// PerLinux is the standard Linux personality.
+    #[default]
     PerLinux,
 
     #[serde(rename = "LINUX32")]
@@ -1415,23 +2398,10 @@ pub enum LinuxPersonalityDomain {
     PerLinux32,
 }
 
-impl Default for LinuxPersonalityDomain {
-    fn default() -> Self {
-        Self::PerLinux
-    }
-}
-
 #[cfg(feature = "proptests")]
-use quickcheck::{Arbitrary, Gen};
-
+use crate::some_none_generator_util;
 #[cfg(feature = "proptests")]
-fn some_none_generator_util<T: Arbitrary>(g: &mut Gen) -> Option<T> {
-    let choice = g.choose(&[true, false]).unwrap();
-    match choice {
-        false => None,
-        true => Some(T::arbitrary(g)),
-    }
-}
+use quickcheck::{Arbitrary, Gen};
 
 #[cfg(feature = "proptests")]
 impl Arbitrary for LinuxDeviceCgroup {
@@ -1703,4 +2673,228 @@ mod tests {
         let unknown_operator = invalid_seccomp_operator_str.parse::<LinuxSeccompOperator>();
         assert!(unknown_operator.is_err());
     }
+
+    #[test]
+    fn id_mappings() {
+        let mapping = |container_id, host_id, size| LinuxIdMapping {
+            container_id,
+            host_id,
+            size,
+        };
+        let valid = [mapping(0, 1000, 1), mapping(1, 100000, 65536)];
+        assert!(validate_id_mappings(&valid).is_ok());
+        assert!(validate_id_mappings(&[]).is_ok());
+        assert_eq!(map_container_id(&valid, 0), Some(1000));
+        assert_eq!(map_container_id(&valid, 65536), Some(165535));
+        assert_eq!(map_container_id(&valid, 65537), None);
+        assert_eq!(mapping(u32::MAX, 0, 1).to_host(u32::MAX), Some(0));
+
+        for invalid in [
+            vec![mapping(0, 0, 0)],
+            vec![mapping(u32::MAX, 0, 2)],
+            vec![mapping(0, u32::MAX - 1, 3)],
+            vec![mapping(0, 1000, 10), mapping(9, 2000, 1)],
+            vec![mapping(0, 1000, 10), mapping(10, 1005, 1)],
+            vec![mapping(0, 0, 1); MAX_ID_MAPPINGS + 1],
+        ] {
+            assert!(validate_id_mappings(&invalid).is_err(), "{invalid:?}");
+        }
+        assert!(
+            validate_id_mappings(&[mapping(0, 0, u32::MAX), mapping(u32::MAX, u32::MAX, 1)])
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn subid() {
+        assert!(LinuxIdMapping::from_subid_entry("alice:1:2:3").is_err());
+        assert!(LinuxIdMapping::from_subid_entry(":1:2").is_err());
+        assert!(LinuxIdMapping::from_subid_entry("alice:x:2").is_err());
+
+        let subuid = "# comment\nalice:100000:65536\n\nbob:165536:65536\n1000:300000:10\n";
+        let mappings = subid_mappings(subuid, "alice", 1000).unwrap();
+        assert_eq!(
+            mappings,
+            [
+                LinuxIdMapping {
+                    container_id: 0,
+                    host_id: 1000,
+                    size: 1
+                },
+                LinuxIdMapping {
+                    container_id: 1,
+                    host_id: 100000,
+                    size: 65536
+                },
+                LinuxIdMapping {
+                    container_id: 65537,
+                    host_id: 300000,
+                    size: 10
+                },
+            ]
+        );
+        assert!(validate_id_mappings(&mappings).is_ok());
+        assert_eq!(subid_mappings(subuid, "carol", 1002).unwrap().len(), 1);
+        assert!(subid_mappings("alice:0:4294967295", "alice", 1000).is_err());
+        assert!(subid_mappings("alice", "bob", 1000).is_err());
+    }
+
+    #[test]
+    fn linux_cpu_validation() {
+        assert!(LinuxCpuBuilder::default()
+            .quota(200_000i64)
+            .period(100_000u64)
+            .burst(50_000u64)
+            .realtime_runtime(950_000i64)
+            .realtime_period(1_000_000u64)
+            .idle(1i64)
+            .cpus("0-3,8")
+            .mems("")
+            .build()
+            .is_ok());
+        assert!(LinuxCpuBuilder::default().quota(-1i64).build().is_ok());
+
+        assert!(LinuxCpuBuilder::default().period(100u64).build().is_err());
+        assert!(LinuxCpuBuilder::default().quota(999i64).build().is_err());
+        assert!(LinuxCpuBuilder::default()
+            .quota(10_000i64)
+            .burst(20_000u64)
+            .build()
+            .is_err());
+        assert!(LinuxCpuBuilder::default()
+            .realtime_runtime(2i64)
+            .realtime_period(1u64)
+            .build()
+            .is_err());
+        assert!(LinuxCpuBuilder::default().idle(2i64).build().is_err());
+        for list in ["0-", "3-1", "0,,1", "a"] {
+            assert!(
+                LinuxCpuBuilder::default().cpus(list).build().is_err(),
+                "{list}"
+            );
+        }
+    }
+
+    #[test]
+    fn normalize_paths() {
+        let mut linux = LinuxBuilder::default()
+            .masked_paths(vec![
+                PathBuf::from("/proc/kcore"),
+                PathBuf::from("/proc//kcore/"),
+                PathBuf::from("/proc/./acpi/../keys"),
+            ])
+            .readonly_paths(vec![PathBuf::from("/proc/sys/.")])
+            .build()
+            .unwrap();
+        linux.normalize_paths();
+        assert_eq!(
+            linux.masked_paths_ref().unwrap(),
+            [PathBuf::from("/proc/kcore"), PathBuf::from("/proc/keys")]
+        );
+        assert_eq!(
+            linux.readonly_paths_ref().unwrap(),
+            [PathBuf::from("/proc/sys")]
+        );
+        assert_eq!(
+            serde_json::to_value(&linux).unwrap()["readonlyPaths"],
+            serde_json::json!(["/proc/sys"])
+        );
+    }
+
+    #[test]
+    fn personality_flags() {
+        let personality: LinuxPersonality =
+            serde_json::from_str(r#"{"domain":"LINUX","flags":["ADDR_NO_RANDOMIZE","NEW_FLAG"]}"#)
+                .unwrap();
+        assert_eq!(
+            personality.flags_ref().unwrap(),
+            [
+                LinuxPersonalityFlag::AddrNoRandomize,
+                LinuxPersonalityFlag::Other("NEW_FLAG".to_owned())
+            ]
+        );
+        assert_eq!(
+            LinuxPersonalityFlag::AddrNoRandomize.bits(),
+            Some(0x0040000)
+        );
+        assert_eq!(
+            serde_json::to_string(&personality).unwrap(),
+            r#"{"domain":"LINUX","flags":["ADDR_NO_RANDOMIZE","NEW_FLAG"]}"#
+        );
+    }
+
+    #[test]
+    fn errno_conversion() {
+        assert_eq!(u32::from(Errno::Eperm), 1);
+        assert_eq!(u32::from(Errno::Enosys), 38);
+        assert_eq!(Errno::try_from(13).unwrap(), Errno::Eacces);
+        assert!(Errno::try_from(0).is_err());
+        assert_eq!(Errno::E2big.to_string(), "E2BIG");
+        assert_eq!("ENOSYS".parse::<Errno>().unwrap(), Errno::Enosys);
+    }
+
+    #[test]
+    fn linux_builder_validation() {
+        let namespace = |typ| LinuxNamespaceBuilder::default().typ(typ).build().unwrap();
+        assert!(LinuxBuilder::default()
+            .namespaces(vec![
+                namespace(LinuxNamespaceType::Pid),
+                namespace(LinuxNamespaceType::Pid),
+            ])
+            .build()
+            .is_err());
+        assert!(LinuxBuilder::default()
+            .uid_mappings(vec![LinuxIdMapping::default()])
+            .build()
+            .is_err());
+
+        let seccomp = |action| {
+            LinuxSeccompBuilder::default()
+                .default_action(action)
+                .default_errno_ret(Errno::Eperm)
+                .build()
+        };
+        assert!(seccomp(LinuxSeccompAction::ScmpActErrno).is_ok());
+        assert!(seccomp(LinuxSeccompAction::ScmpActAllow).is_err());
+        assert!(LinuxSeccompBuilder::default()
+            .default_action(LinuxSeccompAction::ScmpActNotify)
+            .build()
+            .is_err());
+
+        let listener = |path: &str, metadata: usize| {
+            LinuxSeccompBuilder::default()
+                .listener_path(path)
+                .listener_metadata("x".repeat(metadata))
+                .build()
+        };
+        assert!(listener("/run/seccomp-agent.sock", 4096).is_ok());
+        assert!(listener("run/seccomp-agent.sock", 0).is_err());
+        assert!(listener(&format!("/{}", "x".repeat(107)), 0).is_err());
+        assert!(listener("/run/seccomp-agent.sock", 4097).is_err());
+
+        let syscall = |action| {
+            LinuxSyscallBuilder::default()
+                .names(vec!["chmod".to_owned()])
+                .action(action)
+                .errno_ret(Errno::Enosys)
+                .build()
+        };
+        assert!(syscall(LinuxSeccompAction::ScmpActTrace).is_ok());
+        assert!(syscall(LinuxSeccompAction::ScmpActKill).is_err());
+
+        let rule = LinuxSyscall::errno(["clone3"], Errno::Enosys)
+            .with_masked_arg(5, 0xff, 1)
+            .unwrap();
+        assert_eq!(rule.action(), LinuxSeccompAction::ScmpActErrno);
+        let arg = &rule.args_ref().unwrap()[0];
+        assert_eq!(arg.index(), 5);
+        assert_eq!(arg.op(), LinuxSeccompOperator::ScmpCmpMaskedEq);
+        assert_eq!((arg.value(), arg.value_two()), (0xff, Some(1)));
+        assert!(LinuxSyscall::allow(["chmod"])
+            .with_arg(0, LinuxSeccompOperator::ScmpCmpMaskedEq, 1)
+            .is_err());
+        assert!(LinuxSyscall::allow(["chmod"])
+            .with_arg(6, LinuxSeccompOperator::ScmpCmpEq, 0)
+            .is_err());
+    }
 }
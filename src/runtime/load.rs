@@ -0,0 +1,157 @@
+use std::{fmt, sync::Arc};
+
+use crate::error::{oci_error, Result};
+
+use super::{OciVersion, VERSION_MAJOR};
+
+/// Policy applied by [`Spec::load_with`](super::Spec::load_with) if the
+/// `ociVersion` of a spec is outside of the supported window or is not valid
+/// SemVer.
+#[derive(Clone, Default)]
+pub enum UnknownVersionPolicy {
+    /// Fail loading the spec.
+    #[default]
+    Reject,
+    /// Load the spec and report the problem to the provided callback.
+    Warn(Arc<dyn Fn(&str) + Send + Sync>),
+    /// Load the spec without any further action.
+    Accept,
+}
+
+impl UnknownVersionPolicy {
+    /// Create a [`UnknownVersionPolicy::Warn`] reporting to `warn`, which may
+    /// capture a logger or any other context.
+    pub fn warn(warn: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        Self::Warn(Arc::new(warn))
+    }
+}
+
+impl fmt::Debug for UnknownVersionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reject => f.write_str("Reject"),
+            Self::Warn(_) => f.write_str("Warn(..)"),
+            Self::Accept => f.write_str("Accept"),
+        }
+    }
+}
+
+/// Options for loading a [`Spec`](super::Spec) via
+/// [`Spec::load_with`](super::Spec::load_with). Start from
+/// [`LoadOptions::default`] and adjust the fields as needed.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct LoadOptions {
+    /// The lowest supported `ociVersion` (inclusive). No lower bound is
+    /// applied if unset.
+    pub min_version: Option<OciVersion>,
+    /// The first unsupported `ociVersion` (exclusive). No upper bound is
+    /// applied if unset.
+    pub max_version: Option<OciVersion>,
+    /// What to do if the `ociVersion` is outside of the supported window or
+    /// is not valid SemVer.
+    /// Pre-release and build metadata of the `ociVersion` are ignored when
+    /// checking the window.
+    pub on_unknown_version: UnknownVersionPolicy,
+}
+
+/// Accepts any 1.x version of the runtime spec and rejects all others.
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            min_version: Some(OciVersion::new(VERSION_MAJOR as u64, 0, 0)),
+            max_version: Some(OciVersion::new(VERSION_MAJOR as u64 + 1, 0, 0)),
+            on_unknown_version: UnknownVersionPolicy::Reject,
+        }
+    }
+}
+
+/// Options for saving a [`Spec`](super::Spec) via
+/// [`Spec::save_with`](super::Spec::save_with).
+#[cfg(feature = "filesystem")]
+#[derive(Clone, Debug, Default)]
+pub struct SaveOptions {
+    /// Hold an exclusive advisory lock on `<path>.lock` while writing, so
+    /// that concurrent writers cooperating on the same lock file are
+    /// serialized.
+    pub lock: bool,
+}
+
+impl LoadOptions {
+    pub(super) fn check_version(&self, version: &str) -> Result<()> {
+        let Err(message) = self.version_in_window(version) else {
+            return Ok(());
+        };
+        match &self.on_unknown_version {
+            UnknownVersionPolicy::Reject => Err(oci_error(message)),
+            UnknownVersionPolicy::Warn(warn) => {
+                warn(&message);
+                Ok(())
+            }
+            UnknownVersionPolicy::Accept => Ok(()),
+        }
+    }
+
+    fn version_in_window(&self, version: &str) -> std::result::Result<(), String> {
+        let version: OciVersion = version
+            .parse()
+            .map_err(|_| format!("unsupported ociVersion {version:?}, expected SemVer"))?;
+        let release = OciVersion::new(version.major(), version.minor(), version.patch());
+
+        if let Some(min) = self.min_version.as_ref().filter(|min| &release < min) {
+            return Err(format!(
+                "unsupported ociVersion {version}, expected >= {min}"
+            ));
+        }
+        if let Some(max) = self.max_version.as_ref().filter(|max| &release >= max) {
+            return Err(format!(
+                "unsupported ociVersion {version}, expected < {max}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(options: &LoadOptions, version: &str) -> Result<()> {
+        options.check_version(version)
+    }
+
+    #[test]
+    fn version_window() {
+        let options = LoadOptions::default();
+        assert!(check(&options, "1.0.0").is_ok());
+        assert!(check(&options, "1.2.0-rc.1").is_ok());
+        assert!(check(&options, "0.9.0").is_err());
+        assert!(check(&options, "2.0.0").is_err());
+        assert!(check(&options, "1.0").is_err());
+        assert!(check(&options, "").is_err());
+    }
+
+    #[test]
+    fn version_policy() {
+        let options = LoadOptions {
+            on_unknown_version: UnknownVersionPolicy::Accept,
+            ..Default::default()
+        };
+        assert!(check(&options, "2.0.0").is_ok());
+        assert!(check(&options, "1.0").is_ok());
+
+        let warnings = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&warnings);
+        let options = LoadOptions {
+            on_unknown_version: UnknownVersionPolicy::warn(move |message| {
+                sink.lock().unwrap().push(message.to_owned())
+            }),
+            ..Default::default()
+        };
+        assert!(check(&options, "2.0.0").is_ok());
+        assert_eq!(
+            *warnings.lock().unwrap(),
+            ["unsupported ociVersion 2.0.0, expected < 2.0.0"]
+        );
+    }
+}
@@ -2,11 +2,17 @@ use crate::error::OciSpecError;
 use derive_builder::Builder;
 use getset::{CopyGetters, Getters, MutGetters, Setters};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "proptests")]
+use crate::some_none_generator_util;
+#[cfg(feature = "proptests")]
+use quickcheck::{Arbitrary, Gen};
 
 #[derive(
     Builder, Clone, CopyGetters, Debug, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -52,6 +58,7 @@ impl Default for Root {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -78,6 +85,14 @@ pub struct Mount {
     options: Option<Vec<String>>,
 }
 
+option_getters! {
+    Mount {
+        typ_ref => typ: str,
+        source_ref => source: Path,
+        options_ref => options: [String],
+    }
+}
+
 /// utility function to generate default config for mounts.
 pub fn get_default_mounts() -> Vec<Mount> {
     vec![
@@ -161,31 +176,49 @@ pub fn get_default_mounts() -> Vec<Mount> {
 }
 
 /// utility function to generate default rootless config for mounts.
-// TODO(saschagrunert): remove once clippy does not report this false positive any more. We cannot
-// use `inspect` instead of `map` because we need to mutate the mounts.
-// Ref: https://github.com/rust-lang/rust-clippy/issues/13185
-#[allow(clippy::manual_inspect)]
 pub fn get_rootless_mounts() -> Vec<Mount> {
     let mut mounts = get_default_mounts();
+    mounts.iter_mut().for_each(make_mount_rootless);
     mounts
-        .iter_mut()
-        .find(|m| m.destination.to_string_lossy() == "/dev/pts")
-        .map(|m| {
-            if let Some(opts) = &mut m.options {
-                opts.retain(|o| o != "gid=5")
-            }
-            m
-        });
-    mounts
-        .iter_mut()
-        .find(|m| m.destination.to_string_lossy() == "/sys")
-        .map(|m| {
-            m.typ = Some("none".to_string());
-            m.source = Some("/sys".into());
-            if let Some(o) = m.options.as_mut() {
-                o.push("rbind".to_string())
+}
+
+/// Adapts `mount` to be usable in a user namespace without privileges: the
+/// `gid=5` option is dropped from `/dev/pts`, and `/sys` is bind mounted from
+/// the host instead of mounting a new sysfs.
+pub(super) fn make_mount_rootless(mount: &mut Mount) {
+    if mount.destination == Path::new("/dev/pts") {
+        if let Some(options) = &mut mount.options {
+            options.retain(|o| o != "gid=5");
+        }
+    } else if mount.destination == Path::new("/sys") {
+        mount.typ = Some("none".to_string());
+        mount.source = Some("/sys".into());
+        if let Some(options) = &mut mount.options {
+            if !options.iter().any(|o| o == "rbind") {
+                options.push("rbind".to_string());
             }
-            m
-        });
-    mounts
+        }
+    }
+}
+
+#[cfg(feature = "proptests")]
+impl Arbitrary for Root {
+    fn arbitrary(g: &mut Gen) -> Root {
+        Root {
+            path: PathBuf::arbitrary(g),
+            readonly: some_none_generator_util(g),
+        }
+    }
+}
+
+#[cfg(feature = "proptests")]
+impl Arbitrary for Mount {
+    fn arbitrary(g: &mut Gen) -> Mount {
+        Mount {
+            destination: PathBuf::arbitrary(g),
+            typ: some_none_generator_util(g),
+            source: some_none_generator_util(g),
+            options: some_none_generator_util(g),
+        }
+    }
 }
@@ -5,47 +5,79 @@ use getset::{Getters, MutGetters, Setters};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
+    io::{Read, Write},
+};
+#[cfg(feature = "filesystem")]
+use std::{
     fs,
-    io::{BufReader, BufWriter, Write},
     path::{Path, PathBuf},
 };
 
 use crate::error::{oci_error, OciSpecError, Result};
+use extensions::deserialize_extension_platforms;
 
+#[cfg(feature = "filesystem")]
+mod bundle;
 mod capability;
+mod cdi;
+mod cgroups;
+mod extensions;
 mod features;
+#[cfg(feature = "extensions")]
+mod freebsd;
 mod hooks;
 mod linux;
+mod load;
 mod miscellaneous;
 mod process;
 mod solaris;
+mod state;
+pub mod syscalls;
+pub mod sysctl;
 mod test;
+mod transform;
 mod version;
 mod vm;
+mod wasm;
 mod windows;
+mod zos;
 
 // re-export for ease of use
+#[cfg(feature = "filesystem")]
+pub use bundle::*;
 pub use capability::*;
+pub use cdi::*;
+pub use cgroups::*;
+pub use extensions::*;
 pub use features::*;
+#[cfg(feature = "extensions")]
+pub use freebsd::*;
 pub use hooks::*;
 pub use linux::*;
+pub use load::*;
 pub use miscellaneous::*;
 pub use process::*;
 pub use solaris::*;
+pub use state::*;
+pub use transform::*;
 pub use version::*;
 pub use vm::*;
+pub use wasm::*;
 pub use windows::*;
+pub use zos::*;
 
 /// Base configuration for the container.
 #[derive(
     Builder, Clone, Debug, Deserialize, Getters, MutGetters, Setters, PartialEq, Eq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
     pattern = "owned",
+    derive(Clone),
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 #[getset(get_mut = "pub", get = "pub", set = "pub")]
 pub struct Spec {
@@ -60,6 +92,8 @@ pub struct Spec {
     /// runtimes that support any 1.1  or later release of this
     /// specification, but is not compatible with a runtime that supports
     ///  1.0 and not 1.1.
+    /// The version is kept as is when loading a spec, use
+    /// [Spec::oci_version] to parse it.
     version: String,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -93,6 +127,8 @@ pub struct Spec {
     /// [namespace
     /// configuration](https://github.com/opencontainers/runtime-spec/blob/master/config-linux.md#namespaces),
     /// the container UTS namespace may be the runtime UTS namespace.
+    /// [SpecBuilder] rejects host names which are not valid according to
+    /// RFC 1123, e.g. `my_host` or an empty one.
     hostname: Option<String>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -146,6 +182,10 @@ pub struct Spec {
     /// VM specifies configuration for Virtual Machine based containers.
     vm: Option<VM>,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// ZOS is platform-specific configuration for z/OS based containers.
+    zos: Option<ZOS>,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// UID mappings used for changing file owners w/o calling chown, fs should support it.
     /// Every mount point could have its own mapping.
@@ -155,6 +195,65 @@ pub struct Spec {
     /// GID mappings used for changing file owners w/o calling chown, fs should support it.
     /// Every mount point could have its own mapping.
     gid_mappings: Option<Vec<LinuxIdMapping>>,
+
+    #[cfg(feature = "extensions")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// FreeBSD is platform-specific configuration for FreeBSD based
+    /// containers. It is not part of the runtime spec yet.
+    freebsd: Option<FreeBSD>,
+
+    #[serde(flatten, deserialize_with = "deserialize_extension_platforms")]
+    /// Sections of platforms which are not part of the runtime spec yet,
+    /// keyed by their name and kept as raw JSON, so they survive a
+    /// round-trip. Only registered or `x-` prefixed names are kept, see
+    /// [`Spec::extension_platform`].
+    extension_platforms: HashMap<String, serde_json::Value>,
+}
+
+option_getters! {
+    Spec {
+        root_ref => root: Root,
+        mounts_ref => mounts: [Mount],
+        process_ref => process: Process,
+        hostname_ref => hostname: str,
+        domainname_ref => domainname: str,
+        hooks_ref => hooks: Hooks,
+        annotations_ref => annotations: HashMap<String, String>,
+        linux_ref => linux: Linux,
+        solaris_ref => solaris: Solaris,
+        windows_ref => windows: Windows,
+        vm_ref => vm: VM,
+        zos_ref => zos: ZOS,
+        uid_mappings_ref => uid_mappings: [LinuxIdMapping],
+        gid_mappings_ref => gid_mappings: [LinuxIdMapping],
+        #[cfg(feature = "extensions")]
+        freebsd_ref => freebsd: FreeBSD,
+    }
+}
+
+mutable_builder! {
+    /// Non-consuming builder for [`Spec`], which allows setting fields
+    /// conditionally without reassigning the builder.
+    SpecBuilderMut(SpecBuilder) -> Spec {
+        version: String,
+        root: Root,
+        mounts: Vec<Mount>,
+        process: Process,
+        hostname: String,
+        domainname: String,
+        hooks: Hooks,
+        annotations: HashMap<String, String>,
+        linux: Linux,
+        solaris: Solaris,
+        windows: Windows,
+        vm: VM,
+        zos: ZOS,
+        uid_mappings: Vec<LinuxIdMapping>,
+        gid_mappings: Vec<LinuxIdMapping>,
+        #[cfg(feature = "extensions")]
+        freebsd: FreeBSD,
+        extension_platforms: HashMap<String, serde_json::Value>,
+    }
 }
 
 // This gives a basic boilerplate for Spec that can be used calling
@@ -165,7 +264,7 @@ impl Default for Spec {
     fn default() -> Self {
         Spec {
             // Defaults to most current oci version
-            version: String::from("1.0.2-dev"),
+            version: version(),
             process: Some(Default::default()),
             root: Some(Default::default()),
             hostname: "youki".to_string().into(),
@@ -178,32 +277,183 @@ impl Default for Spec {
             solaris: None,
             windows: None,
             vm: None,
+            zos: None,
             uid_mappings: None,
             gid_mappings: None,
+            #[cfg(feature = "extensions")]
+            freebsd: None,
+            extension_platforms: HashMap::new(),
+        }
+    }
+}
+
+impl SpecBuilder {
+    fn validate(&self) -> Result<()> {
+        if let Some(Some(hostname)) = &self.hostname {
+            if !is_valid_hostname(hostname) {
+                return Err(oci_error(format!("invalid hostname {hostname}")));
+            }
         }
+
+        // Unset fields are taken from Spec::default, which has a root and a
+        // linux section.
+        let linux = self.linux.as_ref().is_none_or(Option::is_some);
+        let missing_root = self.root.as_ref().is_some_and(|root| {
+            root.as_ref()
+                .is_none_or(|root| root.path().as_os_str().is_empty())
+        });
+        if linux && missing_root {
+            return Err(oci_error("root is required on Linux"));
+        }
+
+        Ok(())
     }
 }
 
+/// Returns true if `hostname` is a valid host name according to RFC 1123.
+fn is_valid_hostname(hostname: &str) -> bool {
+    hostname.len() <= 253
+        && hostname.split('.').all(|label| {
+            (1..=63).contains(&label.len())
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+        })
+}
+
 impl Spec {
     /// Load a new `Spec` from the provided JSON file `path`.
     /// # Errors
     /// This function will return an [OciSpecError::Io] if the spec does not exist or an
-    /// [OciSpecError::SerDe] if it is invalid.
+    /// [OciSpecError::SerDe] if it is invalid, wrapped in an [OciSpecError::Context]
+    /// holding `path`.
     /// # Example
     /// ``` no_run
     /// use oci_spec::runtime::Spec;
     ///
     /// let spec = Spec::load("config.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let path = path.as_ref();
-        let file = fs::File::open(path)?;
-        let reader = BufReader::new(file);
-        let s = serde_json::from_reader(reader)?;
-        Ok(s)
+        crate::from_file(path)
+    }
+
+    /// Load a new `Spec` from the provided JSON file `path`, rejecting any
+    /// field which is not defined by the runtime specification.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io] if the spec does not exist or an
+    /// [OciSpecError::SerDe] if it is invalid, contains unknown fields or duplicate keys,
+    /// wrapped in an [OciSpecError::Context] holding `path`.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::runtime::Spec;
+    ///
+    /// let spec = Spec::load_strict("config.json").unwrap();
+    /// ```
+    #[cfg(feature = "filesystem")]
+    pub fn load_strict<P: AsRef<Path>>(path: P) -> Result<Self> {
+        crate::from_file_strict(path)
+    }
+
+    /// Load a new `Spec` from the provided JSON file `path` and check its
+    /// `ociVersion` against the supported window of `options`.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io] if the spec does not exist, an
+    /// [OciSpecError::SerDe] if it is invalid or an [OciSpecError::Other] if its version
+    /// is rejected by `options`.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::runtime::{LoadOptions, OciVersion, Spec, UnknownVersionPolicy};
+    ///
+    /// let mut options = LoadOptions::default();
+    /// options.min_version = Some(OciVersion::new(1, 1, 0));
+    /// options.on_unknown_version = UnknownVersionPolicy::warn(|msg| eprintln!("{msg}"));
+    /// let spec = Spec::load_with("config.json", &options).unwrap();
+    /// ```
+    #[cfg(feature = "filesystem")]
+    pub fn load_with<P: AsRef<Path>>(path: P, options: &LoadOptions) -> Result<Self> {
+        let spec = Self::load(path)?;
+        options.check_version(&spec.version)?;
+        Ok(spec)
+    }
+
+    /// Load a new `Spec` from the JSON document read from `reader`.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io] if the reader cannot be read or an
+    /// [OciSpecError::SerDe] if the spec is invalid.
+    /// # Example
+    /// ```
+    /// use oci_spec::runtime::Spec;
+    ///
+    /// let spec = Spec::from_reader(r#"{"ociVersion":"1.2.0"}"#.as_bytes()).unwrap();
+    /// assert_eq!(spec.version().to_string(), "1.2.0");
+    /// ```
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        crate::from_reader(reader)
+    }
+
+    /// Load a new `Spec` from the JSON document read from `reader`, rejecting any field
+    /// which is not defined by the runtime specification.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io] if the reader cannot be read or an
+    /// [OciSpecError::SerDe] if the spec is invalid, contains unknown fields or duplicate
+    /// keys.
+    pub fn from_reader_strict<R: Read>(reader: R) -> Result<Self> {
+        crate::from_reader_strict(reader)
+    }
+
+    /// Load a new `Spec` from the JSON document read from `reader` and check its
+    /// `ociVersion` against the supported window of `options`.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io] if the reader cannot be read, an
+    /// [OciSpecError::SerDe] if the spec is invalid or an [OciSpecError::Other] if its
+    /// version is rejected by `options`.
+    pub fn from_reader_with<R: Read>(reader: R, options: &LoadOptions) -> Result<Self> {
+        let spec = Self::from_reader(reader)?;
+        options.check_version(&spec.version)?;
+        Ok(spec)
+    }
+
+    /// Load a new `Spec` from the `config.json` at the root of the tar archive
+    /// read from `reader`, e.g. a bundle packed with `tar -C bundle -c .`.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io] if the archive cannot be read, an
+    /// [OciSpecError::SerDe] if the spec is invalid or an [OciSpecError::Other] if the
+    /// archive contains no `config.json`.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::runtime::Spec;
+    ///
+    /// let archive = std::fs::File::open("bundle.tar").unwrap();
+    /// let spec = Spec::from_tar(archive).unwrap();
+    /// ```
+    #[cfg(feature = "tar")]
+    pub fn from_tar<R: Read>(reader: R) -> Result<Self> {
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let path = entry.path()?;
+            if path.strip_prefix(".").unwrap_or(&path) == Path::new(CONFIG_FILE) {
+                return Self::from_reader(entry);
+            }
+        }
+        Err(oci_error(format!("no {CONFIG_FILE} found in archive")))
+    }
+
+    /// Write a `Spec` as JSON to `writer`.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io] if the writer cannot be written to
+    /// or an [OciSpecError::SerDe] if the spec cannot be serialized.
+    pub fn to_writer<W: Write>(&self, writer: &mut W) -> Result<()> {
+        crate::to_writer(self, writer, false)
     }
 
-    /// Save a `Spec` to the provided JSON file `path`.
+    /// Save a `Spec` to the provided JSON file `path`. The spec is written to
+    /// a temporary file which then replaces `path`, so a crash never leaves a
+    /// partially written file behind. If `path` is a symlink, its target is
+    /// replaced, and the permissions of an existing file are kept.
     /// # Errors
     /// This function will return an [OciSpecError::Io] if a file cannot be created at the provided
     /// path or an [OciSpecError::SerDe] if the spec cannot be serialized.
@@ -214,16 +464,117 @@ impl Spec {
     /// let mut spec = Spec::load("config.json").unwrap();
     /// spec.save("my_config.json").unwrap();
     /// ```
+    #[cfg(feature = "filesystem")]
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let path = path.as_ref();
-        let file = fs::File::create(path)?;
-        let mut writer = BufWriter::new(file);
-        serde_json::to_writer(&mut writer, self)?;
-        writer.flush()?;
-        Ok(())
+        self.save_with(path, &SaveOptions::default())
+    }
+
+    /// Save a `Spec` to the provided JSON file `path` like [Spec::save],
+    /// applying `options`.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io] if a file cannot be created at the provided
+    /// path or the lock cannot be acquired or an [OciSpecError::SerDe] if the spec cannot be
+    /// serialized.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::runtime::{SaveOptions, Spec};
+    ///
+    /// let spec = Spec::default();
+    /// spec.save_with("config.json", &SaveOptions { lock: true }).unwrap();
+    /// ```
+    #[cfg(feature = "filesystem")]
+    pub fn save_with<P: AsRef<Path>>(&self, path: P, options: &SaveOptions) -> Result<()> {
+        crate::to_file_atomic(self, path, false, options.lock)
+    }
+
+    /// Load a new `Spec` from the provided JSON file `path` asynchronously.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io] if the spec does not exist or an
+    /// [OciSpecError::SerDe] if it is invalid.
+    /// # Example
+    /// ``` no_run
+    /// use oci_spec::runtime::Spec;
+    ///
+    /// # async fn example() {
+    /// let spec = Spec::load_async("config.json").await.unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn load_async<P: AsRef<Path>>(path: P) -> Result<Self> {
+        crate::from_file_async(path).await
+    }
+
+    /// Save a `Spec` to the provided JSON file `path` asynchronously. The file
+    /// is replaced atomically like by [Spec::save].
+    /// # Errors
+    /// This function will return an [OciSpecError::Io] if a file cannot be created at the provided
+    /// path or an [OciSpecError::SerDe] if the spec cannot be serialized.
+    #[cfg(feature = "async")]
+    pub async fn save_async<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        crate::to_file_atomic_async(self, path, false, false).await
+    }
+
+    /// Returns a stable SHA-256 digest of the content of the `Spec`, which can
+    /// be used to deduplicate identical container configurations, as cache key
+    /// for data derived from them or to detect configuration changes without
+    /// diffing the JSON text.
+    ///
+    /// The digest is computed over a normalized JSON serialization: object
+    /// keys and capability sets are sorted and fields holding `null` or empty
+    /// objects and arrays are dropped, so it does not depend on iteration
+    /// order or on whether an optional field is omitted or empty.
+    /// # Example
+    /// ```
+    /// use oci_spec::runtime::Spec;
+    ///
+    /// let spec = Spec::default();
+    /// assert_eq!(spec.content_digest().unwrap(), spec.clone().content_digest().unwrap());
+    /// ```
+    pub fn content_digest(&self) -> Result<crate::Digest> {
+        self.content_digest_without(&[])
+    }
+
+    /// Returns the sha256 digest of the canonical serialization of the `Spec`,
+    /// see [Spec::content_digest]. Engines can compare it to the digest of a
+    /// previously applied spec to detect configuration changes, e.g. to
+    /// restart a container whose config changed.
+    /// # Example
+    /// ```
+    /// use oci_spec::runtime::Spec;
+    ///
+    /// let spec = Spec::default();
+    /// let mut changed = spec.clone();
+    /// changed.set_hostname(Some("changed".to_string()));
+    /// assert_ne!(spec.digest().unwrap(), changed.digest().unwrap());
+    /// ```
+    pub fn digest(&self) -> Result<crate::Digest> {
+        self.content_digest()
+    }
+
+    /// Like [Spec::content_digest], but the values at the given JSON pointers,
+    /// e.g. `/annotations/io.kubernetes.cri.sandbox-id`, are ignored. This
+    /// allows stripping fields which differ between otherwise identical
+    /// containers.
+    pub fn content_digest_without(&self, pointers: &[&str]) -> Result<crate::Digest> {
+        use sha2::{Digest, Sha256};
+
+        // Capabilities are sets, so their serialized order is arbitrary.
+        let sets = [
+            "bounding",
+            "effective",
+            "inheritable",
+            "permitted",
+            "ambient",
+        ]
+        .map(|set| format!("/process/capabilities/{set}"));
+        let sets: Vec<_> = sets.iter().map(String::as_str).collect();
+        let content = crate::to_canonical_vec(self, pointers, &sets)?;
+        let hex = format!("{:x}", Sha256::digest(content));
+        Ok(hex.parse::<crate::Sha256Digest>()?.into())
     }
 
     /// Canonicalize the `root.path` of the `Spec` for the provided `bundle`.
+    #[cfg(feature = "filesystem")]
     pub fn canonicalize_rootfs<P: AsRef<Path>>(&mut self, bundle: P) -> Result<()> {
         let root = self
             .root
@@ -255,6 +606,7 @@ impl Spec {
         }
     }
 
+    #[cfg(feature = "filesystem")]
     fn canonicalize_path<B, P>(bundle: B, path: P) -> Result<PathBuf>
     where
         B: AsRef<Path>,
@@ -269,10 +621,37 @@ impl Spec {
     }
 }
 
+#[cfg(feature = "proptests")]
+use crate::some_none_generator_util;
+#[cfg(feature = "proptests")]
+use quickcheck::{Arbitrary, Gen};
+
+#[cfg(feature = "proptests")]
+impl Arbitrary for Spec {
+    fn arbitrary(g: &mut Gen) -> Spec {
+        Spec {
+            version: String::arbitrary(g),
+            root: some_none_generator_util(g),
+            mounts: some_none_generator_util(g),
+            process: some_none_generator_util(g),
+            hostname: some_none_generator_util(g),
+            domainname: some_none_generator_util(g),
+            annotations: some_none_generator_util(g),
+            linux: g.choose(&[None, Some(Linux::default())]).unwrap().clone(),
+            uid_mappings: None,
+            gid_mappings: None,
+            ..Default::default()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
     use super::*;
 
+    #[cfg(feature = "filesystem")]
     #[test]
     fn test_canonicalize_rootfs() {
         let rootfs_name = "rootfs";
@@ -320,13 +699,82 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_content_digest() {
+        let spec = Spec::default();
+        let digest = spec.content_digest().unwrap();
+        assert_eq!(digest.algorithm(), &crate::DigestAlgorithm::Sha256);
+        assert_eq!(digest.digest().len(), 64);
+        assert_eq!(spec.digest().unwrap(), digest);
+
+        // Key and capability order as well as empty optional fields do not
+        // affect the digest.
+        let json = serde_json::to_string(&spec).unwrap();
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let object = value.as_object_mut().unwrap();
+        let root = object.shift_remove("root").unwrap();
+        object.insert("root".to_string(), root);
+        object.insert("hooks".to_string(), serde_json::json!({}));
+        let reordered: Spec = serde_json::from_value(value).unwrap();
+        assert_eq!(reordered.content_digest().unwrap(), digest);
+
+        let mut other = spec.clone();
+        other.set_hostname(Some("other".to_string()));
+        assert_ne!(other.content_digest().unwrap(), digest);
+
+        let mut annotated = spec.clone();
+        annotated.set_annotations(Some(
+            [(
+                "io.kubernetes.cri.sandbox-id".to_string(),
+                "abc".to_string(),
+            )]
+            .into(),
+        ));
+        assert_ne!(annotated.content_digest().unwrap(), digest);
+        assert_eq!(
+            annotated
+                .content_digest_without(&["/annotations/io.kubernetes.cri.sandbox-id"])
+                .unwrap(),
+            digest
+        );
+    }
+
+    #[test]
+    fn test_spec_builder_mut() {
+        let gpu = true;
+        let mut builder = SpecBuilderMut::default();
+        builder
+            .version(OciVersion::new(1, 0, 2).to_string())
+            .hostname("container");
+        if gpu {
+            let mut linux = LinuxBuilderMut::default();
+            linux.devices(vec![LinuxDevice::default()]);
+            builder.linux(linux.build().expect("build linux"));
+        }
+
+        let spec = builder.build().expect("build spec");
+        assert_eq!(spec.version().to_string(), "1.0.2");
+        assert_eq!(spec.hostname().as_deref(), Some("container"));
+        assert_eq!(
+            spec.linux()
+                .as_ref()
+                .and_then(|l| l.devices().as_ref())
+                .map(Vec::len),
+            Some(1)
+        );
+
+        // The builder is not consumed and can be built again.
+        assert_eq!(builder.build().expect("build spec again"), spec);
+    }
+
+    #[cfg(feature = "filesystem")]
     #[test]
     fn test_load_save() {
         let spec = Spec {
             ..Default::default()
         };
         let test_dir = tempfile::tempdir().expect("failed to create tmp test dir");
-        let spec_path = test_dir.into_path().join("config.json");
+        let spec_path = test_dir.path().join("config.json");
 
         // Test first save the default config, and then load the saved config.
         // The before and after should be the same.
@@ -338,6 +786,199 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "filesystem")]
+    #[test]
+    fn test_save_atomic() {
+        let test_dir = tempfile::tempdir().expect("failed to create tmp test dir");
+        let spec_path = test_dir.path().join("config.json");
+        fs::write(&spec_path, "x".repeat(4096)).unwrap();
+
+        let mut spec = Spec::default();
+        spec.set_hostname(Some("atomic".to_string()));
+        spec.save_with(&spec_path, &SaveOptions { lock: true })
+            .expect("failed to save spec");
+        assert_eq!(Spec::load(&spec_path).unwrap(), spec);
+
+        let mut files: Vec<_> = fs::read_dir(test_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        files.sort();
+        assert_eq!(files, ["config.json", "config.json.lock"]);
+
+        assert!(spec
+            .save(test_dir.path().join("missing/config.json"))
+            .is_err());
+        assert!(spec.save("/").is_err());
+    }
+
+    #[cfg(all(feature = "filesystem", unix))]
+    #[test]
+    fn test_save_atomic_keeps_mode_and_symlink() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let test_dir = tempfile::tempdir().expect("failed to create tmp test dir");
+        let target = test_dir.path().join("config.json");
+        let link = test_dir.path().join("link.json");
+        Spec::default().save(&target).unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o600)).unwrap();
+        symlink("config.json", &link).unwrap();
+
+        let mut spec = Spec::default();
+        spec.set_hostname(Some("through-link".to_string()));
+        spec.save(&link).expect("failed to save spec");
+
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(Spec::load(&target).unwrap(), spec);
+        let mode = fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[cfg(feature = "filesystem")]
+    #[test]
+    fn test_load_strict() {
+        let test_dir = tempfile::tempdir().expect("failed to create tmp test dir");
+        let spec_path = test_dir.path().join("config.json");
+
+        Spec::default()
+            .save(&spec_path)
+            .expect("failed to save spec");
+        let spec = Spec::load_strict(&spec_path).expect("failed to load the saved spec");
+        assert_eq!(spec, Spec::default());
+
+        fs::write(
+            &spec_path,
+            "{\"ociVersion\": \"1.0.2\",\n \"process\": {\"cwd\": \"/\", \"user\": {\"uid\": 0, \"gid\": 0}, \"unknown\": null}}",
+        )
+        .expect("failed to write spec");
+        assert!(Spec::load(&spec_path).is_ok());
+        let err = Spec::load_strict(&spec_path).expect_err("unknown field should be rejected");
+        assert_eq!(err.get_context(), Some(spec_path.to_str().unwrap()));
+        let OciSpecError::SerDe(err) = err.root() else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!((err.line(), err.column()), (2, 64));
+        assert_eq!(
+            err.to_string(),
+            "unknown field `process.unknown` at line 2 column 64"
+        );
+
+        fs::write(&spec_path, r#"{"ociVersion": "1.0.2", "hostnme": "typo"}"#)
+            .expect("failed to write spec");
+        assert!(Spec::load(&spec_path).is_ok());
+        assert!(Spec::load_strict(&spec_path).is_err());
+
+        fs::write(
+            &spec_path,
+            r#"{"ociVersion": "1.0.2", "linux": {"sysctl": {"net.ipv4.ip_forward": "0", "net.ipv4.ip_forward": "1"}}}"#,
+        )
+        .expect("failed to write spec");
+        assert!(Spec::load(&spec_path).is_ok());
+        let err = Spec::load_strict(&spec_path).expect_err("duplicate key should be rejected");
+        assert!(
+            err.root()
+                .to_string()
+                .starts_with("serde failed: duplicate key `linux.sysctl.net.ipv4.ip_forward`"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[cfg(feature = "filesystem")]
+    #[test]
+    fn test_load_with() {
+        let test_dir = tempfile::tempdir().expect("failed to create tmp test dir");
+        let spec_path = test_dir.path().join("config.json");
+
+        let spec = SpecBuilder::default()
+            .version("2.0.0")
+            .build()
+            .expect("failed to build spec");
+        spec.save(&spec_path).expect("failed to save spec");
+
+        assert!(Spec::load(&spec_path).is_ok());
+        let err = Spec::load_with(&spec_path, &LoadOptions::default())
+            .expect_err("2.x spec should be rejected");
+        assert_eq!(
+            err.to_string(),
+            "unsupported ociVersion 2.0.0, expected < 2.0.0"
+        );
+
+        let options = LoadOptions {
+            max_version: None,
+            ..Default::default()
+        };
+        let loaded = Spec::load_with(&spec_path, &options).expect("failed to load spec");
+        assert_eq!(loaded, spec);
+    }
+
+    #[test]
+    fn test_lenient_version() {
+        let spec = Spec::from_reader("{}".as_bytes()).expect("missing ociVersion");
+        assert_eq!(spec.version(), "");
+
+        let json = r#"{"ociVersion":"1.0"}"#;
+        let spec = Spec::from_reader(json.as_bytes()).expect("non-SemVer ociVersion");
+        assert_eq!(spec.version(), "1.0");
+        assert_eq!(serde_json::to_string(&spec).unwrap(), json);
+
+        let err = Spec::from_reader_with(json.as_bytes(), &LoadOptions::default())
+            .expect_err("non-SemVer ociVersion should be rejected");
+        assert_eq!(
+            err.to_string(),
+            "unsupported ociVersion \"1.0\", expected SemVer"
+        );
+        let options = LoadOptions {
+            on_unknown_version: UnknownVersionPolicy::Accept,
+            ..Default::default()
+        };
+        assert!(Spec::from_reader_with(json.as_bytes(), &options).is_ok());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_load_save_async() {
+        let spec = Spec::default();
+        let test_dir = tempfile::tempdir().expect("failed to create tmp test dir");
+        let spec_path = test_dir.path().join("config.json");
+
+        fs::write(&spec_path, "x".repeat(4096)).unwrap();
+        spec.save_async(&spec_path)
+            .await
+            .expect("failed to save spec");
+        let loaded_spec = Spec::load_async(&spec_path)
+            .await
+            .expect("failed to load the saved spec.");
+        assert_eq!(spec, loaded_spec);
+    }
+
+    #[cfg(feature = "filesystem")]
+    #[test]
+    fn test_load_error_path() {
+        let test_dir = tempfile::tempdir().expect("failed to create tmp test dir");
+        let spec_path = test_dir.path().join("config.json");
+        fs::write(
+            &spec_path,
+            r#"{"ociVersion": "1.0.2", "linux": {"resources": {"memory": {"limit": "1G"}}}}"#,
+        )
+        .expect("failed to write spec");
+
+        let err = Spec::load(&spec_path).expect_err("invalid limit should be rejected");
+        assert_eq!(err.get_context(), Some(spec_path.to_str().unwrap()));
+        let OciSpecError::SerDe(inner) = err.root() else {
+            panic!("unexpected error: {err}");
+        };
+        assert!(
+            inner
+                .to_string()
+                .starts_with("linux.resources.memory.limit: invalid type"),
+            "unexpected error: {inner}"
+        );
+        assert_eq!((inner.line(), inner.column()), (1, 72));
+    }
+
     #[test]
     fn test_rootless() {
         const UID: u32 = 1000;
@@ -400,12 +1041,11 @@ mod tests {
         assert!(
             !mounts.iter().any(|m| {
                 if m.destination().to_string_lossy() == "/dev/pts" {
-                    return m
-                        .options()
+                    m.options()
                         .clone()
                         .expect("options should not be empty")
                         .iter()
-                        .any(|o| o == "gid=5");
+                        .any(|o| o == "gid=5")
                 } else {
                     false
                 }
@@ -447,4 +1087,90 @@ mod tests {
         assert!(spec.uid_mappings == spec_rootless.uid_mappings);
         assert!(spec.gid_mappings == spec_rootless.gid_mappings);
     }
+
+    #[test]
+    fn spec_builder_validation() {
+        assert!(SpecBuilder::default().build().is_ok());
+        assert!(SpecBuilder::default()
+            .hostname("web-1.example.com")
+            .build()
+            .is_ok());
+        for hostname in ["", "-web", "web_1", "web..example", &"a".repeat(64)] {
+            assert!(
+                SpecBuilder::default().hostname(hostname).build().is_err(),
+                "{hostname}"
+            );
+        }
+
+        let empty_root = RootBuilder::default().path("").build().unwrap();
+        let err = SpecBuilder::default()
+            .root(empty_root)
+            .build()
+            .expect_err("root is required on Linux");
+        assert_eq!(err.to_string(), "root is required on Linux");
+
+        // An unset linux section is taken from Spec::default, so a spec
+        // for another platform still gets the root of Spec::default unless
+        // root is cleared as well.
+        let windows = WindowsBuilder::default()
+            .layer_folders(vec!["C:\\layers\\base".to_owned()])
+            .build()
+            .unwrap();
+        let spec = SpecBuilder::default()
+            .windows(windows.clone())
+            .build()
+            .expect("root of Spec::default");
+        assert!(spec.linux().is_some());
+        assert_eq!(
+            spec.root_ref().map(|root| root.path().as_path()),
+            Some(Path::new("rootfs"))
+        );
+        let empty_root = RootBuilder::default().path("").build().unwrap();
+        assert!(SpecBuilder::default()
+            .windows(windows)
+            .root(empty_root)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn option_getters() {
+        let mut spec = Spec::default();
+        assert_eq!(spec.hostname_ref(), Some("youki"));
+        assert_eq!(
+            spec.root_ref().map(|r| r.path().as_path()),
+            Some(Path::new("rootfs"))
+        );
+        assert_eq!(
+            spec.process_ref().and_then(|p| p.args_ref()),
+            Some(&["sh".to_owned()][..])
+        );
+        assert_eq!(
+            spec.mounts_ref().map(<[Mount]>::len),
+            spec.mounts().as_ref().map(Vec::len)
+        );
+
+        spec.set_hostname(None);
+        assert_eq!(spec.hostname_ref(), None);
+    }
+
+    #[test]
+    fn chained_setters() {
+        let mut spec = Spec::default();
+        spec.set_hostname(Some("web".to_owned()))
+            .set_domainname(Some("example.com".to_owned()))
+            .set_hooks(None);
+        assert_eq!(spec.hostname_ref(), Some("web"));
+        assert_eq!(spec.domainname_ref(), Some("example.com"));
+    }
+
+    #[cfg(feature = "proptests")]
+    #[test]
+    fn spec_round_trip() {
+        fn prop(spec: Spec) -> bool {
+            let json = serde_json::to_string(&spec).unwrap();
+            serde_json::from_str::<Spec>(&json).unwrap() == spec
+        }
+        quickcheck::quickcheck(prop as fn(Spec) -> bool);
+    }
 }
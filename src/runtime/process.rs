@@ -1,15 +1,18 @@
 use crate::{
-    error::OciSpecError,
+    error::{oci_error, OciSpecError},
     runtime::{Capabilities, Capability},
 };
 use derive_builder::Builder;
 use getset::{CopyGetters, Getters, MutGetters, Setters};
-use regex::Regex;
 use serde::{de, Deserialize, Deserializer, Serialize};
-use std::path::PathBuf;
-use std::sync::OnceLock;
+use std::path::{Path, PathBuf};
 use strum_macros::{Display as StrumDisplay, EnumString};
 
+#[cfg(feature = "proptests")]
+use crate::some_none_generator_util;
+#[cfg(feature = "proptests")]
+use quickcheck::{Arbitrary, Gen};
+
 #[derive(
     Builder,
     Clone,
@@ -23,12 +26,14 @@ use strum_macros::{Display as StrumDisplay, EnumString};
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
     pattern = "owned",
+    derive(Clone),
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 /// Process contains information to start a specific application inside the
 /// container.
@@ -117,6 +122,44 @@ pub struct Process {
     exec_cpu_affinity: Option<ExecCPUAffinity>,
 }
 
+option_getters! {
+    Process {
+        args_ref => args: [String],
+        command_line_ref => command_line: str,
+        env_ref => env: [String],
+        capabilities_ref => capabilities: LinuxCapabilities,
+        rlimits_ref => rlimits: [PosixRlimit],
+        apparmor_profile_ref => apparmor_profile: str,
+        selinux_label_ref => selinux_label: str,
+        io_priority_ref => io_priority: LinuxIOPriority,
+        scheduler_ref => scheduler: Scheduler,
+        exec_cpu_affinity_ref => exec_cpu_affinity: ExecCPUAffinity,
+    }
+}
+
+mutable_builder! {
+    /// Non-consuming builder for [`Process`], which allows setting fields
+    /// conditionally without reassigning the builder.
+    ProcessBuilderMut(ProcessBuilder) -> Process {
+        terminal: bool,
+        console_size: Box,
+        user: User,
+        args: Vec<String>,
+        command_line: String,
+        env: Vec<String>,
+        cwd: PathBuf,
+        capabilities: LinuxCapabilities,
+        rlimits: Vec<PosixRlimit>,
+        no_new_privileges: bool,
+        apparmor_profile: String,
+        oom_score_adj: i32,
+        selinux_label: String,
+        io_priority: LinuxIOPriority,
+        scheduler: Scheduler,
+        exec_cpu_affinity: ExecCPUAffinity,
+    }
+}
+
 // Default impl for processes in the container
 impl Default for Process {
     fn default() -> Self {
@@ -167,6 +210,7 @@ impl Default for Process {
 #[derive(
     Builder, Clone, Copy, CopyGetters, Debug, Default, Deserialize, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -186,12 +230,17 @@ pub struct Box {
     width: u64,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString)]
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 /// Available rlimit types (see <https://man7.org/linux/man-pages/man2/getrlimit.2.html>)
+#[non_exhaustive]
 pub enum PosixRlimitType {
     /// Limit in seconds of the amount of CPU time that the process can consume.
+    #[default]
     RlimitCpu,
 
     /// Maximum size in bytes of the files that the process creates.
@@ -246,15 +295,10 @@ pub enum PosixRlimitType {
     RlimitRttime,
 }
 
-impl Default for PosixRlimitType {
-    fn default() -> Self {
-        Self::RlimitCpu
-    }
-}
-
 #[derive(
     Builder, Clone, Copy, CopyGetters, Debug, Default, Deserialize, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -291,6 +335,7 @@ pub struct PosixRlimit {
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -327,7 +372,154 @@ pub struct User {
     username: Option<String>,
 }
 
+option_getters! {
+    User {
+        additional_gids_ref => additional_gids: [u32],
+        username_ref => username: str,
+    }
+}
+
+impl User {
+    /// Parses the `User` of an image configuration, which has one of the
+    /// forms `user`, `uid`, `user:group`, `uid:gid`, `uid:group` and
+    /// `user:gid`. An empty string stands for the root user.
+    ///
+    /// ```
+    /// use oci_spec::runtime::{IdOrName, User};
+    ///
+    /// let spec = User::from_spec_string("1000:100").unwrap();
+    /// let user = spec.to_user().unwrap();
+    /// assert_eq!((user.uid(), user.gid()), (1000, 100));
+    ///
+    /// let spec = User::from_spec_string("nginx").unwrap();
+    /// assert_eq!(spec.user, IdOrName::Name("nginx".to_string()));
+    /// assert!(spec.to_user().is_none());
+    /// ```
+    pub fn from_spec_string(user: &str) -> crate::Result<UserSpec> {
+        user.parse()
+    }
+}
+
+/// A user or group, given either by numeric ID or by name.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IdOrName {
+    /// A numeric ID.
+    Id(u32),
+    /// A name, which has to be resolved using the `/etc/passwd` or
+    /// `/etc/group` file of the container.
+    Name(String),
+}
+
+impl std::str::FromStr for IdOrName {
+    type Err = OciSpecError;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        if s.is_empty() {
+            return Err(oci_error("empty user or group"));
+        }
+        Ok(match s.parse() {
+            Ok(id) => Self::Id(id),
+            Err(_) => Self::Name(s.to_owned()),
+        })
+    }
+}
+
+impl std::fmt::Display for IdOrName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "{id}"),
+            Self::Name(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// The user and optional group of an image configuration, see
+/// [User::from_spec_string].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserSpec {
+    /// The user.
+    pub user: IdOrName,
+    /// The group. If it is not set, the primary group of the user applies.
+    pub group: Option<IdOrName>,
+}
+
+impl UserSpec {
+    /// Returns the runtime user, if both user and group are given by ID.
+    pub fn to_user(&self) -> Option<User> {
+        match (&self.user, &self.group) {
+            (IdOrName::Id(uid), Some(IdOrName::Id(gid))) => Some(User {
+                uid: *uid,
+                gid: *gid,
+                ..Default::default()
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the runtime user, resolving names with `lookup_user`, which
+    /// returns the UID and the primary GID of a user name or of a UID, and
+    /// `lookup_group`, which returns the GID of a group name. A UID unknown
+    /// to `lookup_user` has the primary GID 0.
+    ///
+    /// # Errors
+    /// Fails if a name cannot be resolved.
+    pub fn resolve<U, G>(&self, lookup_user: U, lookup_group: G) -> crate::Result<User>
+    where
+        U: Fn(&IdOrName) -> Option<(u32, u32)>,
+        G: Fn(&str) -> Option<u32>,
+    {
+        let (uid, primary_gid) = match (&self.user, lookup_user(&self.user)) {
+            (_, Some(ids)) => ids,
+            (IdOrName::Id(uid), None) => (*uid, 0),
+            (IdOrName::Name(name), None) => return Err(oci_error(format!("unknown user {name}"))),
+        };
+        let gid = match &self.group {
+            None => primary_gid,
+            Some(IdOrName::Id(gid)) => *gid,
+            Some(IdOrName::Name(name)) => {
+                lookup_group(name).ok_or_else(|| oci_error(format!("unknown group {name}")))?
+            }
+        };
+        Ok(User {
+            uid,
+            gid,
+            ..Default::default()
+        })
+    }
+}
+
+impl std::str::FromStr for UserSpec {
+    type Err = OciSpecError;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        if s.is_empty() {
+            return Ok(Self {
+                user: IdOrName::Id(0),
+                group: None,
+            });
+        }
+        let (user, group) = match s.split_once(':') {
+            Some((user, group)) => (user, Some(group.parse()?)),
+            None => (s, None),
+        };
+        Ok(Self {
+            user: user.parse()?,
+            group,
+        })
+    }
+}
+
+impl std::fmt::Display for UserSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.group {
+            Some(group) => write!(f, "{}:{group}", self.user),
+            None => write!(f, "{}", self.user),
+        }
+    }
+}
+
 #[derive(Builder, Clone, Debug, Deserialize, Getters, Setters, Eq, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -359,6 +551,16 @@ pub struct LinuxCapabilities {
     ambient: Option<Capabilities>,
 }
 
+option_getters! {
+    LinuxCapabilities {
+        bounding_ref => bounding: Capabilities,
+        effective_ref => effective: Capabilities,
+        inheritable_ref => inheritable: Capabilities,
+        permitted_ref => permitted: Capabilities,
+        ambient_ref => ambient: Capabilities,
+    }
+}
+
 // Default container's linux capabilities:
 // CAP_AUDIT_WRITE gives container ability to write to linux audit logs,
 // CAP_KILL gives container ability to kill non root processes
@@ -384,6 +586,7 @@ impl Default for LinuxCapabilities {
 #[derive(
     Builder, Clone, Copy, CopyGetters, Debug, Default, Deserialize, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -402,7 +605,10 @@ pub struct LinuxIOPriority {
     priority: i64,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString)]
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 /// IOPriorityClass represents an I/O scheduling class.
@@ -422,6 +628,7 @@ pub enum IOPriorityClass {
     /// to the cpu nice levels just more coarsely implemented. 0 is the highest
     /// BE prio level, 7 is the lowest. The mapping between cpu nice level and io
     /// nice level is determined as: io_nice = (cpu_nice + 20) / 5.
+    #[default]
     IoprioClassBe,
     /// This is the idle scheduling class, processes running at this
     /// level only get io time when no one else needs the disk. The idle class has no
@@ -429,13 +636,8 @@ pub enum IOPriorityClass {
     IoprioClassIdle,
 }
 
-impl Default for IOPriorityClass {
-    fn default() -> Self {
-        Self::IoprioClassBe
-    }
-}
-
 #[derive(Builder, Clone, Debug, Deserialize, Getters, Setters, Eq, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -476,6 +678,12 @@ pub struct Scheduler {
     period: Option<u64>,
 }
 
+option_getters! {
+    Scheduler {
+        flags_ref => flags: [LinuxSchedulerFlag],
+    }
+}
+
 /// Default scheduler is SCHED_OTHER with no priority.
 impl Default for Scheduler {
     fn default() -> Self {
@@ -492,9 +700,11 @@ impl Default for Scheduler {
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 ///  LinuxSchedulerPolicy represents different scheduling policies used with the Linux Scheduler
+#[non_exhaustive]
 pub enum LinuxSchedulerPolicy {
     /// SchedOther is the default scheduling policy
     SchedOther,
@@ -520,9 +730,11 @@ impl Default for LinuxSchedulerPolicy {
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, StrumDisplay, EnumString)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 ///  LinuxSchedulerFlag represents the flags used by the Linux Scheduler.
+#[non_exhaustive]
 pub enum LinuxSchedulerFlag {
     /// SchedFlagResetOnFork represents the reset on fork scheduling flag
     SchedResetOnFork,
@@ -550,6 +762,7 @@ impl Default for LinuxSchedulerFlag {
 #[derive(
     Builder, Clone, Debug, Default, Deserialize, Getters, Setters, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -583,6 +796,45 @@ pub struct ExecCPUAffinity {
     cpu_affinity_final: Option<String>,
 }
 
+option_getters! {
+    ExecCPUAffinity {
+        cpu_affinity_initial_ref => cpu_affinity_initial: str,
+        cpu_affinity_final_ref => cpu_affinity_final: str,
+    }
+}
+
+impl ProcessBuilder {
+    fn validate(&self) -> Result<(), OciSpecError> {
+        if let Some(cwd) = &self.cwd {
+            if !is_absolute_path(cwd) {
+                return Err(oci_error(format!(
+                    "process cwd {} is not an absolute path",
+                    cwd.display()
+                )));
+            }
+        }
+
+        if let Some(Some(args)) = &self.args {
+            if args.is_empty() {
+                return Err(oci_error("process args must not be empty"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns true if `path` is absolute on the platform of the container rather
+/// than the host: it starts with `/` or is a Windows path with a drive letter,
+/// like `C:\\`, or a UNC path, like `\\\\server\\share`.
+fn is_absolute_path(path: &Path) -> bool {
+    match path.as_os_str().as_encoded_bytes() {
+        [b'/', ..] | [b'\\', b'\\', ..] => true,
+        [drive, b':', b'\\' | b'/', ..] => drive.is_ascii_alphabetic(),
+        _ => false,
+    }
+}
+
 impl ExecCPUAffinityBuilder {
     fn validate(&self) -> Result<(), OciSpecError> {
         if let Some(Some(ref s)) = self.cpu_affinity_initial {
@@ -610,27 +862,97 @@ where
     Ok(value)
 }
 
-fn exec_cpu_affinity_regex() -> &'static Regex {
-    static EXEC_CPU_AFFINITY_REGEX: OnceLock<Regex> = OnceLock::new();
-    EXEC_CPU_AFFINITY_REGEX.get_or_init(|| {
-        Regex::new(r"^(\d+(-\d+)?)(,\d+(-\d+)?)*$")
-            .expect("Failed to create regex for execCPUAffinity")
-    })
-}
-
 fn validate_cpu_affinity(s: &str) -> Result<(), String> {
-    if !exec_cpu_affinity_regex().is_match(s) {
+    if !super::linux::is_cpu_list(s) {
         return Err(format!("Invalid execCPUAffinity format: {}", s));
     }
 
     Ok(())
 }
 
+#[cfg(feature = "proptests")]
+impl Arbitrary for User {
+    fn arbitrary(g: &mut Gen) -> User {
+        User {
+            uid: u32::arbitrary(g),
+            gid: u32::arbitrary(g),
+            umask: some_none_generator_util(g),
+            additional_gids: some_none_generator_util(g),
+            username: some_none_generator_util(g),
+        }
+    }
+}
+
+#[cfg(feature = "proptests")]
+impl Arbitrary for Process {
+    fn arbitrary(g: &mut Gen) -> Process {
+        Process {
+            terminal: some_none_generator_util(g),
+            user: User::arbitrary(g),
+            args: some_none_generator_util(g),
+            env: some_none_generator_util(g),
+            cwd: PathBuf::arbitrary(g),
+            no_new_privileges: some_none_generator_util(g),
+            apparmor_profile: some_none_generator_util(g),
+            oom_score_adj: some_none_generator_util(g),
+            selinux_label: some_none_generator_util(g),
+            ..Default::default()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn user_spec_forms() {
+        let name = |n: &str| IdOrName::Name(n.to_owned());
+        for (input, user, group) in [
+            ("", IdOrName::Id(0), None),
+            ("nginx", name("nginx"), None),
+            ("1000", IdOrName::Id(1000), None),
+            ("nginx:www", name("nginx"), Some(name("www"))),
+            ("1000:100", IdOrName::Id(1000), Some(IdOrName::Id(100))),
+            ("1000:www", IdOrName::Id(1000), Some(name("www"))),
+            ("nginx:100", name("nginx"), Some(IdOrName::Id(100))),
+        ] {
+            let spec = User::from_spec_string(input).unwrap();
+            assert_eq!(spec, UserSpec { user, group }, "{input}");
+            if !input.is_empty() {
+                assert_eq!(spec.to_string(), input);
+            }
+        }
+        for invalid in [":", "nginx:", ":www"] {
+            assert!(User::from_spec_string(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn user_spec_resolve() {
+        let lookup_user = |user: &IdOrName| match user {
+            IdOrName::Name(name) if name == "nginx" => Some((101, 101)),
+            IdOrName::Id(101) => Some((101, 101)),
+            _ => None,
+        };
+        let lookup_group = |group: &str| (group == "www").then_some(33);
+        let resolve = |s: &str| {
+            User::from_spec_string(s)
+                .unwrap()
+                .resolve(lookup_user, lookup_group)
+                .map(|u| (u.uid(), u.gid()))
+        };
+
+        assert_eq!(resolve("nginx").unwrap(), (101, 101));
+        assert_eq!(resolve("101").unwrap(), (101, 101));
+        assert_eq!(resolve("nginx:www").unwrap(), (101, 33));
+        assert_eq!(resolve("1000").unwrap(), (1000, 0));
+        assert_eq!(resolve("1000:5").unwrap(), (1000, 5));
+        assert!(resolve("apache").is_err());
+        assert!(resolve("nginx:staff").is_err());
+    }
+
     // PosixRlimitType test cases
     #[test]
     fn posix_rlimit_type_enum_to_string() {
@@ -774,4 +1096,20 @@ mod tests {
         assert!(affinity.cpu_affinity_initial.is_none());
         assert!(affinity.cpu_affinity_final.is_none());
     }
+
+    #[test]
+    fn process_builder_validation() {
+        assert!(ProcessBuilder::default().cwd("/srv").build().is_ok());
+        assert!(ProcessBuilder::default().cwd("srv").build().is_err());
+        for cwd in ["C:\\", "c:/Users", "\\\\server\\share"] {
+            assert!(ProcessBuilder::default().cwd(cwd).build().is_ok(), "{cwd}");
+        }
+        for cwd in ["C:", "C:foo", "\\foo", "1:\\"] {
+            assert!(ProcessBuilder::default().cwd(cwd).build().is_err(), "{cwd}");
+        }
+        assert!(ProcessBuilder::default()
+            .args(Vec::<String>::new())
+            .build()
+            .is_err());
+    }
 }
@@ -1,4 +1,4 @@
-use crate::error::OciSpecError;
+use crate::error::{oci_error, OciSpecError, Result};
 use derive_builder::Builder;
 use getset::{Getters, Setters};
 use serde::{Deserialize, Serialize};
@@ -6,12 +6,13 @@ use serde::{Deserialize, Serialize};
 #[derive(
     Builder, Clone, Debug, Default, Deserialize, Getters, Setters, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 #[getset(get = "pub", set = "pub")]
 /// Solaris contains platform-specific configuration for Solaris application
@@ -45,15 +46,81 @@ pub struct Solaris {
     capped_memory: Option<SolarisCappedMemory>,
 }
 
+option_getters! {
+    Solaris {
+        milestone_ref => milestone: str,
+        limitpriv_ref => limitpriv: str,
+        max_shm_memory_ref => max_shm_memory: str,
+        anet_ref => anet: [SolarisAnet],
+        capped_cpu_ref => capped_cpu: SolarisCappedCPU,
+        capped_memory_ref => capped_memory: SolarisCappedMemory,
+    }
+}
+
+impl Solaris {
+    /// Returns the maximum amount of shared memory in bytes, see
+    /// [parse_solaris_size].
+    pub fn max_shm_memory_bytes(&self) -> Result<Option<u64>> {
+        self.max_shm_memory_ref()
+            .map(parse_solaris_size)
+            .transpose()
+    }
+}
+
+impl SolarisBuilder {
+    fn validate(&self) -> Result<()> {
+        if let Some(Some(size)) = &self.max_shm_memory {
+            parse_solaris_size(size)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a zonecfg memory size, which is a whole number of bytes with an
+/// optional binary scale suffix `K`, `M`, `G` or `T`, e.g. `512M`. The
+/// suffix is case-insensitive and may be followed by `B`.
+///
+/// ```
+/// use oci_spec::runtime::parse_solaris_size;
+///
+/// assert_eq!(parse_solaris_size("512m").unwrap(), 512 << 20);
+/// assert_eq!(parse_solaris_size("2GB").unwrap(), 2 << 30);
+/// assert_eq!(parse_solaris_size("4096").unwrap(), 4096);
+/// assert!(parse_solaris_size("1.5G").is_err());
+/// ```
+pub fn parse_solaris_size(size: &str) -> Result<u64> {
+    let invalid = || oci_error(format!("invalid solaris memory size: {size:?}"));
+    let trimmed = size.trim();
+    let digits = trimmed.trim_end_matches(|c: char| !c.is_ascii_digit());
+    let suffix = trimmed[digits.len()..].to_ascii_lowercase();
+    let shift = match suffix.strip_suffix('b').unwrap_or(&suffix) {
+        "" => 0,
+        "k" => 10,
+        "m" => 20,
+        "g" => 30,
+        "t" => 40,
+        _ => return Err(invalid()),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|value| value.checked_mul(1 << shift))
+        .ok_or_else(invalid)
+}
+
 #[derive(
     Builder, Clone, Debug, Default, Deserialize, Getters, Setters, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 #[getset(get = "pub", set = "pub")]
 /// SolarisAnet provides the specification for automatic creation of network
@@ -87,16 +154,112 @@ pub struct SolarisAnet {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Set the VNIC's macAddress.
     mac_address: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The VLAN ID of the VNIC, from 1 to 4094. This is the zonecfg
+    /// `vlan-id` property and not part of the runtime spec.
+    vlan_id: Option<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The maximum transmission unit of the VNIC. This is the zonecfg `mtu`
+    /// property and not part of the runtime spec.
+    mtu: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The full duplex bandwidth limit of the VNIC, e.g. `100M`. This is the
+    /// zonecfg `maxbw` property and not part of the runtime spec.
+    maxbw: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The relative priority of the VNIC: `low`, `medium` or `high`. This is
+    /// the zonecfg `priority` property and not part of the runtime spec.
+    priority: Option<String>,
+}
+
+option_getters! {
+    SolarisAnet {
+        linkname_ref => linkname: str,
+        lower_link_ref => lower_link: str,
+        allowed_address_ref => allowed_address: str,
+        configure_allowed_address_ref => configure_allowed_address: str,
+        defrouter_ref => defrouter: str,
+        link_protection_ref => link_protection: str,
+        mac_address_ref => mac_address: str,
+        maxbw_ref => maxbw: str,
+        priority_ref => priority: str,
+    }
+}
+
+const ANET_LINK_PROTECTIONS: [&str; 4] =
+    ["mac-nospoof", "restricted", "ip-nospoof", "dhcp-nospoof"];
+const ANET_PRIORITIES: [&str; 3] = ["low", "medium", "high"];
+
+impl SolarisAnetBuilder {
+    fn validate(&self) -> Result<()> {
+        if let Some(Some(configure)) = &self.configure_allowed_address {
+            if configure != "true" && configure != "false" {
+                return Err(oci_error(format!(
+                    "invalid anet configureAllowedAddress {configure:?}, expected true or false"
+                )));
+            }
+        }
+        if let Some(Some(protection)) = &self.link_protection {
+            if let Some(unknown) = protection
+                .split(',')
+                .map(str::trim)
+                .find(|p| !ANET_LINK_PROTECTIONS.contains(p))
+            {
+                return Err(oci_error(format!(
+                    "invalid anet linkProtection {unknown:?}"
+                )));
+            }
+        }
+        if let Some(Some(mac)) = &self.mac_address {
+            if !is_anet_mac_address(mac) {
+                return Err(oci_error(format!("invalid anet macAddress {mac:?}")));
+            }
+        }
+        if let Some(Some(vlan_id)) = self.vlan_id {
+            if !(1..=4094).contains(&vlan_id) {
+                return Err(oci_error(format!(
+                    "anet vlanId {vlan_id} out of range 1-4094"
+                )));
+            }
+        }
+        if let Some(Some(maxbw)) = &self.maxbw {
+            parse_solaris_size(maxbw)?;
+        }
+        if let Some(Some(priority)) = &self.priority {
+            if !ANET_PRIORITIES.contains(&priority.as_str()) {
+                return Err(oci_error(format!("invalid anet priority {priority:?}")));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if `mac` is one of the zonecfg keywords `factory`, `random`
+/// or `auto`, or a MAC address of six colon separated hex octets.
+fn is_anet_mac_address(mac: &str) -> bool {
+    if matches!(mac, "factory" | "random" | "auto") {
+        return true;
+    }
+    let octets: Vec<&str> = mac.split(':').collect();
+    octets.len() == 6
+        && octets.iter().all(|octet| {
+            (1..=2).contains(&octet.len()) && octet.bytes().all(|b| b.is_ascii_hexdigit())
+        })
 }
 
 #[derive(
     Builder, Clone, Debug, Default, Deserialize, Getters, Setters, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 #[getset(get = "pub", set = "pub")]
 /// SolarisCappedCPU allows users to set limit on the amount of CPU time
@@ -107,14 +270,45 @@ pub struct SolarisCappedCPU {
     ncpus: Option<String>,
 }
 
+option_getters! {
+    SolarisCappedCPU {
+        ncpus_ref => ncpus: str,
+    }
+}
+
+impl SolarisCappedCPU {
+    /// Returns the amount of CPUs as number, e.g. `1.5` for `"1.5"`.
+    pub fn ncpus_value(&self) -> Result<Option<f64>> {
+        self.ncpus_ref().map(parse_ncpus).transpose()
+    }
+}
+
+impl SolarisCappedCPUBuilder {
+    fn validate(&self) -> Result<()> {
+        if let Some(Some(ncpus)) = &self.ncpus {
+            parse_ncpus(ncpus)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a positive, finite amount of CPUs.
+fn parse_ncpus(ncpus: &str) -> Result<f64> {
+    match ncpus.trim().parse::<f64>() {
+        Ok(value) if value.is_finite() && value > 0.0 => Ok(value),
+        _ => Err(oci_error(format!("invalid solaris ncpus: {ncpus:?}"))),
+    }
+}
+
 #[derive(
     Builder, Clone, Debug, Default, Deserialize, Getters, Setters, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 #[getset(get = "pub", set = "pub")]
 /// SolarisCappedMemory allows users to set the physical and swap caps on
@@ -128,3 +322,114 @@ pub struct SolarisCappedMemory {
     /// The swap caps on the memory.
     swap: Option<String>,
 }
+
+option_getters! {
+    SolarisCappedMemory {
+        physical_ref => physical: str,
+        swap_ref => swap: str,
+    }
+}
+
+impl SolarisCappedMemory {
+    /// Returns the physical memory cap in bytes, see [parse_solaris_size].
+    pub fn physical_bytes(&self) -> Result<Option<u64>> {
+        self.physical_ref().map(parse_solaris_size).transpose()
+    }
+
+    /// Returns the swap cap in bytes, see [parse_solaris_size].
+    pub fn swap_bytes(&self) -> Result<Option<u64>> {
+        self.swap_ref().map(parse_solaris_size).transpose()
+    }
+}
+
+impl SolarisCappedMemoryBuilder {
+    fn validate(&self) -> Result<()> {
+        for size in [&self.physical, &self.swap].into_iter().flatten().flatten() {
+            parse_solaris_size(size)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solaris_sizes() -> Result<()> {
+        assert_eq!(parse_solaris_size("0")?, 0);
+        assert_eq!(parse_solaris_size("1k")?, 1024);
+        assert_eq!(parse_solaris_size(" 3T ")?, 3 << 40);
+        for invalid in ["", "G", "1x", "-1G", "1 G", "99999999999T"] {
+            assert!(parse_solaris_size(invalid).is_err(), "{invalid}");
+        }
+
+        let memory = SolarisCappedMemoryBuilder::default()
+            .physical("512m")
+            .swap("1g")
+            .build()?;
+        assert_eq!(memory.physical_bytes()?, Some(512 << 20));
+        assert_eq!(memory.swap_bytes()?, Some(1 << 30));
+        assert!(SolarisCappedMemoryBuilder::default()
+            .swap("lots")
+            .build()
+            .is_err());
+        assert!(SolarisBuilder::default()
+            .max_shm_memory("1.5G")
+            .build()
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn solaris_ncpus() -> Result<()> {
+        let cpu = SolarisCappedCPUBuilder::default().ncpus("1.5").build()?;
+        assert_eq!(cpu.ncpus_value()?, Some(1.5));
+        assert_eq!(SolarisCappedCPU::default().ncpus_value()?, None);
+        for invalid in ["0", "-1", "many", "NaN", "inf"] {
+            assert!(
+                SolarisCappedCPUBuilder::default()
+                    .ncpus(invalid)
+                    .build()
+                    .is_err(),
+                "{invalid}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn solaris_anet() -> Result<()> {
+        let anet = SolarisAnetBuilder::default()
+            .linkname("net0")
+            .lower_link("net2")
+            .configure_allowed_address("true")
+            .link_protection("mac-nospoof, ip-nospoof")
+            .mac_address("02:08:20:a2:c3:0d")
+            .vlan_id(100u16)
+            .mtu(9000u32)
+            .maxbw("100M")
+            .priority("high")
+            .build()?;
+        let json = serde_json::to_value(&anet)?;
+        assert_eq!(json["vlanId"], 100);
+        assert_eq!(json["maxbw"], "100M");
+        assert_eq!(serde_json::from_value::<SolarisAnet>(json)?, anet);
+
+        assert!(SolarisAnetBuilder::default()
+            .mac_address("auto")
+            .build()
+            .is_ok());
+        for builder in [
+            SolarisAnetBuilder::default().configure_allowed_address("yes"),
+            SolarisAnetBuilder::default().link_protection("mac-nospoof,none"),
+            SolarisAnetBuilder::default().mac_address("02:08:20:a2:c3"),
+            SolarisAnetBuilder::default().vlan_id(4095u16),
+            SolarisAnetBuilder::default().maxbw("fast"),
+            SolarisAnetBuilder::default().priority("urgent"),
+        ] {
+            assert!(builder.build().is_err());
+        }
+        Ok(())
+    }
+}
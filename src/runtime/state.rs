@@ -0,0 +1,278 @@
+use std::{collections::HashMap, path::PathBuf};
+
+#[cfg(feature = "filesystem")]
+use std::path::Path;
+
+use derive_builder::Builder;
+use getset::{CopyGetters, Getters, MutGetters, Setters};
+use serde::{Deserialize, Serialize};
+
+use super::OciVersion;
+#[cfg(feature = "filesystem")]
+use super::SaveOptions;
+use crate::error::OciSpecError;
+#[cfg(feature = "filesystem")]
+use crate::error::Result;
+
+/// The name of the file descriptor of the seccomp notify listener in the
+/// `fds` of a [ContainerProcessState].
+pub const SECCOMP_FD_NAME: &str = "seccompFd";
+
+/// The runtime status of a container.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ContainerState {
+    /// The container is being created.
+    #[default]
+    Creating,
+    /// The runtime has finished the create operation, and the container
+    /// process has neither exited nor executed the user-specified program.
+    Created,
+    /// The container process has executed the user-specified program but has
+    /// not exited.
+    Running,
+    /// The container process has exited.
+    Stopped,
+}
+
+#[derive(
+    Builder,
+    Clone,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Getters,
+    MutGetters,
+    Setters,
+    PartialEq,
+    Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+#[getset(get_mut = "pub", get = "pub", set = "pub")]
+/// The [state](https://github.com/opencontainers/runtime-spec/blob/main/runtime.md#state)
+/// of a container, as reported by the `state` operation of a runtime.
+pub struct State {
+    #[serde(rename = "ociVersion")]
+    /// The version of the runtime specification with which the state
+    /// complies.
+    version: OciVersion,
+
+    /// The ID of the container.
+    id: String,
+
+    /// The runtime status of the container.
+    status: ContainerState,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The ID of the container process, as seen by the host. It is required
+    /// while the container is created or running.
+    pid: Option<i32>,
+
+    /// The absolute path to the bundle directory of the container.
+    bundle: PathBuf,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The annotations of the container, taken from its config.
+    annotations: Option<HashMap<String, String>>,
+}
+
+option_getters! {
+    State {
+        annotations_ref => annotations: HashMap<String, String>,
+    }
+}
+
+impl State {
+    /// Load a `State` from the provided JSON file `path`.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io] if the file does not exist or an
+    /// [OciSpecError::SerDe] if it is invalid, wrapped in an [OciSpecError::Context]
+    /// holding `path`.
+    #[cfg(feature = "filesystem")]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        crate::from_file(path)
+    }
+
+    /// Save the `State` to the provided JSON file `path`. The file is replaced
+    /// atomically like by [Spec::save](super::Spec::save).
+    /// # Errors
+    /// This function will return an [OciSpecError::Io] if a file cannot be created at the provided
+    /// path or an [OciSpecError::SerDe] if the state cannot be serialized.
+    #[cfg(feature = "filesystem")]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.save_with(path, &SaveOptions::default())
+    }
+
+    /// Save the `State` to the provided JSON file `path` like [State::save],
+    /// applying `options`. Locking serializes shims which write the state of
+    /// the same container concurrently.
+    /// # Errors
+    /// This function will return an [OciSpecError::Io] if a file cannot be created at the provided
+    /// path or the lock cannot be acquired or an [OciSpecError::SerDe] if the state cannot be
+    /// serialized.
+    #[cfg(feature = "filesystem")]
+    pub fn save_with<P: AsRef<Path>>(&self, path: P, options: &SaveOptions) -> Result<()> {
+        crate::to_file_atomic(self, path, false, options.lock)
+    }
+}
+
+#[derive(
+    Builder,
+    Clone,
+    CopyGetters,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Getters,
+    MutGetters,
+    Setters,
+    PartialEq,
+    Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+/// The state sent by the runtime to a seccomp agent over the
+/// `listenerPath` socket, together with the file descriptors named in
+/// `fds`.
+pub struct ContainerProcessState {
+    #[serde(rename = "ociVersion")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
+    /// The version of the runtime specification with which the state
+    /// complies.
+    version: OciVersion,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
+    /// The names of the file descriptors passed along with the state, in the
+    /// order in which they are passed.
+    fds: Vec<String>,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    /// The ID of the container process, as seen by the runtime.
+    pid: i32,
+
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
+    /// The `listenerMetadata` of the seccomp configuration of the container.
+    metadata: String,
+
+    #[getset(get_mut = "pub", get = "pub", set = "pub")]
+    /// The state of the container.
+    state: State,
+}
+
+impl ContainerProcessState {
+    /// Create the state sent to a seccomp agent for the container process
+    /// `pid`, passing the seccomp notify listener as the only file
+    /// descriptor.
+    ///
+    /// ```
+    /// use oci_spec::runtime::{ContainerProcessState, ContainerState, StateBuilder};
+    ///
+    /// let state = StateBuilder::default()
+    ///     .id("container")
+    ///     .status(ContainerState::Creating)
+    ///     .pid(4422)
+    ///     .bundle("/containers/redis")
+    ///     .build()
+    ///     .unwrap();
+    /// let process_state = ContainerProcessState::for_seccomp_agent(4422, "", state);
+    /// assert_eq!(process_state.seccomp_fd_index(), Some(0));
+    /// ```
+    pub fn for_seccomp_agent(pid: i32, metadata: impl Into<String>, state: State) -> Self {
+        Self {
+            version: OciVersion::default(),
+            fds: vec![SECCOMP_FD_NAME.to_owned()],
+            pid,
+            metadata: metadata.into(),
+            state,
+        }
+    }
+
+    /// Returns the index of the seccomp notify listener among the file
+    /// descriptors passed with the state, i.e. the position of
+    /// [SECCOMP_FD_NAME] in `fds`.
+    pub fn seccomp_fd_index(&self) -> Option<usize> {
+        self.fds.iter().position(|fd| fd == SECCOMP_FD_NAME)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn container_process_state() {
+        let json = r#"{
+            "ociVersion": "1.0.2",
+            "fds": ["seccompFd"],
+            "pid": 4422,
+            "metadata": "MKNOD=/dev/null,/dev/net/tun;BPF_MAP_TYPES=hash,array",
+            "state": {
+                "ociVersion": "1.0.2",
+                "id": "oci-container1",
+                "status": "creating",
+                "pid": 4422,
+                "bundle": "/containers/redis",
+                "annotations": {"myKey": "myValue"}
+            }
+        }"#;
+        let process_state: ContainerProcessState = serde_json::from_str(json).unwrap();
+        assert_eq!(process_state.pid(), 4422);
+        assert_eq!(process_state.seccomp_fd_index(), Some(0));
+        assert_eq!(process_state.state().status(), &ContainerState::Creating);
+        assert_eq!(
+            process_state.state().annotations_ref().unwrap()["myKey"],
+            "myValue"
+        );
+
+        let agent = ContainerProcessState::for_seccomp_agent(
+            4422,
+            process_state.metadata().clone(),
+            process_state.state().clone(),
+        );
+        assert_eq!(agent.fds(), process_state.fds());
+        let value = serde_json::to_value(&agent).unwrap();
+        assert_eq!(value["fds"], serde_json::json!([SECCOMP_FD_NAME]));
+        assert_eq!(value["state"]["status"], "creating");
+
+        let mut no_fds = agent;
+        no_fds.set_fds(vec![]);
+        assert_eq!(no_fds.seccomp_fd_index(), None);
+    }
+
+    #[cfg(feature = "filesystem")]
+    #[test]
+    fn state_save_load() {
+        let test_dir = tempfile::tempdir().expect("failed to create tmp test dir");
+        let path = test_dir.path().join("state.json");
+        let state = StateBuilder::default()
+            .id("container")
+            .status(ContainerState::Running)
+            .pid(1)
+            .bundle("/bundle")
+            .build()
+            .unwrap();
+        state
+            .save_with(&path, &SaveOptions { lock: true })
+            .expect("failed to save state");
+        assert_eq!(State::load(&path).unwrap(), state);
+    }
+}
@@ -0,0 +1,144 @@
+//! Well-known groups of syscall names for authoring seccomp profiles, based on
+//! the [Docker default seccomp profile](https://docs.docker.com/engine/security/seccomp/).
+//!
+//! ```
+//! use oci_spec::runtime::{syscalls, LinuxSeccompAction, LinuxSyscall};
+//!
+//! let rule = LinuxSyscall::new(syscalls::CLOCK, LinuxSeccompAction::ScmpActErrno);
+//! assert!(rule.names().contains(&"clock_settime".to_string()));
+//! ```
+
+/// Setting the system clock, allowed by the Docker default profile only with
+/// `CAP_SYS_TIME`.
+pub const CLOCK: &[&str] = &[
+    "adjtimex",
+    "clock_adjtime",
+    "clock_adjtime64",
+    "clock_settime",
+    "clock_settime64",
+    "settimeofday",
+    "stime",
+];
+
+/// Mounting and unmounting filesystems, allowed by the Docker default profile
+/// only with `CAP_SYS_ADMIN`.
+pub const MOUNT: &[&str] = &[
+    "fsconfig",
+    "fsmount",
+    "fsopen",
+    "fspick",
+    "mount",
+    "mount_setattr",
+    "move_mount",
+    "open_tree",
+    "pivot_root",
+    "umount",
+    "umount2",
+];
+
+/// Joining and creating namespaces, allowed by the Docker default profile only
+/// with `CAP_SYS_ADMIN`.
+pub const NAMESPACE: &[&str] = &["setns", "unshare"];
+
+/// Loading and unloading kernel modules, allowed by the Docker default profile
+/// only with `CAP_SYS_MODULE`.
+pub const MODULE: &[&str] = &[
+    "create_module",
+    "delete_module",
+    "finit_module",
+    "get_kernel_syms",
+    "init_module",
+    "query_module",
+];
+
+/// Rebooting and loading a new kernel, allowed by the Docker default profile
+/// only with `CAP_SYS_BOOT`.
+pub const REBOOT: &[&str] = &["kexec_file_load", "kexec_load", "reboot"];
+
+/// Tracing and inspecting other processes, allowed by the Docker default
+/// profile only with `CAP_SYS_PTRACE`.
+pub const PTRACE: &[&str] = &[
+    "kcmp",
+    "pidfd_getfd",
+    "process_madvise",
+    "process_vm_readv",
+    "process_vm_writev",
+    "ptrace",
+];
+
+/// Managing the kernel keyring, which is not namespaced and therefore blocked
+/// by the Docker default profile.
+pub const KEYRING: &[&str] = &["add_key", "keyctl", "request_key"];
+
+/// Enabling and disabling swap devices, allowed by the Docker default profile
+/// only with `CAP_SYS_ADMIN`.
+pub const SWAP: &[&str] = &["swapoff", "swapon"];
+
+/// The significant syscalls blocked by the Docker default profile for
+/// containers without additional capabilities.
+pub const PRIVILEGED: &[&str] = &[
+    "_sysctl",
+    "acct",
+    "add_key",
+    "bpf",
+    "clock_adjtime",
+    "clock_settime",
+    "create_module",
+    "delete_module",
+    "finit_module",
+    "get_kernel_syms",
+    "get_mempolicy",
+    "init_module",
+    "ioperm",
+    "iopl",
+    "kcmp",
+    "kexec_file_load",
+    "kexec_load",
+    "keyctl",
+    "lookup_dcookie",
+    "mbind",
+    "mount",
+    "move_pages",
+    "name_to_handle_at",
+    "nfsservctl",
+    "open_by_handle_at",
+    "perf_event_open",
+    "personality",
+    "pivot_root",
+    "process_vm_readv",
+    "process_vm_writev",
+    "ptrace",
+    "query_module",
+    "quotactl",
+    "reboot",
+    "request_key",
+    "set_mempolicy",
+    "setns",
+    "settimeofday",
+    "stime",
+    "swapoff",
+    "swapon",
+    "sysfs",
+    "umount",
+    "umount2",
+    "unshare",
+    "uselib",
+    "userfaultfd",
+    "ustat",
+    "vm86",
+    "vm86old",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_are_sorted_and_unique() {
+        for group in [
+            CLOCK, MOUNT, NAMESPACE, MODULE, REBOOT, PTRACE, KEYRING, SWAP, PRIVILEGED,
+        ] {
+            assert!(group.windows(2).all(|w| w[0] < w[1]), "{group:?}");
+        }
+    }
+}
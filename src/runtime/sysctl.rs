@@ -0,0 +1,138 @@
+//! Names of commonly used sysctls and checks whether they are namespaced,
+//! i.e. safe to set per container.
+//!
+//! Only sysctls isolated by the network or IPC namespace of a container are
+//! considered namespaced, following the rules applied by Kubernetes:
+//! `net.*`, `kernel.shm*`, `kernel.msg*`, `kernel.sem` and `fs.mqueue.*`.
+//!
+//! ```
+//! use oci_spec::runtime::sysctl;
+//!
+//! assert!(sysctl::is_namespaced(sysctl::NET_IPV4_IP_FORWARD));
+//! assert!(!sysctl::is_namespaced("kernel.panic"));
+//! ```
+
+use super::{Linux, LinuxNamespaceType};
+use crate::error::{oci_error, Result};
+
+/// Enables forwarding of IPv4 packets.
+pub const NET_IPV4_IP_FORWARD: &str = "net.ipv4.ip_forward";
+
+/// The first port which can be bound without `CAP_NET_BIND_SERVICE`.
+pub const NET_IPV4_IP_UNPRIVILEGED_PORT_START: &str = "net.ipv4.ip_unprivileged_port_start";
+
+/// The range of group ids allowed to create ICMP echo sockets.
+pub const NET_IPV4_PING_GROUP_RANGE: &str = "net.ipv4.ping_group_range";
+
+/// The range of local ports used for outgoing connections.
+pub const NET_IPV4_IP_LOCAL_PORT_RANGE: &str = "net.ipv4.ip_local_port_range";
+
+/// Enables TCP SYN cookies.
+pub const NET_IPV4_TCP_SYNCOOKIES: &str = "net.ipv4.tcp_syncookies";
+
+/// The maximum length of the listen queue of a socket.
+pub const NET_CORE_SOMAXCONN: &str = "net.core.somaxconn";
+
+/// Disables IPv6 on all interfaces.
+pub const NET_IPV6_CONF_ALL_DISABLE_IPV6: &str = "net.ipv6.conf.all.disable_ipv6";
+
+/// The maximum size of a shared memory segment in bytes.
+pub const KERNEL_SHMMAX: &str = "kernel.shmmax";
+
+/// The total amount of shared memory in pages.
+pub const KERNEL_SHMALL: &str = "kernel.shmall";
+
+/// Destroys shared memory segments once no process is attached anymore.
+pub const KERNEL_SHM_RMID_FORCED: &str = "kernel.shm_rmid_forced";
+
+/// The maximum size of a message queue message in bytes.
+pub const KERNEL_MSGMAX: &str = "kernel.msgmax";
+
+/// The maximum size of a message queue in bytes.
+pub const KERNEL_MSGMNB: &str = "kernel.msgmnb";
+
+/// The semaphore limits.
+pub const KERNEL_SEM: &str = "kernel.sem";
+
+/// The maximum number of POSIX message queues.
+pub const FS_MQUEUE_QUEUES_MAX: &str = "fs.mqueue.queues_max";
+
+/// The namespace isolating the sysctl `key`, if any.
+fn namespace_of(key: &str) -> Option<LinuxNamespaceType> {
+    if key.starts_with("net.") {
+        Some(LinuxNamespaceType::Network)
+    } else if key.starts_with("kernel.shm")
+        || key.starts_with("kernel.msg")
+        || key == KERNEL_SEM
+        || key.starts_with("fs.mqueue.")
+    {
+        Some(LinuxNamespaceType::Ipc)
+    } else {
+        None
+    }
+}
+
+/// Returns true if the sysctl `key` is isolated by a namespace and can be set
+/// per container.
+pub fn is_namespaced(key: &str) -> bool {
+    namespace_of(key).is_some()
+}
+
+impl Linux {
+    /// Check that every sysctl is namespaced and that the container has its
+    /// own namespace of the required type, i.e. that setting it does not
+    /// affect the host. If `allow_unsafe` is set, all sysctls are accepted.
+    /// # Errors
+    /// This function will return an [OciSpecError::Other](crate::OciSpecError::Other) for
+    /// the first sysctl violating these rules.
+    pub fn validate_sysctl(&self, allow_unsafe: bool) -> Result<()> {
+        if allow_unsafe {
+            return Ok(());
+        }
+
+        let namespaces = self.namespaces_ref().unwrap_or_default();
+        for key in self.sysctl_ref().into_iter().flat_map(|s| s.keys()) {
+            let Some(typ) = namespace_of(key) else {
+                return Err(oci_error(format!("sysctl {key} is not namespaced")));
+            };
+            if !namespaces.iter().any(|ns| ns.typ() == typ) {
+                return Err(oci_error(format!(
+                    "sysctl {key} requires a {typ} namespace"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::LinuxBuilder;
+
+    #[test]
+    fn validate_sysctl() {
+        let linux = |key: &str| {
+            LinuxBuilder::default()
+                .sysctl([(key.to_string(), "1".to_string())])
+                .build()
+                .unwrap()
+        };
+        assert!(linux(NET_IPV4_IP_FORWARD).validate_sysctl(false).is_ok());
+        assert!(linux(KERNEL_SHM_RMID_FORCED).validate_sysctl(false).is_ok());
+        assert!(linux(FS_MQUEUE_QUEUES_MAX).validate_sysctl(false).is_ok());
+        assert!(linux("kernel.panic").validate_sysctl(false).is_err());
+        assert!(linux("kernel.panic").validate_sysctl(true).is_ok());
+
+        let mut host_network = linux(NET_CORE_SOMAXCONN);
+        host_network
+            .namespaces_mut()
+            .as_mut()
+            .unwrap()
+            .retain(|ns| ns.typ() != LinuxNamespaceType::Network);
+        assert_eq!(
+            host_network.validate_sysctl(false).unwrap_err().to_string(),
+            "sysctl net.core.somaxconn requires a net namespace"
+        );
+    }
+}
@@ -30,14 +30,25 @@ fn test_linux_device_cgroup_to_string() {
     assert_eq!(ldc.to_string(), "a 1:9 rwm");
 }
 
+#[cfg(feature = "filesystem")]
 #[test]
 fn test_load_sample_spec() {
     let fixture_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .join("src/runtime/test/fixture/sample.json");
-    let err = Spec::load(fixture_path);
+    let err = Spec::load(&fixture_path);
     assert!(err.is_ok(), "failed to load spec: {err:?}");
+    // The sample still sets the deprecated linux.resources.oomScoreAdj,
+    // which strict loading reports as unknown.
+    let err = Spec::load_strict(fixture_path).expect_err("deprecated field should be rejected");
+    assert!(
+        err.root()
+            .to_string()
+            .contains("unknown field `linux.resources.oomScoreAdj`"),
+        "unexpected error: {err}"
+    );
 }
 
+#[cfg(feature = "filesystem")]
 #[test]
 fn test_load_sample_windows_spec() {
     let fixture_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -45,3 +56,50 @@ fn test_load_sample_windows_spec() {
     let err = Spec::load(fixture_path);
     assert!(err.is_ok(), "failed to load spec: {err:?}");
 }
+
+#[test]
+fn test_windows_cpu_tolerant_parsing() {
+    let cpu: WindowsCPUResources =
+        serde_json::from_str(r#"{"count": 2, "shares": "0o1750", "maximum": "50%"}"#).unwrap();
+    assert_eq!(cpu.count(), Some(2));
+    assert_eq!(cpu.shares(), Some(1000));
+    assert_eq!(cpu.maximum(), Some(5000));
+    assert_eq!(
+        serde_json::to_string(&cpu).unwrap(),
+        r#"{"count":2,"shares":1000,"maximum":5000}"#
+    );
+
+    let cpu: WindowsCPUResources =
+        serde_json::from_str(r#"{"shares": 10, "maximum": "12.5 %"}"#).unwrap();
+    assert_eq!(cpu.shares(), Some(10));
+    assert_eq!(cpu.maximum(), Some(1250));
+
+    for invalid in [
+        r#"{"maximum": 0}"#,
+        r#"{"maximum": 10001}"#,
+        r#"{"maximum": "101%"}"#,
+        r#"{"shares": "abc"}"#,
+    ] {
+        assert!(
+            serde_json::from_str::<WindowsCPUResources>(invalid).is_err(),
+            "{invalid} should be rejected"
+        );
+    }
+
+    assert!(WindowsCPUResourcesBuilder::default()
+        .maximum(0u16)
+        .build()
+        .is_err());
+}
+
+#[cfg(feature = "schemars")]
+#[test]
+fn spec_json_schema() {
+    let schema = schemars::schema_for!(Spec);
+    let properties = schema
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .expect("spec schema has properties");
+    assert!(properties.contains_key("ociVersion"));
+    assert!(properties.contains_key("linux"));
+}
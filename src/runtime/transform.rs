@@ -0,0 +1,427 @@
+//! Composable modifications of a [`Spec`].
+
+#[cfg(feature = "filesystem")]
+use std::path::PathBuf;
+
+use crate::{
+    env::env_key,
+    error::{oci_error, OciSpecError, Result},
+};
+
+use super::{miscellaneous::make_mount_rootless, Hook, Hooks, Linux, LinuxDevice, Mount, Spec};
+
+/// A modification of a [`Spec`].
+///
+/// Closures taking a `&mut Spec` implement this trait, so ad hoc edits can be
+/// mixed with the transforms of this module in a [`Pipeline`].
+pub trait SpecTransform {
+    /// Applies the modification to `spec`.
+    fn apply(&self, spec: &mut Spec) -> Result<()>;
+}
+
+impl<F> SpecTransform for F
+where
+    F: Fn(&mut Spec) -> Result<()>,
+{
+    fn apply(&self, spec: &mut Spec) -> Result<()> {
+        self(spec)
+    }
+}
+
+type Condition = Box<dyn Fn(&Spec) -> bool>;
+
+/// An ordered sequence of [`SpecTransform`]s, which is a transform itself.
+///
+/// ```
+/// use oci_spec::runtime::{Pipeline, Rootless, Sanitize, Spec};
+///
+/// let pipeline = Pipeline::new()
+///     .then(Rootless::new(1000, 1000))
+///     .then_if(
+///         |spec: &Spec| spec.hostname().as_deref() == Some("youki"),
+///         |spec: &mut Spec| {
+///             spec.set_hostname(Some("container".to_string()));
+///             Ok(())
+///         },
+///     )
+///     .then(Sanitize);
+///
+/// let mut spec = Spec::default();
+/// pipeline.run(&mut spec).unwrap();
+/// assert_eq!(spec.hostname().as_deref(), Some("container"));
+/// ```
+#[derive(Default)]
+pub struct Pipeline {
+    steps: Vec<(Option<Condition>, Box<dyn SpecTransform>)>,
+    collect_errors: bool,
+}
+
+impl Pipeline {
+    /// Creates an empty pipeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a transform which is always applied.
+    pub fn then(mut self, transform: impl SpecTransform + 'static) -> Self {
+        self.steps.push((None, Box::new(transform)));
+        self
+    }
+
+    /// Appends a transform which is applied only if `condition` holds for
+    /// the spec as modified by the preceding transforms.
+    pub fn then_if(
+        mut self,
+        condition: impl Fn(&Spec) -> bool + 'static,
+        transform: impl SpecTransform + 'static,
+    ) -> Self {
+        self.steps
+            .push((Some(Box::new(condition)), Box::new(transform)));
+        self
+    }
+
+    /// Continues with the remaining transforms if one fails, and reports
+    /// the errors of all failed transforms at the end, as an
+    /// [`OciSpecError::Multiple`] if more than one failed. By default, the
+    /// pipeline stops at the first error.
+    pub fn collect_errors(mut self, collect_errors: bool) -> Self {
+        self.collect_errors = collect_errors;
+        self
+    }
+
+    /// Returns the number of transforms in the pipeline.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns true if the pipeline has no transforms.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Applies all transforms to `spec` in order.
+    pub fn run(&self, spec: &mut Spec) -> Result<()> {
+        let mut errors = Vec::new();
+        for (i, (condition, transform)) in self.steps.iter().enumerate() {
+            if condition.as_ref().is_some_and(|c| !c(spec)) {
+                continue;
+            }
+            if let Err(err) = transform.apply(spec) {
+                let err = err.context(format!("transform {i}"));
+                if !self.collect_errors {
+                    return Err(err);
+                }
+                errors.push(err);
+            }
+        }
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(OciSpecError::Multiple(errors)),
+        }
+    }
+}
+
+impl SpecTransform for Pipeline {
+    fn apply(&self, spec: &mut Spec) -> Result<()> {
+        self.run(spec)
+    }
+}
+
+/// Adapts a spec to run in a user namespace without privileges, like
+/// [`Spec::rootless`] does for the default spec.
+///
+/// The user and group `0` of the container are mapped to `uid` and `gid` if
+/// no mappings are set, a user namespace is added, the network namespace is
+/// removed and the `/dev/pts` and `/sys` mounts are made usable.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rootless {
+    uid: u32,
+    gid: u32,
+}
+
+impl Rootless {
+    /// Creates the transform mapping the root user to `uid` and `gid`.
+    pub fn new(uid: u32, gid: u32) -> Self {
+        Self { uid, gid }
+    }
+}
+
+impl SpecTransform for Rootless {
+    fn apply(&self, spec: &mut Spec) -> Result<()> {
+        spec.linux_mut()
+            .get_or_insert_with(Linux::default)
+            .make_rootless(self.uid, self.gid);
+        spec.mounts_mut()
+            .iter_mut()
+            .flatten()
+            .for_each(make_mount_rootless);
+        Ok(())
+    }
+}
+
+/// Canonicalizes the `root.path` of a spec relative to a bundle directory,
+/// see [`Spec::canonicalize_rootfs`].
+#[cfg(feature = "filesystem")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CanonicalizeRootfs {
+    bundle: PathBuf,
+}
+
+#[cfg(feature = "filesystem")]
+impl CanonicalizeRootfs {
+    /// Creates the transform for the bundle at `bundle`.
+    pub fn new(bundle: impl Into<PathBuf>) -> Self {
+        Self {
+            bundle: bundle.into(),
+        }
+    }
+}
+
+#[cfg(feature = "filesystem")]
+impl SpecTransform for CanonicalizeRootfs {
+    fn apply(&self, spec: &mut Spec) -> Result<()> {
+        spec.canonicalize_rootfs(&self.bundle)
+    }
+}
+
+/// Edits injected into a container, modelled on the `containerEdits` of the
+/// [Container Device Interface](https://github.com/cncf-tags/container-device-interface).
+///
+/// Environment variables replace existing ones of the same name, devices and
+/// mounts replace existing ones at the same path and hooks are appended.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ContainerEdits {
+    /// Environment variables in `KEY=value` form.
+    pub env: Vec<String>,
+    /// Device nodes to create in the container.
+    pub device_nodes: Vec<LinuxDevice>,
+    /// Mounts to add to the container.
+    pub mounts: Vec<Mount>,
+    /// Hooks to run for the container.
+    pub hooks: Hooks,
+}
+
+impl SpecTransform for ContainerEdits {
+    fn apply(&self, spec: &mut Spec) -> Result<()> {
+        if !self.env.is_empty() {
+            let process = spec
+                .process_mut()
+                .as_mut()
+                .ok_or_else(|| oci_error("cannot edit the environment without a process"))?;
+            let env = process.env_mut().get_or_insert_with(Vec::new);
+            for var in &self.env {
+                let key = env_key(var);
+                env.retain(|v| env_key(v) != key);
+                env.push(var.clone());
+            }
+        }
+
+        if !self.device_nodes.is_empty() {
+            let linux = spec.linux_mut().get_or_insert_with(Linux::default);
+            let devices = linux.devices_mut().get_or_insert_with(Vec::new);
+            for device in &self.device_nodes {
+                devices.retain(|d| d.path() != device.path());
+                devices.push(device.clone());
+            }
+        }
+
+        if !self.mounts.is_empty() {
+            let mounts = spec.mounts_mut().get_or_insert_with(Vec::new);
+            for mount in &self.mounts {
+                mounts.retain(|m| m.destination() != mount.destination());
+                mounts.push(mount.clone());
+            }
+        }
+
+        if self.hooks != Hooks::default() {
+            let hooks = spec.hooks_mut().get_or_insert_with(Hooks::default);
+            let append = |to: &mut Option<Vec<Hook>>, from: &Option<Vec<Hook>>| {
+                if let Some(from) = from {
+                    to.get_or_insert_with(Vec::new).extend(from.iter().cloned());
+                }
+            };
+            append(hooks.prestart_mut(), self.hooks.prestart());
+            append(hooks.create_runtime_mut(), self.hooks.create_runtime());
+            append(hooks.create_container_mut(), self.hooks.create_container());
+            append(hooks.start_container_mut(), self.hooks.start_container());
+            append(hooks.poststart_mut(), self.hooks.poststart());
+            append(hooks.poststop_mut(), self.hooks.poststop());
+        }
+        Ok(())
+    }
+}
+
+/// Removes redundant entries of a spec: environment variables set multiple
+/// times keep their last value, and of multiple mounts at the same
+/// destination only the last one is kept.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Sanitize;
+
+impl SpecTransform for Sanitize {
+    fn apply(&self, spec: &mut Spec) -> Result<()> {
+        if let Some(env) = spec
+            .process_mut()
+            .as_mut()
+            .and_then(|p| p.env_mut().as_mut())
+        {
+            let mut sanitized: Vec<String> = Vec::with_capacity(env.len());
+            for var in env.drain(..).rev() {
+                if !sanitized.iter().any(|v| env_key(v) == env_key(&var)) {
+                    sanitized.push(var);
+                }
+            }
+            sanitized.reverse();
+            *env = sanitized;
+        }
+
+        if let Some(mounts) = spec.mounts_mut() {
+            let mut sanitized: Vec<Mount> = Vec::with_capacity(mounts.len());
+            for mount in mounts.drain(..).rev() {
+                if !sanitized
+                    .iter()
+                    .any(|m| m.destination() == mount.destination())
+                {
+                    sanitized.push(mount);
+                }
+            }
+            sanitized.reverse();
+            *mounts = sanitized;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::runtime::{HookBuilder, LinuxDeviceBuilder, LinuxNamespaceType, MountBuilder};
+
+    #[test]
+    fn pipeline() {
+        let pipeline = Pipeline::new()
+            .then(|spec: &mut Spec| {
+                spec.set_hostname(Some("a".to_owned()));
+                Ok(())
+            })
+            .then_if(
+                |spec: &Spec| spec.hostname().as_deref() == Some("b"),
+                |_: &mut Spec| Err(oci_error("not applied")),
+            );
+        let mut spec = Spec::default();
+        pipeline.run(&mut spec).unwrap();
+        assert_eq!(spec.hostname().as_deref(), Some("a"));
+        assert_eq!(pipeline.len(), 2);
+    }
+
+    #[test]
+    fn pipeline_errors() {
+        let fail = |_: &mut Spec| Err(oci_error("failed"));
+        let err = Pipeline::new()
+            .then(fail)
+            .then(fail)
+            .run(&mut Spec::default())
+            .unwrap_err();
+        assert_eq!(err.get_context(), Some("transform 0"));
+        assert_eq!(err.root().to_string(), "failed");
+
+        let err = Pipeline::new()
+            .then(fail)
+            .then(Sanitize)
+            .then(fail)
+            .collect_errors(true)
+            .run(&mut Spec::default())
+            .unwrap_err();
+        let OciSpecError::Multiple(errors) = &err else {
+            panic!("unexpected error: {err}");
+        };
+        let contexts: Vec<_> = errors.iter().map(|e| e.get_context().unwrap()).collect();
+        assert_eq!(contexts, ["transform 0", "transform 2"]);
+        assert_eq!(err.to_string(), "transform 0: failed; transform 2: failed");
+    }
+
+    #[test]
+    fn rootless() {
+        let mut spec = Spec::default();
+        Rootless::new(1000, 1000).apply(&mut spec).unwrap();
+        assert_eq!(spec.mounts(), &Spec::rootless(1000, 1000).mounts().clone());
+        let linux = spec.linux().as_ref().unwrap();
+        assert_eq!(linux.uid_mappings().as_ref().unwrap()[0].host_id(), 1000);
+        let namespaces = linux.namespaces().as_ref().unwrap();
+        assert!(namespaces
+            .iter()
+            .any(|ns| ns.typ() == LinuxNamespaceType::User));
+        assert!(!namespaces
+            .iter()
+            .any(|ns| ns.typ() == LinuxNamespaceType::Network));
+    }
+
+    #[test]
+    fn container_edits() {
+        let edits = ContainerEdits {
+            env: vec!["TERM=dumb".to_owned(), "GPU=0".to_owned()],
+            device_nodes: vec![LinuxDeviceBuilder::default()
+                .path("/dev/nvidia0")
+                .build()
+                .unwrap()],
+            mounts: vec![MountBuilder::default()
+                .destination("/proc")
+                .typ("bind")
+                .build()
+                .unwrap()],
+            hooks: Hooks::default()
+                .set_prestart(Some(vec![HookBuilder::default()
+                    .path("/usr/bin/hook")
+                    .build()
+                    .unwrap()]))
+                .clone(),
+        };
+        let mut spec = Spec::default();
+        let mounts = spec.mounts().as_ref().unwrap().len();
+        edits.apply(&mut spec).unwrap();
+
+        let env = spec.process().as_ref().unwrap().env().as_ref().unwrap();
+        assert_eq!(env.iter().filter(|v| v.starts_with("TERM=")).count(), 1);
+        assert!(env.contains(&"GPU=0".to_owned()));
+        assert_eq!(spec.mounts().as_ref().unwrap().len(), mounts);
+        assert_eq!(
+            spec.linux().as_ref().unwrap().devices().as_ref().unwrap()[0].path(),
+            &PathBuf::from("/dev/nvidia0")
+        );
+        assert_eq!(
+            spec.hooks()
+                .as_ref()
+                .unwrap()
+                .prestart()
+                .as_ref()
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn sanitize() {
+        let mut spec = Spec::default();
+        spec.process_mut().as_mut().unwrap().set_env(Some(vec![
+            "A=1".to_owned(),
+            "B=2".to_owned(),
+            "A=3".to_owned(),
+        ]));
+        let mut mounts = spec.mounts().clone().unwrap();
+        mounts.push(mounts[0].clone());
+        spec.set_mounts(Some(mounts));
+
+        Sanitize.apply(&mut spec).unwrap();
+        assert_eq!(
+            spec.process().as_ref().unwrap().env().as_ref().unwrap(),
+            &["B=2", "A=3"]
+        );
+        let defaults = Spec::default().mounts().clone().unwrap();
+        let mounts = spec.mounts().as_ref().unwrap();
+        assert_eq!(mounts.len(), defaults.len());
+        assert_eq!(mounts.last(), defaults.first());
+    }
+}
@@ -1,3 +1,15 @@
+use super::Spec;
+use crate::{
+    error::{oci_error, OciSpecError, Result},
+    semver::SemVer,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
 /// API incompatible changes.
 pub const VERSION_MAJOR: u32 = 1;
 
@@ -10,17 +22,470 @@ pub const VERSION_PATCH: u32 = 2;
 /// Indicates development branch. Releases will be empty string.
 pub const VERSION_DEV: &str = "-dev";
 
+/// The runtime specification release implemented by this crate, without
+/// the development suffix.
+pub const VERSION: OciVersion = OciVersion::new(
+    VERSION_MAJOR as u64,
+    VERSION_MINOR as u64,
+    VERSION_PATCH as u64,
+);
+
 /// Retrieve the version as string representation.
 pub fn version() -> String {
-    format!("{VERSION_MAJOR}.{VERSION_MINOR}.{VERSION_PATCH}{VERSION_DEV}")
+    format!("{VERSION}{VERSION_DEV}")
+}
+
+/// A [SemVer](https://semver.org) version as used by `ociVersion` and the
+/// `ociVersionMin`/`ociVersionMax` fields of the runtime features.
+///
+/// Versions are ordered by SemVer precedence, build metadata only breaks ties
+/// between otherwise equal versions.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct OciVersion {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    pre: Option<String>,
+    build: Option<String>,
+}
+
+impl OciVersion {
+    /// Create a release version without pre-release or build metadata.
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: None,
+            build: None,
+        }
+    }
+
+    /// The major version.
+    pub fn major(&self) -> u64 {
+        self.major
+    }
+
+    /// The minor version.
+    pub fn minor(&self) -> u64 {
+        self.minor
+    }
+
+    /// The patch version.
+    pub fn patch(&self) -> u64 {
+        self.patch
+    }
+
+    /// The pre-release identifiers, e.g. `rc.1` for `1.1.0-rc.1`.
+    pub fn pre(&self) -> Option<&str> {
+        self.pre.as_deref()
+    }
+
+    /// The build metadata, e.g. `abc` for `1.1.0+abc`.
+    pub fn build(&self) -> Option<&str> {
+        self.build.as_deref()
+    }
+
+    /// Returns true if a document declaring this version can be consumed by an
+    /// implementation of `other`: both share the same major version (and
+    /// minor version for `0.x`) and this version adds no fields unknown to
+    /// `other`. Patch versions, pre-releases and build metadata are ignored.
+    ///
+    /// ```
+    /// use oci_spec::runtime::OciVersion;
+    ///
+    /// let implemented = OciVersion::new(1, 2, 0);
+    /// assert!(OciVersion::new(1, 0, 2).is_compatible_with(&implemented));
+    /// assert!(!OciVersion::new(1, 3, 0).is_compatible_with(&implemented));
+    /// assert!(!OciVersion::new(2, 0, 0).is_compatible_with(&implemented));
+    /// ```
+    pub fn is_compatible_with(&self, other: &OciVersion) -> bool {
+        if self.major != other.major {
+            return false;
+        }
+        match self.major {
+            0 => self.minor == other.minor,
+            _ => self.minor <= other.minor,
+        }
+    }
+}
+
+/// Defaults to the runtime specification release implemented by this crate,
+/// see [VERSION].
+impl Default for OciVersion {
+    fn default() -> Self {
+        VERSION
+    }
+}
+
+impl OciVersion {
+    fn semver(&self) -> SemVer<'_> {
+        SemVer {
+            major: self.major,
+            minor: self.minor,
+            patch: self.patch,
+            pre: self.pre.as_deref(),
+            build: self.build.as_deref(),
+        }
+    }
+}
+
+impl FromStr for OciVersion {
+    type Err = OciSpecError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let version =
+            SemVer::parse(s).ok_or_else(|| oci_error(format!("invalid version {s:?}")))?;
+        Ok(Self {
+            major: version.major,
+            minor: version.minor,
+            patch: version.patch,
+            pre: version.pre.map(String::from),
+            build: version.build.map(String::from),
+        })
+    }
+}
+
+impl TryFrom<&str> for OciVersion {
+    type Error = OciSpecError;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for OciVersion {
+    type Error = OciSpecError;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl Display for OciVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{pre}")?;
+        }
+        if let Some(build) = &self.build {
+            write!(f, "+{build}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for OciVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.semver()
+            .cmp_precedence(&other.semver())
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for OciVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Serialize for OciVersion {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for OciVersion {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for OciVersion {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "OciVersion".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+        })
+    }
+}
+
+/// Released versions of the runtime specification which a [`Spec`] can be
+/// serialized for.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SpecVersion {
+    /// Runtime specification v1.0.
+    V1_0,
+    /// Runtime specification v1.1.
+    V1_1,
+    /// Runtime specification v1.2.
+    V1_2,
+}
+
+impl From<SpecVersion> for OciVersion {
+    fn from(version: SpecVersion) -> Self {
+        match version {
+            SpecVersion::V1_0 => OciVersion::new(1, 0, 2),
+            SpecVersion::V1_1 => OciVersion::new(1, 1, 0),
+            SpecVersion::V1_2 => OciVersion::new(1, 2, 0),
+        }
+    }
+}
+
+impl SpecVersion {
+    /// The `ociVersion` written for this release.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::V1_0 => "1.0.2",
+            Self::V1_1 => "1.1.0",
+            Self::V1_2 => "1.2.0",
+        }
+    }
+}
+
+impl Display for SpecVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Spec {
+    /// Parse the `ociVersion` of the spec. Loading a spec keeps its version as
+    /// is, so that specs with a version which is not valid SemVer can still be
+    /// inspected.
+    /// # Errors
+    /// This function will return an [OciSpecError::Other] if the version is not
+    /// valid SemVer.
+    /// # Example
+    /// ```
+    /// use oci_spec::runtime::{OciVersion, Spec};
+    ///
+    /// let spec = Spec::from_reader(r#"{"ociVersion":"1.2.0"}"#.as_bytes()).unwrap();
+    /// assert_eq!(spec.oci_version().unwrap(), OciVersion::new(1, 2, 0));
+    ///
+    /// let spec = Spec::from_reader(r#"{"ociVersion":"1.0"}"#.as_bytes()).unwrap();
+    /// assert_eq!(spec.version(), "1.0");
+    /// assert!(spec.oci_version().is_err());
+    /// ```
+    pub fn oci_version(&self) -> Result<OciVersion> {
+        self.version.parse()
+    }
+
+    /// Remove every field which is not part of the runtime specification
+    /// `version` and set `ociVersion` accordingly. Returns the paths of the
+    /// fields which had a value and were dropped.
+    pub fn downgrade(&mut self, version: SpecVersion) -> Vec<&'static str> {
+        let mut omitted = Vec::new();
+        let mut omit = |present: bool, field: &'static str| {
+            if present {
+                omitted.push(field);
+            }
+        };
+
+        if version < SpecVersion::V1_2 {
+            omit(self.zos.take().is_some(), "zos");
+            if let Some(process) = &mut self.process {
+                omit(
+                    process.exec_cpu_affinity().is_some(),
+                    "process.execCPUAffinity",
+                );
+                process.set_exec_cpu_affinity(None);
+            }
+        }
+
+        if version < SpecVersion::V1_1 {
+            omit(self.domainname.take().is_some(), "domainname");
+            if let Some(process) = &mut self.process {
+                omit(process.scheduler().is_some(), "process.scheduler");
+                process.set_scheduler(None);
+                omit(process.io_priority().is_some(), "process.ioPriority");
+                process.set_io_priority(None);
+            }
+            if let Some(linux) = &mut self.linux {
+                omit(
+                    linux.time_offsets_mut().take().is_some(),
+                    "linux.timeOffsets",
+                );
+                if let Some(memory) = linux
+                    .resources_mut()
+                    .as_mut()
+                    .and_then(|r| r.memory_mut().as_mut())
+                {
+                    omit(
+                        memory.check_before_update().is_some(),
+                        "linux.resources.memory.checkBeforeUpdate",
+                    );
+                    memory.set_check_before_update(None);
+                }
+            }
+        }
+
+        self.version = version.to_string();
+        omitted
+    }
+
+    /// Serialize the `Spec` as JSON for the runtime specification `version`,
+    /// omitting the fields introduced after it. `warn` is called once for
+    /// every field which had a value and was lost in the process.
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe] if the spec cannot be
+    /// serialized.
+    /// # Example
+    /// ```
+    /// use oci_spec::runtime::{SpecVersion, Spec};
+    ///
+    /// let mut spec = Spec::default();
+    /// spec.set_domainname(Some("example.com".to_string()));
+    /// let json = spec
+    ///     .serialize_for(SpecVersion::V1_0, |msg| eprintln!("{msg}"))
+    ///     .unwrap();
+    /// assert!(!json.contains("domainname"));
+    /// ```
+    pub fn serialize_for<F: FnMut(&str)>(
+        &self,
+        version: SpecVersion,
+        mut warn: F,
+    ) -> Result<String> {
+        let mut spec = self.clone();
+        for field in spec.downgrade(version) {
+            warn(&format!(
+                "{field} is not supported by ociVersion {version}, omitting it"
+            ));
+        }
+        Ok(serde_json::to_string(&spec)?)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runtime::{LinuxBuilder, ProcessBuilder, SchedulerBuilder, ZOS};
 
     #[test]
     fn version_test() {
         assert_eq!(version(), "1.0.2-dev".to_string())
     }
+
+    #[test]
+    fn oci_version_parse() {
+        let v: OciVersion = "1.1.0-rc.2+build.5".parse().unwrap();
+        assert_eq!((v.major(), v.minor(), v.patch()), (1, 1, 0));
+        assert_eq!(v.pre(), Some("rc.2"));
+        assert_eq!(v.build(), Some("build.5"));
+        assert_eq!(v.to_string(), "1.1.0-rc.2+build.5");
+        assert_eq!(VERSION.to_string(), "1.0.2");
+
+        for invalid in [
+            "", "1", "1.0", "1.0.0.0", "01.0.0", "1.0.0-", "1.0.0-01", "1.0.0+", "v1.0.0",
+        ] {
+            assert!(invalid.parse::<OciVersion>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn oci_version_ordering() {
+        let versions = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.2",
+            "1.1.0",
+            "2.0.0",
+        ]
+        .map(|v| v.parse::<OciVersion>().unwrap());
+        assert!(versions.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn oci_version_compatibility() {
+        let v1_1 = OciVersion::new(1, 1, 0);
+        assert!(OciVersion::new(1, 0, 0).is_compatible_with(&v1_1));
+        assert!("1.1.5-rc.1"
+            .parse::<OciVersion>()
+            .unwrap()
+            .is_compatible_with(&v1_1));
+        assert!(!OciVersion::new(1, 2, 0).is_compatible_with(&v1_1));
+        assert!(!OciVersion::new(0, 1, 0).is_compatible_with(&OciVersion::new(0, 2, 0)));
+        assert_eq!(
+            OciVersion::from(SpecVersion::V1_0).to_string(),
+            SpecVersion::V1_0.as_str()
+        );
+    }
+
+    #[test]
+    fn spec_version_ordering() {
+        assert!(SpecVersion::V1_0 < SpecVersion::V1_1);
+        assert!(SpecVersion::V1_1 < SpecVersion::V1_2);
+        assert_eq!(SpecVersion::V1_1.to_string(), "1.1.0");
+    }
+
+    #[test]
+    fn serialize_for_older_versions() {
+        let mut spec = Spec::default();
+        spec.set_domainname(Some("example.com".to_string()));
+        spec.set_zos(Some(ZOS::default()));
+        spec.set_process(Some(
+            ProcessBuilder::default()
+                .scheduler(SchedulerBuilder::default().build().unwrap())
+                .build()
+                .unwrap(),
+        ));
+        spec.set_linux(Some(
+            LinuxBuilder::default()
+                .time_offsets([("monotonic".to_string(), "1".to_string())])
+                .build()
+                .unwrap(),
+        ));
+
+        let mut warnings = Vec::new();
+        let json = spec
+            .serialize_for(SpecVersion::V1_2, |msg| warnings.push(msg.to_string()))
+            .unwrap();
+        assert!(warnings.is_empty());
+        assert!(json.contains(r#""ociVersion":"1.2.0""#));
+        assert!(json.contains("domainname"));
+
+        let json = spec
+            .serialize_for(SpecVersion::V1_1, |msg| warnings.push(msg.to_string()))
+            .unwrap();
+        assert_eq!(
+            warnings,
+            ["zos is not supported by ociVersion 1.1.0, omitting it"]
+        );
+        assert!(json.contains("scheduler"));
+
+        warnings.clear();
+        let mut downgraded = spec.clone();
+        assert_eq!(
+            downgraded.downgrade(SpecVersion::V1_0),
+            [
+                "zos",
+                "domainname",
+                "process.scheduler",
+                "linux.timeOffsets"
+            ]
+        );
+        let json = spec
+            .serialize_for(SpecVersion::V1_0, |msg| warnings.push(msg.to_string()))
+            .unwrap();
+        assert_eq!(warnings.len(), 4);
+        assert_eq!(json, serde_json::to_string(&downgraded).unwrap());
+        assert!(!json.contains("timeOffsets"));
+    }
 }
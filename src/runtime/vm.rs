@@ -1,12 +1,13 @@
-use crate::error::OciSpecError;
+use crate::error::{oci_error, OciSpecError};
 use derive_builder::Builder;
 use getset::{Getters, Setters};
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{fmt::Display, path::PathBuf};
 
 #[derive(
     Builder, Clone, Debug, Default, Deserialize, Getters, Setters, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -31,9 +32,17 @@ pub struct VM {
     image: Option<VMImage>,
 }
 
+option_getters! {
+    VM {
+        hypervisor_ref => hypervisor: VMHypervisor,
+        image_ref => image: VMImage,
+    }
+}
+
 #[derive(
     Builder, Clone, Debug, Default, Deserialize, Getters, Setters, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -53,14 +62,21 @@ pub struct VMHypervisor {
     parameters: Option<Vec<String>>,
 }
 
+option_getters! {
+    VMHypervisor {
+        parameters_ref => parameters: [String],
+    }
+}
+
 #[derive(
     Builder, Clone, Debug, Default, Deserialize, Getters, Setters, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 #[getset(get = "pub", set = "pub")]
 /// VMKernel contains information about the kernel to use for a virtual
@@ -80,14 +96,35 @@ pub struct VMKernel {
     initrd: Option<String>,
 }
 
+option_getters! {
+    VMKernel {
+        parameters_ref => parameters: [String],
+        initrd_ref => initrd: str,
+    }
+}
+
+impl VMKernelBuilder {
+    fn validate(&self) -> Result<(), OciSpecError> {
+        if self
+            .path
+            .as_ref()
+            .is_none_or(|path| path.as_os_str().is_empty())
+        {
+            return Err(oci_error("vm kernel path must not be empty"));
+        }
+        Ok(())
+    }
+}
+
 #[derive(
     Builder, Clone, Debug, Default, Deserialize, Getters, Setters, Eq, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 #[getset(get = "pub", set = "pub")]
 /// VMImage contains information about the virtual machine root image.
@@ -98,5 +135,145 @@ pub struct VMImage {
 
     /// Format is the root image format type (e.g. "qcow2", "raw", "vhd",
     /// etc).
-    format: String,
+    format: VMImageFormat,
+}
+
+impl VMImageBuilder {
+    fn validate(&self) -> Result<(), OciSpecError> {
+        match &self.format {
+            None => Err(oci_error("vm image format must be set")),
+            Some(VMImageFormat::Other(format)) if format.is_empty() => {
+                Err(oci_error("vm image format must not be empty"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The format of a [VMImage].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum VMImageFormat {
+    /// The QEMU copy-on-write format, version 2.
+    Qcow2,
+    /// A raw disk image.
+    #[default]
+    Raw,
+    /// The Hyper-V virtual hard disk format.
+    Vhd,
+    /// The VirtualBox disk image format.
+    Vdi,
+    /// A format not enumerated by the runtime spec.
+    Other(String),
+}
+
+impl From<&str> for VMImageFormat {
+    fn from(format: &str) -> Self {
+        match format {
+            "qcow2" => Self::Qcow2,
+            "raw" => Self::Raw,
+            "vhd" => Self::Vhd,
+            "vdi" => Self::Vdi,
+            format => Self::Other(format.to_owned()),
+        }
+    }
+}
+
+impl From<String> for VMImageFormat {
+    fn from(format: String) -> Self {
+        format.as_str().into()
+    }
+}
+
+impl AsRef<str> for VMImageFormat {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Qcow2 => "qcow2",
+            Self::Raw => "raw",
+            Self::Vhd => "vhd",
+            Self::Vdi => "vdi",
+            Self::Other(format) => format,
+        }
+    }
+}
+
+impl Display for VMImageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl Serialize for VMImageFormat {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_ref())
+    }
+}
+
+impl<'de> Deserialize<'de> for VMImageFormat {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(String::deserialize(deserializer)?.into())
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for VMImageFormat {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "VMImageFormat".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vm_image_format() {
+        let image: VMImage =
+            serde_json::from_str(r#"{"path":"/vm/disk.img","format":"qcow2"}"#).unwrap();
+        assert_eq!(image.format(), &VMImageFormat::Qcow2);
+        let image: VMImage =
+            serde_json::from_str(r#"{"path":"/vm/disk.img","format":"vmdk"}"#).unwrap();
+        assert_eq!(image.format(), &VMImageFormat::Other("vmdk".to_owned()));
+        assert_eq!(
+            serde_json::to_string(&image).unwrap(),
+            r#"{"path":"/vm/disk.img","format":"vmdk"}"#
+        );
+
+        assert!(VMImageBuilder::default()
+            .path("/vm/disk.img")
+            .format("vhd")
+            .build()
+            .is_ok());
+        assert!(VMImageBuilder::default()
+            .path("/vm/disk.img")
+            .build()
+            .is_err());
+        assert!(VMImageBuilder::default()
+            .path("/vm/disk.img")
+            .format("")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn vm_kernel_path() {
+        assert!(VMKernelBuilder::default()
+            .path("/vm/vmlinuz")
+            .build()
+            .is_ok());
+        assert!(VMKernelBuilder::default().build().is_err());
+        assert!(VMKernelBuilder::default().path("").build().is_err());
+    }
 }
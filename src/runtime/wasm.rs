@@ -0,0 +1,78 @@
+//! Helpers for WebAssembly workloads, as run by the Wasm handlers of crun
+//! and the runwasi shims of containerd.
+
+use super::{Process, Spec};
+
+/// The annotation selecting the handler of crun which runs the container,
+/// `wasm` for WebAssembly modules.
+pub const ANNOTATION_RUN_OCI_HANDLER: &str = "run.oci.handler";
+/// The annotation marking an image or container as a WebAssembly workload,
+/// with the value [WASM_VARIANT_COMPAT] or [WASM_VARIANT_COMPAT_SMART].
+pub const ANNOTATION_WASM_VARIANT: &str = "module.wasm.image/variant";
+/// The value of [ANNOTATION_RUN_OCI_HANDLER] for WebAssembly modules.
+pub const WASM_HANDLER: &str = "wasm";
+/// The variant of images which only contain WebAssembly modules.
+pub const WASM_VARIANT_COMPAT: &str = "compat";
+/// The variant of images which may also contain native binaries, which are
+/// run without a Wasm handler.
+pub const WASM_VARIANT_COMPAT_SMART: &str = "compat-smart";
+
+impl Spec {
+    /// Returns the default spec running the WebAssembly module at
+    /// `module_path`, relative to the root filesystem, with the Wasm handler.
+    ///
+    /// ```
+    /// use oci_spec::runtime::Spec;
+    ///
+    /// let spec = Spec::wasm_workload("/app.wasm");
+    /// assert!(spec.is_wasm_workload());
+    /// assert_eq!(spec.process().as_ref().unwrap().args().as_ref().unwrap(), &["/app.wasm"]);
+    /// ```
+    pub fn wasm_workload(module_path: impl Into<String>) -> Self {
+        let mut spec = Spec::default();
+        let mut process = spec.process.take().unwrap_or_else(Process::default);
+        process.set_args(Some(vec![module_path.into()]));
+        spec.set_process(Some(process));
+
+        let annotations = spec.annotations.get_or_insert_with(Default::default);
+        annotations.insert(ANNOTATION_RUN_OCI_HANDLER.into(), WASM_HANDLER.into());
+        annotations.insert(ANNOTATION_WASM_VARIANT.into(), WASM_VARIANT_COMPAT.into());
+        spec
+    }
+
+    /// Returns true if the spec selects the Wasm handler or is marked as a
+    /// WebAssembly workload by the variant annotation.
+    pub fn is_wasm_workload(&self) -> bool {
+        self.annotations.as_ref().is_some_and(|annotations| {
+            annotations
+                .get(ANNOTATION_RUN_OCI_HANDLER)
+                .is_some_and(|handler| handler == WASM_HANDLER)
+                || annotations.contains_key(ANNOTATION_WASM_VARIANT)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wasm_workload() {
+        let spec = Spec::wasm_workload("/hello.wasm");
+        let annotations = spec.annotations().as_ref().unwrap();
+        assert_eq!(annotations[ANNOTATION_RUN_OCI_HANDLER], WASM_HANDLER);
+        assert_eq!(annotations[ANNOTATION_WASM_VARIANT], WASM_VARIANT_COMPAT);
+        assert_eq!(
+            spec.process().as_ref().unwrap().args(),
+            &Some(vec!["/hello.wasm".to_owned()])
+        );
+        assert!(spec.is_wasm_workload());
+        assert!(!Spec::default().is_wasm_workload());
+
+        let mut spec = Spec::default();
+        spec.set_annotations(Some(
+            [(ANNOTATION_RUN_OCI_HANDLER.to_owned(), "krun".to_owned())].into(),
+        ));
+        assert!(!spec.is_wasm_workload());
+    }
+}
@@ -1,8 +1,8 @@
-use crate::error::OciSpecError;
+use crate::error::{oci_error, OciSpecError};
 use derive_builder::Builder;
 use getset::{CopyGetters, Getters, Setters};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::{collections::HashMap, fmt::Display};
 
 #[derive(
     Builder,
@@ -17,6 +17,7 @@ use std::collections::HashMap;
     PartialEq,
     Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -75,10 +76,213 @@ pub struct Windows {
     network: Option<WindowsNetwork>,
 }
 
+option_getters! {
+    Windows {
+        layer_folders_ref => layer_folders: [String],
+        devices_ref => devices: [WindowsDevice],
+        credential_spec_ref => credential_spec: HashMap<String, Option<serde_json::Value>>,
+        hyperv_ref => hyperv: WindowsHyperV,
+        network_ref => network: WindowsNetwork,
+    }
+}
+
+impl Windows {
+    /// Returns the credential spec as [WindowsCredentialSpec].
+    ///
+    /// # Errors
+    /// This function will return an [OciSpecError::SerDe] if the credential
+    /// spec does not match the typed representation.
+    pub fn typed_credential_spec(&self) -> Result<Option<WindowsCredentialSpec>, OciSpecError> {
+        self.credential_spec
+            .as_ref()
+            .map(|spec| serde_json::from_value(serde_json::to_value(spec)?))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    /// Sets the credential spec from a [WindowsCredentialSpec].
+    pub fn set_typed_credential_spec(
+        &mut self,
+        spec: Option<WindowsCredentialSpec>,
+    ) -> Result<&mut Self, OciSpecError> {
+        self.credential_spec = spec
+            .map(|spec| serde_json::from_value(serde_json::to_value(spec)?))
+            .transpose()?;
+        Ok(self)
+    }
+}
+
 #[derive(
     Builder, Clone, Debug, Default, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
 )]
-#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "PascalCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+#[getset(get = "pub", set = "pub")]
+/// WindowsCredentialSpec is the typed form of the group Managed Service
+/// Account (gMSA) credential spec of [Windows], as generated by the
+/// `CredentialSpec` PowerShell module and consumed by hcsshim.
+///
+/// ```
+/// use oci_spec::runtime::WindowsCredentialSpec;
+///
+/// let spec: WindowsCredentialSpec = serde_json::from_str(r#"{
+///     "CmsPlugins": ["ActiveDirectory"],
+///     "DomainJoinConfig": {"DnsName": "contoso.com", "NetBiosName": "CONTOSO"},
+///     "ActiveDirectoryConfig": {
+///         "GroupManagedServiceAccounts": [{"Name": "WebApp01", "Scope": "contoso.com"}]
+///     },
+///     "Custom": 1
+/// }"#).unwrap();
+///
+/// let gmsa = spec
+///     .active_directory_config_ref()
+///     .and_then(|config| config.group_managed_service_accounts_ref())
+///     .unwrap();
+/// assert_eq!(gmsa[0].name(), "WebApp01");
+/// assert_eq!(spec.other()["Custom"], 1);
+/// ```
+pub struct WindowsCredentialSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The credential management plugins, usually `ActiveDirectory`.
+    cms_plugins: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The domain the container host is joined to.
+    domain_join_config: Option<WindowsDomainJoinConfig>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The gMSAs and the plugin used to retrieve their credentials.
+    active_directory_config: Option<WindowsActiveDirectoryConfig>,
+
+    #[serde(flatten)]
+    /// All fields not covered by the typed representation.
+    other: HashMap<String, serde_json::Value>,
+}
+
+option_getters! {
+    WindowsCredentialSpec {
+        cms_plugins_ref => cms_plugins: [String],
+        domain_join_config_ref => domain_join_config: WindowsDomainJoinConfig,
+        active_directory_config_ref => active_directory_config: WindowsActiveDirectoryConfig,
+    }
+}
+
+#[derive(
+    Builder, Clone, Debug, Default, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "PascalCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+#[getset(get = "pub", set = "pub")]
+/// WindowsDomainJoinConfig describes the Active Directory domain of a
+/// [WindowsCredentialSpec].
+pub struct WindowsDomainJoinConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The security identifier of the domain.
+    sid: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The account name of the gMSA the container runs as.
+    machine_account_name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The GUID of the domain.
+    guid: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The DNS name of the forest root domain.
+    dns_tree_name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The DNS name of the domain.
+    dns_name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The NetBIOS name of the domain.
+    net_bios_name: Option<String>,
+}
+
+option_getters! {
+    WindowsDomainJoinConfig {
+        sid_ref => sid: str,
+        machine_account_name_ref => machine_account_name: str,
+        guid_ref => guid: str,
+        dns_tree_name_ref => dns_tree_name: str,
+        dns_name_ref => dns_name: str,
+        net_bios_name_ref => net_bios_name: str,
+    }
+}
+
+#[derive(
+    Builder, Clone, Debug, Default, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "PascalCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+#[getset(get = "pub", set = "pub")]
+/// WindowsActiveDirectoryConfig lists the gMSAs of a
+/// [WindowsCredentialSpec].
+pub struct WindowsActiveDirectoryConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The gMSAs whose credentials are available to the container.
+    group_managed_service_accounts: Option<Vec<WindowsGroupManagedServiceAccount>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The Container Credential Guard (CCG) plugin retrieving the gMSA
+    /// credentials on hosts not joined to the domain.
+    host_account_config: Option<WindowsHostAccountConfig>,
+}
+
+option_getters! {
+    WindowsActiveDirectoryConfig {
+        group_managed_service_accounts_ref => group_managed_service_accounts: [WindowsGroupManagedServiceAccount],
+        host_account_config_ref => host_account_config: WindowsHostAccountConfig,
+    }
+}
+
+#[derive(
+    Builder, Clone, Debug, Default, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "PascalCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+#[getset(get = "pub", set = "pub")]
+/// WindowsGroupManagedServiceAccount is a gMSA of a
+/// [WindowsActiveDirectoryConfig].
+pub struct WindowsGroupManagedServiceAccount {
+    /// The account name of the gMSA.
+    name: String,
+
+    /// The domain of the gMSA.
+    scope: String,
+}
+
+#[derive(
+    Builder, Clone, Debug, Default, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "PascalCase")]
 #[builder(
     default,
     pattern = "owned",
@@ -86,6 +290,33 @@ pub struct Windows {
     build_fn(error = "OciSpecError")
 )]
 #[getset(get = "pub", set = "pub")]
+/// WindowsHostAccountConfig configures the Container Credential Guard (CCG)
+/// plugin of a [WindowsActiveDirectoryConfig].
+pub struct WindowsHostAccountConfig {
+    /// The version of the portable CCG interface, e.g. `1`.
+    portable_ccg_version: String,
+
+    #[serde(rename = "PluginGUID")]
+    /// The COM class GUID of the CCG plugin.
+    plugin_guid: String,
+
+    /// The input passed to the plugin, e.g. the location of the credentials
+    /// of the host account.
+    plugin_input: String,
+}
+
+#[derive(
+    Builder, Clone, Debug, Default, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(validate = "Self::validate", error = "OciSpecError")
+)]
+#[getset(get = "pub", set = "pub")]
 /// WindowsDevice represents information about a host device to be mapped
 /// into the container.
 pub struct WindowsDevice {
@@ -93,12 +324,133 @@ pub struct WindowsDevice {
     id: String,
 
     /// Device identifier type: "class", etc..
-    id_type: String,
+    id_type: WindowsDeviceIdType,
+}
+
+impl WindowsDeviceBuilder {
+    fn validate(&self) -> Result<(), OciSpecError> {
+        let id = self.id.as_deref().unwrap_or_default();
+        if id.is_empty() {
+            return Err(oci_error("windows device id must not be empty"));
+        }
+        match self.id_type.as_ref() {
+            Some(WindowsDeviceIdType::Class) | None if !is_guid(id) => Err(oci_error(format!(
+                "windows device class {id} is not a GUID"
+            ))),
+            Some(WindowsDeviceIdType::Other(id_type)) if id_type.is_empty() => {
+                Err(oci_error("windows device id type must not be empty"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns true if `s` is a GUID like `5B45201D-F2F2-4F3B-85BB-30FF1F953599`,
+/// optionally enclosed in braces.
+fn is_guid(s: &str) -> bool {
+    let s = s
+        .strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .unwrap_or(s);
+    let groups: Vec<&str> = s.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && group.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// The type of the identifier of a [WindowsDevice], as supported by
+/// hcsshim.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WindowsDeviceIdType {
+    /// The id is the GUID of a device interface class, all devices of which
+    /// are mapped into the container.
+    #[default]
+    Class,
+    /// The id is the instance id of a device assigned to a Hyper-V isolated
+    /// container via virtual PCI.
+    VpciInstanceId,
+    /// The legacy name of [WindowsDeviceIdType::VpciInstanceId].
+    Vpci,
+    /// The id identifies a GPU assigned to a Hyper-V isolated container.
+    Gpu,
+    /// An id type not known to this crate.
+    Other(String),
+}
+
+impl From<&str> for WindowsDeviceIdType {
+    fn from(id_type: &str) -> Self {
+        match id_type {
+            "class" => Self::Class,
+            "vpci-instance-id" => Self::VpciInstanceId,
+            "vpci" => Self::Vpci,
+            "gpu" => Self::Gpu,
+            id_type => Self::Other(id_type.to_owned()),
+        }
+    }
+}
+
+impl From<String> for WindowsDeviceIdType {
+    fn from(id_type: String) -> Self {
+        id_type.as_str().into()
+    }
+}
+
+impl AsRef<str> for WindowsDeviceIdType {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Class => "class",
+            Self::VpciInstanceId => "vpci-instance-id",
+            Self::Vpci => "vpci",
+            Self::Gpu => "gpu",
+            Self::Other(id_type) => id_type,
+        }
+    }
+}
+
+impl Display for WindowsDeviceIdType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl Serialize for WindowsDeviceIdType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_ref())
+    }
+}
+
+impl<'de> Deserialize<'de> for WindowsDeviceIdType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(String::deserialize(deserializer)?.into())
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for WindowsDeviceIdType {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "WindowsDeviceIdType".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+        })
+    }
 }
 
 #[derive(
     Builder, Clone, Copy, Debug, Default, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -124,6 +476,7 @@ pub struct WindowsResources {
 #[derive(
     Builder, Clone, Copy, Debug, Default, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
@@ -139,34 +492,117 @@ pub struct WindowsMemoryResources {
 }
 
 #[derive(
-    Builder, Clone, Copy, Debug, Default, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
+    Builder,
+    Clone,
+    Copy,
+    CopyGetters,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Setters,
+    PartialEq,
+    Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[builder(
     default,
     pattern = "owned",
     setter(into, strip_option),
-    build_fn(error = "OciSpecError")
+    build_fn(validate = "Self::validate", error = "OciSpecError")
 )]
 #[getset(get_copy = "pub", set = "pub")]
 /// WindowsCPUResources contains CPU resource management settings.
+///
+/// Generated configurations encode `shares` and `maximum` inconsistently, so
+/// besides integers, decimal, octal (`0o` prefix) and percentage strings
+/// like `"50%"` are accepted when deserializing. Both values must be within
+/// 1 to 10000.
 pub struct WindowsCPUResources {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Number of CPUs available to the container.
     count: Option<u64>,
 
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_cpu_value"
+    )]
     /// CPU shares (relative weight to other containers with cpu shares).
     shares: Option<u16>,
 
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_cpu_value"
+    )]
     /// Specifies the portion of processor cycles that this container can
     /// use as a percentage times 100.
     maximum: Option<u16>,
 }
 
+impl WindowsCPUResourcesBuilder {
+    fn validate(&self) -> Result<(), OciSpecError> {
+        for value in [self.shares, self.maximum].into_iter().flatten().flatten() {
+            validate_cpu_value(value.into()).map_err(OciSpecError::Other)?;
+        }
+        Ok(())
+    }
+}
+
+const CPU_VALUE_MAX: u64 = 10000;
+
+fn validate_cpu_value(value: u64) -> Result<u16, String> {
+    match value {
+        1..=CPU_VALUE_MAX => Ok(value as u16),
+        _ => Err(format!("cpu value {value} out of range 1-{CPU_VALUE_MAX}")),
+    }
+}
+
+/// Parses a CPU value given as string, which is either a decimal or `0o`
+/// prefixed octal integer, or a percentage which is multiplied by 100.
+fn parse_cpu_value(s: &str) -> Result<u16, String> {
+    let s = s.trim();
+    let value = if let Some(percent) = s.strip_suffix('%') {
+        let percent: f64 = percent
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid cpu percentage: {s}"))?;
+        if !percent.is_finite() || percent < 0.0 {
+            return Err(format!("invalid cpu percentage: {s}"));
+        }
+        (percent * 100.0).round() as u64
+    } else if let Some(octal) = s.strip_prefix("0o") {
+        u64::from_str_radix(octal, 8).map_err(|_| format!("invalid cpu value: {s}"))?
+    } else {
+        s.parse().map_err(|_| format!("invalid cpu value: {s}"))?
+    };
+    validate_cpu_value(value)
+}
+
+fn deserialize_cpu_value<'de, D>(deserializer: D) -> Result<Option<u16>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum CpuValue {
+        Integer(u64),
+        String(String),
+    }
+
+    let value = match Option::<CpuValue>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(CpuValue::Integer(value)) => validate_cpu_value(value),
+        Some(CpuValue::String(s)) => parse_cpu_value(&s),
+    };
+    value.map(Some).map_err(de::Error::custom)
+}
+
 #[derive(
     Builder, Clone, Copy, Debug, Default, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -194,6 +630,7 @@ pub struct WindowsStorageResources {
 #[derive(
     Builder, Clone, Debug, Default, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -211,9 +648,16 @@ pub struct WindowsHyperV {
     utility_vm_path: Option<String>,
 }
 
+option_getters! {
+    WindowsHyperV {
+        utility_vm_path_ref => utility_vm_path: str,
+    }
+}
+
 #[derive(
     Builder, Clone, Debug, Default, Deserialize, Eq, Getters, Setters, PartialEq, Serialize,
 )]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[builder(
     default,
@@ -258,3 +702,123 @@ pub struct WindowsNetwork {
     /// container.
     network_namespace: Option<String>,
 }
+
+option_getters! {
+    WindowsNetwork {
+        endpoint_list_ref => endpoint_list: [String],
+        dns_search_list_ref => dns_search_list: [String],
+        network_shared_container_name_ref => network_shared_container_name: str,
+        network_namespace_ref => network_namespace: str,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_device() {
+        let device = WindowsDeviceBuilder::default()
+            .id("{5B45201D-F2F2-4F3B-85BB-30FF1F953599}")
+            .id_type("class")
+            .build()
+            .unwrap();
+        assert_eq!(device.id_type(), &WindowsDeviceIdType::Class);
+        let json = serde_json::to_string(&device).unwrap();
+        assert_eq!(
+            json,
+            r#"{"id":"{5B45201D-F2F2-4F3B-85BB-30FF1F953599}","idType":"class"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<WindowsDevice>(&json).unwrap(),
+            device
+        );
+
+        let device: WindowsDevice =
+            serde_json::from_str(r#"{"id":"PCIP\\VEN_1234","idType":"custom"}"#).unwrap();
+        assert_eq!(
+            device.id_type(),
+            &WindowsDeviceIdType::Other("custom".to_owned())
+        );
+
+        assert!(WindowsDeviceBuilder::default()
+            .id("PCIP\\VEN_1234")
+            .id_type(WindowsDeviceIdType::VpciInstanceId)
+            .build()
+            .is_ok());
+        assert!(WindowsDeviceBuilder::default()
+            .id_type("gpu")
+            .build()
+            .is_err());
+        assert!(WindowsDeviceBuilder::default()
+            .id("not-a-guid")
+            .id_type("class")
+            .build()
+            .is_err());
+        assert!(WindowsDeviceBuilder::default()
+            .id("x")
+            .id_type("")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn credential_spec() {
+        let json = serde_json::json!({
+            "CmsPlugins": ["ActiveDirectory"],
+            "DomainJoinConfig": {
+                "Sid": "S-1-5-21-702590844-1001920913-2680819671",
+                "MachineAccountName": "WebApp01",
+                "Guid": "56d9b66c-d746-4f87-bd26-26760cfdca2e",
+                "DnsTreeName": "contoso.com",
+                "DnsName": "contoso.com",
+                "NetBiosName": "CONTOSO"
+            },
+            "ActiveDirectoryConfig": {
+                "GroupManagedServiceAccounts": [
+                    {"Name": "WebApp01", "Scope": "contoso.com"},
+                    {"Name": "WebApp01", "Scope": "CONTOSO"}
+                ],
+                "HostAccountConfig": {
+                    "PortableCcgVersion": "1",
+                    "PluginGUID": "{859E1386-BDB4-49E8-85C7-3070B13920E1}",
+                    "PluginInput": "contoso.com:gmsaccg:<password>"
+                }
+            },
+            "Unknown": {"Key": null}
+        });
+        let mut windows: Windows =
+            serde_json::from_value(serde_json::json!({ "credentialSpec": json })).unwrap();
+
+        let spec = windows.typed_credential_spec().unwrap().unwrap();
+        assert_eq!(
+            spec.domain_join_config()
+                .as_ref()
+                .unwrap()
+                .net_bios_name()
+                .as_deref(),
+            Some("CONTOSO")
+        );
+        let ad = spec.active_directory_config().as_ref().unwrap();
+        assert_eq!(
+            ad.group_managed_service_accounts().as_ref().unwrap().len(),
+            2
+        );
+        assert_eq!(
+            ad.host_account_config().as_ref().unwrap().plugin_guid(),
+            "{859E1386-BDB4-49E8-85C7-3070B13920E1}"
+        );
+        assert!(spec.other().contains_key("Unknown"));
+
+        let before = windows.clone();
+        windows.set_typed_credential_spec(Some(spec)).unwrap();
+        assert_eq!(windows, before);
+        assert_eq!(
+            serde_json::to_value(windows.credential_spec()).unwrap(),
+            json
+        );
+
+        windows.set_typed_credential_spec(None).unwrap();
+        assert_eq!(windows.typed_credential_spec().unwrap(), None);
+    }
+}
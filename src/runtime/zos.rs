@@ -0,0 +1,202 @@
+use crate::error::OciSpecError;
+use derive_builder::Builder;
+use getset::{CopyGetters, Getters, Setters};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use strum_macros::{Display as StrumDisplay, EnumString};
+
+use super::LinuxDeviceType;
+
+#[derive(
+    Builder, Clone, Debug, Default, Deserialize, Getters, Setters, Eq, PartialEq, Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+#[getset(get = "pub", set = "pub")]
+/// ZOS contains platform-specific configuration for z/OS based containers.
+pub struct ZOS {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Devices are a list of device nodes that are created for the
+    /// container.
+    devices: Option<Vec<ZOSDevice>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Namespaces contains the namespaces that are created and/or joined by
+    /// the container.
+    namespaces: Option<Vec<ZOSNamespace>>,
+}
+
+option_getters! {
+    ZOS {
+        devices_ref => devices: [ZOSDevice],
+        namespaces_ref => namespaces: [ZOSNamespace],
+    }
+}
+
+#[derive(
+    Builder,
+    Clone,
+    CopyGetters,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Getters,
+    Setters,
+    PartialEq,
+    Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+/// ZOSDevice represents the mknod information for a z/OS special device
+/// file.
+pub struct ZOSDevice {
+    #[getset(get = "pub", set = "pub")]
+    /// Path to the device.
+    path: PathBuf,
+
+    #[serde(rename = "type")]
+    #[getset(get_copy = "pub", set = "pub")]
+    /// Device type, block, char, etc..
+    typ: LinuxDeviceType,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    /// Major is the device's major number.
+    major: i64,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    /// Minor is the device's minor number.
+    minor: i64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    /// FileMode permission bits for the device.
+    file_mode: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    /// UID of the device.
+    uid: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    /// Gid of the device.
+    gid: Option<u32>,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    PartialEq,
+    Serialize,
+    Hash,
+    StrumDisplay,
+    EnumString,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+/// Available z/OS namespaces.
+#[non_exhaustive]
+pub enum ZOSNamespaceType {
+    /// Mount namespace for isolation of mountpoints.
+    #[default]
+    Mount,
+    /// PID namespace for isolation of process ids.
+    Pid,
+    /// Network namespace for isolation of network devices and stacks.
+    Network,
+    /// IPC namespace for isolation of System V IPC objects.
+    Ipc,
+    /// UTS namespace for isolation of hostname and domain name.
+    Uts,
+}
+
+#[derive(
+    Builder,
+    Clone,
+    CopyGetters,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Getters,
+    Setters,
+    PartialEq,
+    Serialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[builder(
+    default,
+    pattern = "owned",
+    setter(into, strip_option),
+    build_fn(error = "OciSpecError")
+)]
+/// ZOSNamespace is the configuration for a z/OS namespace.
+pub struct ZOSNamespace {
+    #[serde(rename = "type")]
+    #[getset(get_copy = "pub", set = "pub")]
+    /// Type is the type of namespace.
+    typ: ZOSNamespaceType,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    /// Path is a path to an existing namespace persisted on disk that can
+    /// be joined and is of the same type.
+    path: Option<PathBuf>,
+}
+
+option_getters! {
+    ZOSNamespace {
+        path_ref => path: Path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::Spec;
+
+    #[test]
+    fn zos_section() {
+        let spec: Spec = serde_json::from_str(
+            r#"{
+                "ociVersion": "1.2.0",
+                "zos": {
+                    "devices": [{"path": "/dev/tty", "type": "c", "major": 4, "minor": 0, "fileMode": 438}],
+                    "namespaces": [{"type": "pid"}, {"type": "mount", "path": "/proc/1/ns/mnt"}]
+                }
+            }"#,
+        )
+        .unwrap();
+        let zos = spec.zos().as_ref().unwrap();
+        let device = &zos.devices_ref().unwrap()[0];
+        assert_eq!(device.typ(), LinuxDeviceType::C);
+        assert_eq!(device.file_mode(), Some(438));
+        let namespaces = zos.namespaces_ref().unwrap();
+        assert_eq!(namespaces[0].typ(), ZOSNamespaceType::Pid);
+        assert_eq!(namespaces[1].path_ref(), Some(Path::new("/proc/1/ns/mnt")));
+        assert!(spec
+            .extension_platform::<serde_json::Value>("zos")
+            .unwrap()
+            .is_none());
+
+        let json = serde_json::to_value(&spec).unwrap();
+        assert_eq!(json["zos"]["namespaces"][0]["type"], "pid");
+    }
+}
@@ -0,0 +1,170 @@
+//! Validation of raw JSON documents against the JSON schemas of the
+//! [OCI image spec](https://github.com/opencontainers/image-spec) and the
+//! [OCI runtime spec](https://github.com/opencontainers/runtime-spec).
+//!
+//! Typed parsing cannot express many of the constraints of the
+//! specifications, for example value ranges or string patterns. The functions
+//! of this module report every violation of the schema together with the
+//! path of the offending value. The bundled schemas follow the ones published
+//! alongside the specifications, merged into self-contained documents.
+
+use std::{fmt::Display, sync::OnceLock};
+
+use getset::Getters;
+use jsonschema::{Resource, Validator};
+use serde_json::Value;
+
+use crate::error::{oci_error, Result};
+
+const IMAGE_DEFS_URI: &str = "https://opencontainers.org/schema/image/defs.json";
+const IMAGE_DEFS: &str = include_str!("defs-image.json");
+const IMAGE_MANIFEST: &str = include_str!("image-manifest.json");
+const IMAGE_INDEX: &str = include_str!("image-index.json");
+const IMAGE_CONFIG: &str = include_str!("image-config.json");
+const RUNTIME_CONFIG: &str = include_str!("runtime-config.json");
+
+/// A single violation of a JSON schema.
+#[derive(Clone, Debug, Eq, Getters, PartialEq)]
+#[getset(get = "pub")]
+pub struct SchemaViolation {
+    /// JSON pointer to the value which violates the schema, e.g.
+    /// `/process/user/uid`. The pointer is empty for the document root.
+    path: String,
+    /// JSON pointer to the schema keyword which was violated.
+    schema_path: String,
+    /// Human readable description of the violation.
+    message: String,
+}
+
+impl Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{path}: {}", self.message)
+    }
+}
+
+/// Validates `json` against the runtime spec `config.json` schema.
+/// # Errors
+/// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe)
+/// if `json` is not valid JSON.
+/// # Example
+/// ```
+/// use oci_spec::schema::validate_runtime_config;
+///
+/// let violations = validate_runtime_config(r#"{"ociVersion": 1}"#).unwrap();
+/// assert_eq!(violations[0].path(), "/ociVersion");
+/// ```
+pub fn validate_runtime_config(json: &str) -> Result<Vec<SchemaViolation>> {
+    static VALIDATOR: OnceLock<Validator> = OnceLock::new();
+    validate(&VALIDATOR, RUNTIME_CONFIG, json)
+}
+
+/// Validates `json` against the image manifest schema.
+/// # Errors
+/// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe)
+/// if `json` is not valid JSON.
+pub fn validate_image_manifest(json: &str) -> Result<Vec<SchemaViolation>> {
+    static VALIDATOR: OnceLock<Validator> = OnceLock::new();
+    validate(&VALIDATOR, IMAGE_MANIFEST, json)
+}
+
+/// Validates `json` against the image index schema.
+/// # Errors
+/// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe)
+/// if `json` is not valid JSON.
+pub fn validate_image_index(json: &str) -> Result<Vec<SchemaViolation>> {
+    static VALIDATOR: OnceLock<Validator> = OnceLock::new();
+    validate(&VALIDATOR, IMAGE_INDEX, json)
+}
+
+/// Validates `json` against the image configuration schema.
+/// # Errors
+/// This function will return an [OciSpecError::SerDe](crate::OciSpecError::SerDe)
+/// if `json` is not valid JSON.
+pub fn validate_image_config(json: &str) -> Result<Vec<SchemaViolation>> {
+    static VALIDATOR: OnceLock<Validator> = OnceLock::new();
+    validate(&VALIDATOR, IMAGE_CONFIG, json)
+}
+
+fn validate(
+    validator: &OnceLock<Validator>,
+    schema: &str,
+    json: &str,
+) -> Result<Vec<SchemaViolation>> {
+    let instance: Value = serde_json::from_str(json)?;
+    let validator = match validator.get() {
+        Some(validator) => validator,
+        None => {
+            let compiled = compile(schema)?;
+            validator.get_or_init(|| compiled)
+        }
+    };
+
+    Ok(validator
+        .iter_errors(&instance)
+        .map(|e| SchemaViolation {
+            path: e.instance_path.to_string(),
+            schema_path: e.schema_path.to_string(),
+            message: e.to_string(),
+        })
+        .collect())
+}
+
+fn compile(schema: &str) -> Result<Validator> {
+    let schema: Value = serde_json::from_str(schema)?;
+    let defs = Resource::from_contents(serde_json::from_str(IMAGE_DEFS)?)
+        .map_err(|e| oci_error(format!("invalid schema definitions: {e}")))?;
+    jsonschema::options()
+        .with_resource(IMAGE_DEFS_URI, defs)
+        .build(&schema)
+        .map_err(|e| oci_error(format!("invalid schema: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = include_str!("../../test/data/manifest.json");
+    const INDEX: &str = include_str!("../../test/data/index.json");
+    const CONFIG: &str = include_str!("../../test/data/config.json");
+    const RUNTIME: &str = include_str!("../runtime/test/fixture/sample.json");
+
+    #[test]
+    fn valid_documents() {
+        assert_eq!(validate_image_manifest(MANIFEST).unwrap(), vec![]);
+        assert_eq!(validate_image_index(INDEX).unwrap(), vec![]);
+        assert_eq!(validate_image_config(CONFIG).unwrap(), vec![]);
+        assert_eq!(validate_runtime_config(RUNTIME).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn invalid_manifest() {
+        let mut manifest: Value = serde_json::from_str(MANIFEST).unwrap();
+        manifest["schemaVersion"] = 1.into();
+        manifest["layers"][2]["digest"] = "sha256".into();
+
+        let violations = validate_image_manifest(&manifest.to_string()).unwrap();
+        let paths: Vec<_> = violations.iter().map(|v| v.path().as_str()).collect();
+        assert_eq!(paths, ["/schemaVersion", "/layers/2/digest"]);
+    }
+
+    #[test]
+    fn invalid_runtime_config() {
+        let violations =
+            validate_runtime_config(r#"{"ociVersion": "1.0.2", "process": {"user": {"uid": -1}}}"#)
+                .unwrap();
+        let paths: Vec<_> = violations.iter().map(|v| v.path().as_str()).collect();
+        assert_eq!(paths.len(), 2);
+        assert!(paths.contains(&"/process"));
+        assert!(paths.contains(&"/process/user/uid"));
+    }
+
+    #[test]
+    fn invalid_json() {
+        assert!(validate_runtime_config("{").is_err());
+    }
+}
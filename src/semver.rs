@@ -0,0 +1,153 @@
+//! Parsing and precedence of [SemVer 2.0](https://semver.org) versions, shared
+//! by the `ociVersion` of the runtime spec and the tags of the distribution
+//! spec.
+
+use std::cmp::Ordering;
+
+/// A SemVer version borrowing its pre-release identifiers and build metadata
+/// from the parsed string.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct SemVer<'a> {
+    pub(crate) major: u64,
+    pub(crate) minor: u64,
+    pub(crate) patch: u64,
+    pub(crate) pre: Option<&'a str>,
+    pub(crate) build: Option<&'a str>,
+}
+
+/// Returns true if `s` is a non-negative number without leading zeros.
+fn is_numeric_identifier(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) && (s == "0" || !s.starts_with('0'))
+}
+
+fn is_identifier(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+}
+
+impl<'a> SemVer<'a> {
+    /// Parses `s` following the SemVer 2.0 grammar, returning `None` if it is
+    /// not a valid version.
+    pub(crate) fn parse(s: &'a str) -> Option<Self> {
+        let (rest, build) = match s.split_once('+') {
+            Some((rest, build)) => (rest, Some(build)),
+            None => (s, None),
+        };
+        let (core, pre) = match rest.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (rest, None),
+        };
+
+        let mut parts = core.split('.').map(|part| {
+            is_numeric_identifier(part)
+                .then(|| part.parse::<u64>().ok())
+                .flatten()
+        });
+        let (Some(Some(major)), Some(Some(minor)), Some(Some(patch)), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+
+        if pre.is_some_and(|pre| {
+            !pre.split('.').all(|id| {
+                is_identifier(id)
+                    && (!id.bytes().all(|b| b.is_ascii_digit()) || is_numeric_identifier(id))
+            })
+        }) {
+            return None;
+        }
+        if build.is_some_and(|build| !build.split('.').all(is_identifier)) {
+            return None;
+        }
+
+        Some(Self {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+    }
+
+    /// Compares two versions by [precedence](https://semver.org/#spec-item-11),
+    /// which ignores build metadata.
+    pub(crate) fn cmp_precedence(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre, other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => cmp_pre_release(a, b),
+            })
+    }
+}
+
+/// Compares dot separated pre-release identifiers by SemVer precedence.
+fn cmp_pre_release(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn semver_precedence() {
+        let versions = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "2.0.0",
+            "2.1.0",
+            "2.1.1",
+        ];
+        for pair in versions.windows(2) {
+            let a = SemVer::parse(pair[0]).unwrap();
+            let b = SemVer::parse(pair[1]).unwrap();
+            assert_eq!(
+                a.cmp_precedence(&b),
+                Ordering::Less,
+                "{} < {}",
+                pair[0],
+                pair[1]
+            );
+        }
+
+        let build = SemVer::parse("1.2.3+build.5").unwrap();
+        assert_eq!(build.build, Some("build.5"));
+        assert_eq!(
+            build.cmp_precedence(&SemVer::parse("1.2.3").unwrap()),
+            Ordering::Equal
+        );
+
+        for invalid in [
+            "", "latest", "1.2", "1.2.3.4", "1.2.x", "01.2.3", "1.2.3-", "1.2.3-01", "1.2.3+",
+            "v1.2.3", "1..3",
+        ] {
+            assert_eq!(SemVer::parse(invalid), None, "{invalid}");
+        }
+    }
+}